@@ -54,9 +54,19 @@ async function main() {
         process.exit(1);
     }
 
+    // Token with non-default decimals; it's not added to the governance, so tests can register it when needed.
+    const erc20SixDecimals = await deployContract(
+        deployWallet,
+        readContractCode('dev-contracts/TestnetERC20Token'),
+        ['Matter Labs Trial Dollar', 'MLTD', 6],
+        { gasLimit: 5000000 }
+    );
+    console.log(`CONTRACTS_TEST_ERC20_6_DECIMALS=${erc20SixDecimals.address}`);
+
     for (let i = 0; i < 10; ++i) {
         const testWallet = Wallet.fromMnemonic(ethTestConfig.test_mnemonic, "m/44'/60'/0'/0/" + i).connect(provider);
         await (await erc20.mint(testWallet.address, '0x4B3B4CA85A86C47A098A224000000000')).wait();
+        await (await erc20SixDecimals.mint(testWallet.address, '0x4B3B4CA85A86C47A098A224000000000')).wait();
     }
     const pendingWithdrawer = await deployContract(
         deployWallet,
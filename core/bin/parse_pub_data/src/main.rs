@@ -1,22 +1,24 @@
-use zksync_types::ZkSyncOp;
+use num::BigUint;
+use zksync_types::operations::parse_block_pubdata;
+
+/// Default maximum plausible fee: 10^12 tokens with 18 decimals.
+const DEFAULT_MAX_FEE: &str = "1000000000000000000000000000000";
 
 fn main() {
-    let hex_data = std::env::args()
-        .nth(1)
-        .expect("cli arg should be hex of pubdata");
+    let mut args = std::env::args().skip(1);
+    let hex_data = args.next().expect("cli arg should be hex of pubdata");
+    let max_fee: BigUint = args
+        .next()
+        .as_deref()
+        .unwrap_or(DEFAULT_MAX_FEE)
+        .parse()
+        .expect("second cli arg should be the maximum plausible fee");
     let data = hex::decode(&hex_data).expect("failed to decode hex");
 
-    let mut unparsed_data = data.as_slice();
-    while !unparsed_data.is_empty() {
-        let op_type = unparsed_data[0];
-        let op_data_len = ZkSyncOp::public_data_length(op_type).expect("wrong op type");
-        assert!(
-            data.len() > op_data_len,
-            "not enough bytes in the pubdata for current op"
-        );
-        let (current_op, unparsed) = unparsed_data.split_at(op_data_len);
-        let op = ZkSyncOp::from_public_data(current_op).expect("failed to parse pubdata");
+    for op in parse_block_pubdata(&data).expect("failed to parse pubdata") {
         println!("{:#?}", op);
-        unparsed_data = unparsed;
+        if let Err(err) = op.validate_fee(&max_fee) {
+            println!("WARNING: implausible operation fee: {}", err);
+        }
     }
 }
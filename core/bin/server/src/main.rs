@@ -1,6 +1,7 @@
 use futures::{channel::mpsc, executor::block_on, SinkExt, StreamExt};
 use std::cell::RefCell;
 use std::str::FromStr;
+use std::time::Duration;
 
 use structopt::StructOpt;
 
@@ -27,6 +28,12 @@ use zksync_storage::ConnectionPool;
 
 const DEFAULT_CHANNEL_CAPACITY: usize = 32_768;
 
+/// Interval between the reports of the database connection pools state.
+const POOL_STATS_REPORT_INTERVAL: Duration = Duration::from_secs(10);
+/// Interval between the summaries of the storage query timings.
+#[cfg(feature = "storage_timing")]
+const STORAGE_TIMING_REPORT_INTERVAL: Duration = Duration::from_secs(60);
+
 #[derive(Debug, Clone, Copy)]
 pub enum ServerCommand {
     Genesis,
@@ -155,7 +162,14 @@ async fn run_server(components: &ComponentsToRun) {
     let read_only_connection_pool = ConnectionPool::new_readonly_pool(None);
     let (stop_signal_sender, mut stop_signal_receiver) = mpsc::channel(256);
 
-    let mut tasks = vec![];
+    let mut tasks = vec![
+        connection_pool.run_stats_reporter("master", POOL_STATS_REPORT_INTERVAL),
+        read_only_connection_pool.run_stats_reporter("replica", POOL_STATS_REPORT_INTERVAL),
+    ];
+    #[cfg(feature = "storage_timing")]
+    tasks.push(zksync_storage::timing::run_timing_reporter(
+        STORAGE_TIMING_REPORT_INTERVAL,
+    ));
 
     if components.0.contains(&Component::Web3Api) {
         // Run web3 api
@@ -194,7 +208,7 @@ async fn run_server(components: &ComponentsToRun) {
         // Run signer
         let (sign_check_sender, sign_check_receiver) = mpsc::channel(DEFAULT_CHANNEL_CAPACITY);
         tasks.push(zksync_api::signature_checker::start_sign_checker(
-            eth_gateway,
+            eth_gateway.clone(),
             sign_check_receiver,
         ));
 
@@ -230,6 +244,8 @@ async fn run_server(components: &ComponentsToRun) {
                 chain_config.state_keeper.miniblock_iteration_interval(),
                 mempool_tx_request_sender,
                 eth_watch_config.confirmations_for_eth_event,
+                chain_config.state_keeper.block_chunk_sizes.clone(),
+                eth_gateway.clone(),
             ));
         }
 
@@ -250,6 +266,8 @@ async fn run_server(components: &ComponentsToRun) {
                 &token_config,
                 mempool_tx_request_sender,
                 eth_watch_config.confirmations_for_eth_event,
+                chain_config.state_keeper.block_chunk_sizes.clone(),
+                eth_gateway,
             ));
         }
 
@@ -1,4 +1,6 @@
-use super::rpc_server::types::{ETHOpInfoResp, ResponseAccountState, TransactionInfoResp};
+use super::rpc_server::types::{
+    BlockInfoResp, ETHOpInfoResp, ResponseAccountState, TransactionInfoResp,
+};
 use futures::{channel::mpsc, select, stream::StreamExt};
 use jsonrpc_pubsub::{
     typed::{Sink, Subscriber},
@@ -42,6 +44,11 @@ pub enum EventSubscribeRequest {
         action: ActionType,
         subscriber: Subscriber<ResponseAccountState>,
     },
+    /// Subscription to every new block. `None` action means both committed and verified blocks.
+    Block {
+        action: Option<ActionType>,
+        subscriber: Subscriber<BlockInfoResp>,
+    },
 }
 
 pub enum EventNotifierRequest {
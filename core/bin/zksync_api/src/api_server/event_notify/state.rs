@@ -8,11 +8,11 @@ use zksync_token_db_cache::TokenDBCache;
 use zksync_types::aggregated_operations::AggregatedActionType;
 use zksync_types::tx::TxHash;
 use zksync_types::BlockNumber;
-use zksync_types::{AccountId, ActionType, Address};
+use zksync_types::{AccountId, ActionType, Address, PriorityOpId};
 
 pub struct NotifierState {
     pub(super) cache_of_executed_priority_operations:
-        LruCache<u32, StoredExecutedPriorityOperation>,
+        LruCache<PriorityOpId, StoredExecutedPriorityOperation>,
     pub(super) cache_of_transaction_receipts: LruCache<Vec<u8>, TxReceiptResponse>,
     pub(super) cache_of_blocks_info: LruCache<BlockNumber, BlockInfo>,
     pub(super) tokens_cache: TokenDBCache,
@@ -125,7 +125,7 @@ impl NotifierState {
 
     pub async fn get_executed_priority_operation(
         &mut self,
-        serial_id: u32,
+        serial_id: PriorityOpId,
     ) -> Result<Option<StoredExecutedPriorityOperation>, anyhow::Error> {
         let start = Instant::now();
         let res = if let Some(executed_op) = self
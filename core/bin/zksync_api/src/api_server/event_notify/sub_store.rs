@@ -1,5 +1,6 @@
 //! Storage for subscription objects.
 use super::SubscriptionSender;
+use crate::api_server::rpc_server::types::BlockInfoResp;
 use std::{cmp::Ord, collections::BTreeMap, str::FromStr};
 use zksync_types::{tx::TxHash, AccountId, ActionType, PriorityOpId};
 
@@ -12,6 +13,9 @@ const MAX_LISTENERS_PER_ENTITY: usize = 2048;
 const TX_SUB_PREFIX: &str = "txsub";
 const ETHOP_SUB_PREFIX: &str = "eosub";
 const ACCOUNT_SUB_PREFIX: &str = "acsub";
+const BLOCK_SUB_PREFIX: &str = "blsub";
+/// Maximum number of the block subscriptions.
+const MAX_BLOCK_LISTENERS: usize = 2048;
 
 pub trait ActionId {
     fn sub_type() -> &'static str;
@@ -158,3 +162,73 @@ where
         Ok(())
     }
 }
+
+/// Storage for the block subscriptions.
+///
+/// Unlike `SubStorage`, which removes the subscribers once they are notified,
+/// block subscribers receive every new block until they unsubscribe.
+#[derive(Debug, Default)]
+pub struct BlockSubStorage {
+    /// Subscribers along with the action they are interested in (`None` stands for both actions).
+    subs: Vec<(Option<ActionType>, SubscriptionSender<BlockInfoResp>)>,
+}
+
+impl BlockSubStorage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn generate_sub_id(&self, action_type: Option<ActionType>) -> SubscriptionId {
+        let action_type = action_type
+            .map(|action_type| action_type.to_string())
+            .unwrap_or_else(|| "ALL".to_string());
+        SubscriptionId::String(format!(
+            "{}/{}/{}",
+            BLOCK_SUB_PREFIX,
+            action_type,
+            zksync_crypto::rand::random::<u64>()
+        ))
+    }
+
+    pub fn insert_new(
+        &mut self,
+        sub_id: SubscriptionId,
+        sub: Subscriber<BlockInfoResp>,
+        action_type: Option<ActionType>,
+    ) -> anyhow::Result<()> {
+        if self.subs.len() >= MAX_BLOCK_LISTENERS {
+            sub.reject(jsonrpc_core::Error::invalid_request())
+                .map_err(|_| anyhow::format_err!("SubReject"))?;
+            return Ok(());
+        }
+        let sink = sub
+            .assign_id(sub_id.clone())
+            .map_err(|_| anyhow::format_err!("SubIdAssign"))?;
+        self.subs
+            .push((action_type, SubscriptionSender { id: sub_id, sink }));
+
+        Ok(())
+    }
+
+    pub fn remove(&mut self, sub_id: SubscriptionId) -> anyhow::Result<()> {
+        self.subs.retain(|(_, sub)| sub.id != sub_id);
+        Ok(())
+    }
+
+    /// Sends the block to all the subscribers interested in the action.
+    /// Subscribers which can't be notified (e.g. due to closed connection) are removed.
+    pub fn notify(&mut self, event: BlockInfoResp) {
+        self.subs.retain(|(action_type, sub)| {
+            if action_type.map_or(false, |action_type| action_type != event.action) {
+                return true;
+            }
+            match sub.sink.notify(Ok(event.clone())) {
+                Ok(_) => true,
+                Err(e) => {
+                    vlog::warn!("{}", e.to_string());
+                    false
+                }
+            }
+        });
+    }
+}
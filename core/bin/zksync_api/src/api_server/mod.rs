@@ -15,3 +15,5 @@ pub mod web3;
 
 /// Amount of threads used by each server to serve requests.
 const THREADS_PER_SERVER: usize = 128;
+/// Tag of the database connections held by the API servers.
+const API_CONNECTION_TAG: &str = "api";
@@ -100,6 +100,9 @@ impl SumbitErrorCode {
             SubmitError::UnsupportedFastProcessing => Self::UnsupportedFastProcessing,
             SubmitError::IncorrectTx(_) => Self::IncorrectTx,
             SubmitError::TxAdd(_) => Self::TxAdd,
+            SubmitError::BatchTooBig { .. } => Self::TxAdd,
+            SubmitError::BatchDoesNotFitBlock { .. } => Self::TxAdd,
+            SubmitError::TxFeeTooLow { .. } => Self::TxAdd,
             SubmitError::InappropriateFeeToken => Self::InappropriateFeeToken,
             SubmitError::MempoolCommunication(_) => Self::CommunicationCoreServer,
             SubmitError::Internal(_) => Self::Internal,
@@ -53,6 +53,7 @@ async fn start_server(
                 &api_v01.config.api.common,
                 &api_v01.config.api.token_config,
                 mempool_tx_sender.clone(),
+                api_v01.config.chain.state_keeper.block_chunk_sizes.clone(),
             );
             v02::api_scope(tx_sender, &api_v01.config, api_v01.network_status.clone())
         };
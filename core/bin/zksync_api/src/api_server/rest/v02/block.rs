@@ -104,7 +104,11 @@ impl ApiBlockData {
         &self,
         query: PaginationQuery<ApiEither<BlockNumber>>,
     ) -> Result<Paginated<BlockInfo, BlockNumber>, Error> {
-        let mut storage = self.pool.access_storage().await.map_err(Error::storage)?;
+        let mut storage = self
+            .pool
+            .access_storage_read_only()
+            .await
+            .map_err(Error::storage)?;
         storage.paginate_checked(&query).await
     }
 
@@ -113,7 +117,11 @@ impl ApiBlockData {
         block_number: BlockNumber,
         query: PaginationQuery<ApiEither<TxHash>>,
     ) -> Result<Paginated<Transaction, TxHashSerializeWrapper>, Error> {
-        let mut storage = self.pool.access_storage().await.map_err(Error::storage)?;
+        let mut storage = self
+            .pool
+            .access_storage_read_only()
+            .await
+            .map_err(Error::storage)?;
 
         let new_query = PaginationQuery {
             from: BlockAndTxHash {
@@ -132,7 +140,11 @@ impl ApiBlockData {
         block_number: BlockNumber,
         block_index: u64,
     ) -> Result<Option<TxData>, Error> {
-        let mut storage = self.pool.access_storage().await.map_err(Error::storage)?;
+        let mut storage = self
+            .pool
+            .access_storage_read_only()
+            .await
+            .map_err(Error::storage)?;
         Ok(storage
             .chain()
             .operations_ext_schema()
@@ -142,7 +154,7 @@ impl ApiBlockData {
     }
 
     async fn get_last_committed_block_number(&self) -> QueryResult<BlockNumber> {
-        let mut storage = self.pool.access_storage().await?;
+        let mut storage = self.pool.access_storage_read_only().await?;
         storage
             .chain()
             .block_schema()
@@ -151,7 +163,7 @@ impl ApiBlockData {
     }
 
     async fn get_last_finalized_block_number(&self) -> QueryResult<BlockNumber> {
-        let mut storage = self.pool.access_storage().await?;
+        let mut storage = self.pool.access_storage_read_only().await?;
         storage
             .chain()
             .block_schema()
@@ -49,6 +49,9 @@ pub struct Error {
     pub error_type: String,
     pub code: ErrorCode,
     pub message: String,
+    /// Optional details of the error, e.g. the provided and required fee of the underpriced transaction.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<serde_json::Value>,
 }
 
 /// Trait that can be used to map custom errors to the object.
@@ -60,6 +63,10 @@ pub trait ApiError: std::fmt::Display {
     fn message(&self) -> String {
         self.to_string()
     }
+
+    fn data(&self) -> Option<serde_json::Value> {
+        None
+    }
 }
 
 impl<T> From<T> for Error
@@ -71,6 +78,7 @@ where
             error_type: t.error_type(),
             code: t.code(),
             message: t.message(),
+            data: t.data(),
         }
     }
 }
@@ -196,6 +204,9 @@ impl ApiError for SubmitError {
             Self::UnsupportedFastProcessing => ErrorCode::UnsupportedFastProcessing,
             Self::IncorrectTx(_) => ErrorCode::IncorrectTx,
             Self::TxAdd(_) => ErrorCode::TxAddError,
+            Self::BatchTooBig { .. } => ErrorCode::TxAddError,
+            Self::BatchDoesNotFitBlock { .. } => ErrorCode::TxAddError,
+            Self::TxFeeTooLow { .. } => ErrorCode::TxAddError,
             Self::InappropriateFeeToken => ErrorCode::InappropriateFeeToken,
             Self::MempoolCommunication(_) => ErrorCode::CommunicationCoreServer,
             Self::Internal(_) => ErrorCode::InternalError,
@@ -204,6 +215,16 @@ impl ApiError for SubmitError {
             Self::PriceError(_) => ErrorCode::InternalError,
         }
     }
+
+    fn data(&self) -> Option<serde_json::Value> {
+        match self {
+            Self::TxFeeTooLow { provided, required } => Some(serde_json::json!({
+                "provided": provided.to_string(),
+                "required": required.to_string(),
+            })),
+            _ => None,
+        }
+    }
 }
 
 impl ApiError for PriceError {
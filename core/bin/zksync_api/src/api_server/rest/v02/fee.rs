@@ -172,6 +172,7 @@ mod tests {
                     &cfg.config.api.common,
                     &cfg.config.api.token_config,
                     mempool_tx_request_sender.clone(),
+                    cfg.config.chain.state_keeper.block_chunk_sizes.clone(),
                 ))
             },
             Some(shared_data),
@@ -17,7 +17,10 @@ use zksync_api_types::{
     },
     TxWithSignature,
 };
-use zksync_types::{tx::TxHash, EthBlockId};
+use zksync_types::{
+    tx::{error::TxAddError, TxHash},
+    EthBlockId,
+};
 
 // Local uses
 use super::{error::Error, response::ApiResult};
@@ -174,6 +177,7 @@ async fn submit_tx(
         let err_label = match err {
             SubmitError::IncorrectTx(err) => err.clone(),
             SubmitError::TxAdd(err) => err.to_string(),
+            SubmitError::TxFeeTooLow { .. } => TxAddError::TxFeeTooLow.to_string(),
             _ => "other".to_string(),
         };
         let labels = vec![("stage", "api".to_string()), ("error", err_label)];
@@ -199,6 +203,7 @@ async fn submit_batch(
         let err_label = match err {
             SubmitError::IncorrectTx(err) => err.clone(),
             SubmitError::TxAdd(err) => err.to_string(),
+            SubmitError::TxFeeTooLow { .. } => TxAddError::TxFeeTooLow.to_string(),
             _ => "other".to_string(),
         };
         let labels = vec![("stage", "api".to_string()), ("error", err_label)];
@@ -252,6 +257,7 @@ pub fn api_scope(tx_sender: TxSender) -> Scope {
 mod tests {
     use super::*;
     use crate::api_server::rest::v02::{
+        error::ErrorCode,
         test_utils::{
             deserialize_response_result, dummy_fee_ticker, dummy_sign_verifier, TestServerConfig,
             TestTransactions,
@@ -276,7 +282,7 @@ mod tests {
             EthBatchSignData, EthBatchSignatures, PackedEthSignature, TxEthSignature,
             TxEthSignatureVariant,
         },
-        Address, BlockNumber, SignedZkSyncTx, TokenId, TokenKind, TokenLike,
+        Address, BlockNumber, SignedZkSyncTx, TokenId, TokenKind, TokenLike, ZkSyncTx,
     };
 
     fn submit_txs_loopback() -> (mpsc::Sender<MempoolTransactionRequest>, JoinHandle<()>) {
@@ -353,6 +359,7 @@ mod tests {
                     &cfg.config.api.common,
                     &cfg.config.api.token_config,
                     sender.clone(),
+                    cfg.config.chain.state_keeper.block_chunk_sizes.clone(),
                 ))
             },
             Some(shared_data),
@@ -502,4 +509,128 @@ mod tests {
         task.abort();
         Ok(())
     }
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn oversized_batch_is_rejected() -> anyhow::Result<()> {
+        let (sender, mut receiver) = mpsc::channel(100);
+
+        let cfg = TestServerConfig::default();
+        let shared_data = SharedData {
+            net: cfg.config.chain.eth.network,
+            api_version: ApiVersion::V02,
+        };
+        let (client, server) = cfg.start_server(
+            move |cfg: &TestServerConfig| {
+                api_scope(TxSender::new(
+                    cfg.pool.clone(),
+                    dummy_sign_verifier(),
+                    dummy_fee_ticker(&[], None),
+                    &cfg.config.api.common,
+                    &cfg.config.api.token_config,
+                    sender.clone(),
+                    cfg.config.chain.state_keeper.block_chunk_sizes.clone(),
+                ))
+            },
+            Some(shared_data),
+        );
+
+        let limit = cfg.config.api.common.max_number_of_transactions_per_batch as usize;
+        let tx = TxWithSignature {
+            tx: TestServerConfig::gen_zk_txs(100_u64).txs[0].0.clone(),
+            signature: TxEthSignatureVariant::Single(None),
+        };
+        let response = client.submit_batch(vec![tx; limit + 1], None).await?;
+        let error: Error = serde_json::from_value(response.error.expect("batch must be rejected"))?;
+        assert_eq!(error.code, ErrorCode::TxAddError);
+        assert_eq!(
+            error.message,
+            SubmitError::BatchTooBig {
+                limit,
+                submitted: limit + 1
+            }
+            .to_string()
+        );
+
+        server.stop().await;
+        // The batch should be rejected before reaching the mempool, so nothing is persisted.
+        assert!(!matches!(receiver.try_next(), Ok(Some(_))));
+        Ok(())
+    }
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn underpriced_tx_is_rejected() -> anyhow::Result<()> {
+        let (sender, mut receiver) = mpsc::channel(100);
+
+        let cfg = TestServerConfig::default();
+        let shared_data = SharedData {
+            net: cfg.config.chain.eth.network,
+            api_version: ApiVersion::V02,
+        };
+
+        let mut tokens = HashMap::new();
+        tokens.insert(
+            TokenLike::Id(TokenId(0)),
+            Token::new(TokenId(0), Default::default(), "ETH", 18, TokenKind::ERC20),
+        );
+        let mut market = HashMap::new();
+        market.insert(
+            TokenId(0),
+            TokenMarketVolume {
+                market_volume: Ratio::from_integer(BigUint::from(400u32)),
+                last_updated: Utc::now(),
+            },
+        );
+        let cache = TokenInMemoryCache::new()
+            .with_tokens(tokens)
+            .with_market(market);
+        let prices = vec![(TokenLike::Id(TokenId(0)), 10500_u64.into())];
+
+        let (client, server) = cfg.start_server(
+            move |cfg: &TestServerConfig| {
+                api_scope(TxSender::new(
+                    cfg.pool.clone(),
+                    dummy_sign_verifier(),
+                    dummy_fee_ticker(&prices, Some(cache.clone())),
+                    &cfg.config.api.common,
+                    &cfg.config.api.token_config,
+                    sender.clone(),
+                    cfg.config.chain.state_keeper.block_chunk_sizes.clone(),
+                ))
+            },
+            Some(shared_data),
+        );
+
+        // The second generated transaction is a transfer, it pays no fee at all.
+        let tx = TestServerConfig::gen_zk_txs(0_u64).txs[1].0.clone();
+        assert!(matches!(tx, ZkSyncTx::Transfer(_)));
+        let response = client
+            .submit_tx(tx, TxEthSignatureVariant::Single(None))
+            .await?;
+        let error: Error = serde_json::from_value(response.error.expect("tx must be rejected"))?;
+        assert_eq!(error.code, ErrorCode::TxAddError);
+
+        // The error carries both the provided and the required fee.
+        let data = error.data.clone().expect("fee data must be provided");
+        let provided = BigUint::from_str(data["provided"].as_str().expect("provided fee"))?;
+        let required = BigUint::from_str(data["required"].as_str().expect("required fee"))?;
+        assert_eq!(provided, BigUint::from(0u32));
+        assert!(required > provided);
+        assert_eq!(
+            error.message,
+            SubmitError::TxFeeTooLow { provided, required }.to_string()
+        );
+
+        server.stop().await;
+        // The transaction should be rejected before reaching the mempool.
+        assert!(!matches!(receiver.try_next(), Ok(Some(_))));
+        Ok(())
+    }
 }
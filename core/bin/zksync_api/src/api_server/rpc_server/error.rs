@@ -22,6 +22,7 @@ pub enum RpcErrorCodes {
     OperationsLimitReached = 302,
     UnsupportedFastProcessing = 303,
     Toggle2FA = 304,
+    RequestTimeout = 305,
 }
 
 impl From<TxAddError> for RpcErrorCodes {
@@ -72,6 +73,33 @@ impl From<SubmitError> for jsonrpc_core::Error {
                 message,
                 data: None,
             },
+            SubmitError::BatchTooBig { limit, submitted } => Self {
+                code: RpcErrorCodes::Other.into(),
+                message: inner.to_string(),
+                data: Some(serde_json::json!({
+                    "limit": limit,
+                    "submitted": submitted,
+                })),
+            },
+            SubmitError::BatchDoesNotFitBlock {
+                max_block_chunks,
+                required,
+            } => Self {
+                code: RpcErrorCodes::Other.into(),
+                message: inner.to_string(),
+                data: Some(serde_json::json!({
+                    "maxBlockChunks": max_block_chunks,
+                    "required": required,
+                })),
+            },
+            SubmitError::TxFeeTooLow { provided, required } => Self {
+                code: RpcErrorCodes::FeeTooLow.into(),
+                message: inner.to_string(),
+                data: Some(serde_json::json!({
+                    "provided": provided.to_string(),
+                    "required": required.to_string(),
+                })),
+            },
             SubmitError::TxAdd(inner) => Self {
                 code: RpcErrorCodes::from(inner).into(),
                 message: inner.to_string(),
@@ -50,6 +50,7 @@ fn get_call_with_ip_if_needed(
         ("tx_submit", MethodWithIpDescription::new(1, 4)),
         ("submit_txs_batch", MethodWithIpDescription::new(1, 3)),
         ("get_tx_fee", MethodWithIpDescription::new(3, 4)),
+        ("get_tx_fee_for_tx", MethodWithIpDescription::new(2, 3)),
         (
             "get_txs_batch_fee_in_wei",
             MethodWithIpDescription::new(3, 4),
@@ -1,14 +1,20 @@
 // Built-in uses
-use std::time::Instant;
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 // External uses
+use bigdecimal::BigDecimal;
 use futures::channel::mpsc;
 use jsonrpc_core::{Error, IoHandler, MetaIoHandler, Metadata, Middleware, Result};
 use jsonrpc_http_server::ServerBuilder;
-use tokio::task::JoinHandle;
+use tokio::{sync::RwLock, task::JoinHandle};
 
 // Workspace uses
 use zksync_config::configs::api::{CommonApiConfig, JsonRpcConfig, TokenConfig};
+use zksync_eth_client::EthereumGateway;
 use zksync_storage::{
     chain::{
         block::records::StorageBlockDetails, operations::records::StoredExecutedPriorityOperation,
@@ -16,7 +22,7 @@ use zksync_storage::{
     },
     ConnectionPool, StorageProcessor,
 };
-use zksync_types::{tx::TxHash, Address, BlockNumber};
+use zksync_types::{tx::TxHash, Address, BlockNumber, PriorityOpId, Token, TokenLike};
 use zksync_utils::panic_notify::{spawn_panic_handler, ThreadPanicNotify};
 
 // Local uses
@@ -35,26 +41,65 @@ use crate::fee_ticker::FeeTicker;
 use ip_insert_middleware::IpInsertMiddleWare;
 use zksync_mempool::MempoolTransactionRequest;
 
+/// Time during which the cached `status` response is served without reloading.
+const STATUS_CACHE_LIFETIME: Duration = Duration::from_secs(5);
+/// Interval between the checks for the newly committed blocks, whose accounts
+/// have to be removed from the `account_info` cache, and for the newly registered tokens.
+const CACHE_INVALIDATION_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Kind of the RPC method handler, which determines its time limit.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum HandlerKind {
+    /// Handler which only reads the data. It is cancelled once the request timeout expires.
+    Query,
+    /// Handler which submits transactions. It is never cancelled: the transactions could be
+    /// already accepted by the mempool, and the client must receive their hashes.
+    Submit,
+}
+
 #[derive(Clone)]
 pub struct RpcApp {
-    cache_of_executed_priority_operations: AsyncLruCache<u32, StoredExecutedPriorityOperation>,
+    cache_of_executed_priority_operations:
+        AsyncLruCache<PriorityOpId, StoredExecutedPriorityOperation>,
     cache_of_transaction_receipts: AsyncLruCache<Vec<u8>, TxReceiptResponse>,
     cache_of_complete_withdrawal_tx_hashes: AsyncLruCache<TxHash, String>,
+    /// Tokens list served by the `tokens` method along with the moment it was loaded.
+    /// Reloaded from the database once it gets older than `tokens_cache_lifetime`
+    /// or once a new token is registered, see `run_cache_invalidator`.
+    cache_of_tokens: Arc<RwLock<Option<(HashMap<String, Token>, Instant)>>>,
+    tokens_cache_lifetime: Duration,
+    /// Token prices served by the `get_token_price` method along with the moment they were loaded.
+    cache_of_token_prices: AsyncLruCache<TokenLike, (BigDecimal, Instant)>,
+    token_price_cache_lifetime: Duration,
+    cache_of_status: Arc<RwLock<Option<StatusResp>>>,
+    /// `account_info` responses along with the moment they were loaded.
+    /// Entries of the accounts touched by a committed block are removed by
+    /// `run_cache_invalidator`, the rest expire after `account_info_cache_lifetime`.
+    cache_of_account_info: AsyncLruCache<Address, (AccountInfoResp, Instant)>,
+    account_info_cache_lifetime: Duration,
+
+    request_timeout: Duration,
 
     pub confirmations_for_eth_event: u64,
+    /// Used to determine the current Ethereum block for the pending priority operations.
+    eth_client: EthereumGateway,
 
     tx_sender: TxSender,
 }
 
 impl RpcApp {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         connection_pool: ConnectionPool,
         sign_verify_request_sender: mpsc::Sender<VerifySignatureRequest>,
         ticker: FeeTicker,
         config: &CommonApiConfig,
         token_config: &TokenConfig,
+        json_rpc_config: &JsonRpcConfig,
         confirmations_for_eth_event: u64,
         mempool_tx_sender: mpsc::Sender<MempoolTransactionRequest>,
+        block_chunk_sizes: Vec<usize>,
+        eth_client: EthereumGateway,
     ) -> Self {
         let api_requests_caches_size = config.caches_size;
 
@@ -65,14 +110,25 @@ impl RpcApp {
             config,
             token_config,
             mempool_tx_sender,
+            block_chunk_sizes,
         );
 
         RpcApp {
             cache_of_executed_priority_operations: AsyncLruCache::new(api_requests_caches_size),
             cache_of_transaction_receipts: AsyncLruCache::new(api_requests_caches_size),
             cache_of_complete_withdrawal_tx_hashes: AsyncLruCache::new(api_requests_caches_size),
+            cache_of_tokens: Arc::new(RwLock::new(None)),
+            tokens_cache_lifetime: token_config.invalidate_token_cache_period(),
+            cache_of_token_prices: AsyncLruCache::new(api_requests_caches_size),
+            token_price_cache_lifetime: json_rpc_config.token_price_cache_lifetime(),
+            cache_of_status: Arc::new(RwLock::new(None)),
+            cache_of_account_info: AsyncLruCache::new(json_rpc_config.account_info_cache_size),
+            account_info_cache_lifetime: json_rpc_config.account_info_cache_lifetime(),
+
+            request_timeout: json_rpc_config.request_timeout(),
 
             confirmations_for_eth_event,
+            eth_client,
 
             tx_sender,
         }
@@ -81,21 +137,173 @@ impl RpcApp {
     pub fn extend<T: Metadata, S: Middleware<T>>(self, io: &mut MetaIoHandler<T, S>) {
         io.extend_with(self.to_delegate())
     }
+
+    /// Returns the time limit for processing a request by the handler of the given kind.
+    fn request_timeout(&self, kind: HandlerKind) -> Option<Duration> {
+        match kind {
+            HandlerKind::Query => Some(self.request_timeout),
+            HandlerKind::Submit => None,
+        }
+    }
 }
 
 impl RpcApp {
     async fn access_storage(&self) -> Result<StorageProcessor<'_>> {
         self.tx_sender
             .pool
-            .access_storage()
+            .access_storage_tagged(super::API_CONNECTION_TAG)
             .await
             .map_err(|_| Error::internal_error())
     }
 
     // cache access functions
+    async fn get_tokens(&self) -> Result<HashMap<String, Token>> {
+        if let Some((tokens, loaded_at)) = self.cache_of_tokens.read().await.as_ref() {
+            if loaded_at.elapsed() < self.tokens_cache_lifetime {
+                return Ok(tokens.clone());
+            }
+        }
+
+        let mut cache = self.cache_of_tokens.write().await;
+        // Another request could have refreshed the cache while we were waiting for the lock.
+        if let Some((tokens, loaded_at)) = cache.as_ref() {
+            if loaded_at.elapsed() < self.tokens_cache_lifetime {
+                return Ok(tokens.clone());
+            }
+        }
+
+        let mut storage = self.access_storage().await?;
+        let tokens: HashMap<_, _> = storage
+            .tokens_schema()
+            .load_tokens()
+            .await
+            .map_err(|err| {
+                vlog::warn!("Internal Server Error: '{}'; input: N/A", err);
+                Error::internal_error()
+            })?
+            .into_iter()
+            .map(|(id, token)| {
+                if *id == 0 {
+                    ("ETH".to_string(), token)
+                } else {
+                    (token.symbol.clone(), token)
+                }
+            })
+            .collect();
+
+        *cache = Some((tokens.clone(), Instant::now()));
+        Ok(tokens)
+    }
+
+    /// Drops the cached tokens list, so the next `tokens` request reloads it from the database.
+    async fn invalidate_tokens_cache(&self) {
+        *self.cache_of_tokens.write().await = None;
+    }
+
+    /// Drops the cached tokens list if a token was registered after the list was loaded.
+    async fn invalidate_tokens_cache_on_registration(&self) -> anyhow::Result<()> {
+        let cached_max_token_id = match self.cache_of_tokens.read().await.as_ref() {
+            Some((tokens, _)) => tokens.values().map(|token| *token.id).max(),
+            None => return Ok(()),
+        };
+
+        let mut storage = self
+            .tx_sender
+            .pool
+            .access_storage_tagged(super::API_CONNECTION_TAG)
+            .await?;
+        let max_token_id = storage.tokens_schema().get_max_erc20_token_id().await?;
+        if cached_max_token_id.unwrap_or_default() < max_token_id {
+            self.invalidate_tokens_cache().await;
+        }
+        Ok(())
+    }
+
+    async fn get_cached_token_price(&self, token: &TokenLike) -> Option<BigDecimal> {
+        let (price, loaded_at) = self.cache_of_token_prices.get(token).await?;
+        if loaded_at.elapsed() < self.token_price_cache_lifetime {
+            Some(price)
+        } else {
+            None
+        }
+    }
+
+    async fn cache_token_price(&self, token: TokenLike, price: BigDecimal) {
+        self.cache_of_token_prices
+            .insert(token, (price, Instant::now()))
+            .await;
+    }
+
+    async fn get_status(&self) -> Result<StatusResp> {
+        let is_fresh = |status: &StatusResp| {
+            (chrono::Utc::now() - status.updated_at)
+                .to_std()
+                .map(|age| age < STATUS_CACHE_LIFETIME)
+                .unwrap_or(true)
+        };
+        if let Some(status) = self.cache_of_status.read().await.as_ref() {
+            if is_fresh(status) {
+                return Ok(status.clone());
+            }
+        }
+
+        let mut cache = self.cache_of_status.write().await;
+        if let Some(status) = cache.as_ref() {
+            if is_fresh(status) {
+                return Ok(status.clone());
+            }
+        }
+
+        let mut storage = self.access_storage().await?;
+        let mut transaction = storage
+            .start_transaction()
+            .await
+            .map_err(|_| Error::internal_error())?;
+        let map_err = |err: anyhow::Error| {
+            vlog::warn!("Internal Server Error: '{}'; input: N/A", err);
+            Error::internal_error()
+        };
+
+        let last_committed_block = transaction
+            .chain()
+            .block_schema()
+            .get_last_committed_block()
+            .await
+            .map_err(map_err)?;
+        let last_committed_confirmed_block = transaction
+            .chain()
+            .block_schema()
+            .get_last_committed_confirmed_block()
+            .await
+            .map_err(map_err)?;
+        let last_verified_block = transaction
+            .chain()
+            .block_schema()
+            .get_last_verified_confirmed_block()
+            .await
+            .map_err(map_err)?;
+        let mempool_size = transaction
+            .chain()
+            .mempool_schema()
+            .get_mempool_size()
+            .await
+            .map_err(map_err)?;
+        transaction.commit().await.map_err(map_err)?;
+
+        let status = StatusResp {
+            last_committed_block,
+            last_verified_block,
+            mempool_size,
+            network_synced: last_committed_confirmed_block >= last_committed_block,
+            updated_at: chrono::Utc::now(),
+        };
+        *cache = Some(status.clone());
+        Ok(status)
+    }
+
     async fn get_executed_priority_operation(
         &self,
-        serial_id: u32,
+        serial_id: PriorityOpId,
     ) -> Result<Option<StoredExecutedPriorityOperation>> {
         let start = Instant::now();
         let res = if let Some(executed_op) = self
@@ -180,6 +388,101 @@ impl RpcApp {
         Ok(res)
     }
 
+    async fn get_cached_account_info(&self, address: Address) -> Option<AccountInfoResp> {
+        let (info, loaded_at) = self.cache_of_account_info.get(&address).await?;
+        if loaded_at.elapsed() < self.account_info_cache_lifetime {
+            Some(info)
+        } else {
+            None
+        }
+    }
+
+    async fn cache_account_info(&self, info: AccountInfoResp) {
+        self.cache_of_account_info
+            .insert(info.address, (info, Instant::now()))
+            .await;
+    }
+
+    /// Removes the cached `account_info` responses of the accounts changed
+    /// in the blocks after `from` up to `to` inclusively.
+    async fn invalidate_account_info_cache(
+        &self,
+        from: BlockNumber,
+        to: BlockNumber,
+    ) -> anyhow::Result<()> {
+        let mut storage = self
+            .tx_sender
+            .pool
+            .access_storage_tagged(super::API_CONNECTION_TAG)
+            .await?;
+        let account_ids = storage
+            .chain()
+            .block_schema()
+            .accounts_changed_between(from, to)
+            .await?;
+        for account_id in account_ids {
+            let address = storage
+                .chain()
+                .account_schema()
+                .account_address_by_id(account_id)
+                .await?;
+            if let Some(address) = address {
+                self.cache_of_account_info.remove(&address).await;
+            }
+        }
+        Ok(())
+    }
+
+    async fn load_last_committed_block(&self) -> anyhow::Result<BlockNumber> {
+        let mut storage = self
+            .tx_sender
+            .pool
+            .access_storage_tagged(super::API_CONNECTION_TAG)
+            .await?;
+        storage
+            .chain()
+            .block_schema()
+            .get_last_committed_block()
+            .await
+    }
+
+    /// Polls the storage for the newly registered tokens and committed blocks. Drops the cached
+    /// tokens list once a token is registered, and removes the cached `account_info` responses
+    /// of the accounts touched by the committed blocks.
+    async fn run_cache_invalidator(self) {
+        let mut last_committed_block = None;
+        let mut timer = tokio::time::interval(CACHE_INVALIDATION_INTERVAL);
+        loop {
+            timer.tick().await;
+
+            if let Err(err) = self.invalidate_tokens_cache_on_registration().await {
+                vlog::warn!("Unable to check for the newly registered tokens: {}", err);
+            }
+
+            let block = match self.load_last_committed_block().await {
+                Ok(block) => block,
+                Err(err) => {
+                    vlog::warn!("Unable to load the last committed block: {}", err);
+                    continue;
+                }
+            };
+
+            // The cache is empty before the first check, so there is nothing to invalidate yet.
+            let from = match last_committed_block {
+                Some(from) if from < block => from,
+                Some(_) => continue,
+                None => {
+                    last_committed_block = Some(block);
+                    continue;
+                }
+            };
+            match self.invalidate_account_info_cache(from, block).await {
+                Ok(()) => last_committed_block = Some(block),
+                Err(err) => vlog::warn!("Unable to invalidate the account info cache: {}", err),
+            }
+        }
+    }
+
     async fn get_account_state(&self, address: Address) -> Result<AccountStateInfo> {
         let start = Instant::now();
         let mut storage = self.access_storage().await?;
@@ -266,6 +569,8 @@ pub fn start_rpc_server(
     token_config: &TokenConfig,
     mempool_tx_sender: mpsc::Sender<MempoolTransactionRequest>,
     confirmations_for_eth_event: u64,
+    block_chunk_sizes: Vec<usize>,
+    eth_client: EthereumGateway,
 ) -> JoinHandle<()> {
     let addr = config.http_bind_addr();
     let rpc_app = RpcApp::new(
@@ -274,10 +579,15 @@ pub fn start_rpc_server(
         ticker,
         common_api_config,
         token_config,
+        config,
         confirmations_for_eth_event,
         mempool_tx_sender,
+        block_chunk_sizes,
+        eth_client,
     );
 
+    tokio::spawn(rpc_app.clone().run_cache_invalidator());
+
     let (handler, panic_sender) = spawn_panic_handler();
     std::thread::spawn(move || {
         let _panic_sentinel = ThreadPanicNotify(panic_sender);
@@ -296,8 +606,23 @@ pub fn start_rpc_server(
 
 #[cfg(test)]
 mod test {
+    use num::BigUint;
     use serde::{Deserialize, Serialize};
-    use zksync_types::TxFeeTypes;
+    use zksync_types::{
+        tokens::TokenKind, Address, Deposit, FullExit, PriorityOp, Token, TokenId, TxFeeTypes,
+        ZkSyncPriorityOp, H256,
+    };
+
+    use super::{types::PendingPriorityOpResp, RpcApp};
+    use crate::api_server::rest::v02::test_utils::{
+        dummy_fee_ticker, TestServerConfig, COMMITTED_OP_SERIAL_ID, EXECUTED_BLOCKS_COUNT,
+        VERIFIED_OP_SERIAL_ID,
+    };
+    use futures::channel::mpsc;
+    use jsonrpc_core::ErrorCode;
+    use zksync_api_types::MAX_ACCOUNTS_INFO_ADDRESSES;
+    use zksync_eth_client::{clients::mock::MockEthereum, EthereumGateway};
+    use zksync_types::BlockNumber;
 
     #[test]
     fn tx_fee_type_serialization() {
@@ -327,4 +652,399 @@ mod test {
             assert_eq!(query, de);
         }
     }
+
+    #[test]
+    fn pending_priority_op_response() {
+        let deposit = PriorityOp {
+            serial_id: 7,
+            data: ZkSyncPriorityOp::Deposit(Deposit {
+                from: Address::random(),
+                token: TokenId(1),
+                amount: BigUint::from(500u32),
+                to: Address::random(),
+            }),
+            deadline_block: 100,
+            eth_hash: H256::random(),
+            eth_block: 20,
+            eth_block_index: Some(1),
+        };
+        let resp = PendingPriorityOpResp::new(deposit.clone(), 23, 5);
+        assert_eq!(resp.serial_id, 7);
+        assert_eq!(resp.op_type, "Deposit");
+        assert_eq!(resp.token_id, TokenId(1));
+        assert_eq!(resp.amount, Some(BigUint::from(500u32).into()));
+        assert_eq!(resp.eth_hash, deposit.eth_hash);
+        assert_eq!(resp.received_on_block, 20);
+        assert_eq!(resp.current_confirmations, 3);
+        assert_eq!(resp.required_confirmations, 5);
+        assert_eq!(resp.expected_accept_block, 25);
+
+        let full_exit = PriorityOp {
+            serial_id: 8,
+            data: ZkSyncPriorityOp::FullExit(FullExit {
+                account_id: Default::default(),
+                eth_address: Address::random(),
+                token: TokenId(1),
+                is_legacy: false,
+            }),
+            ..deposit
+        };
+        let resp = PendingPriorityOpResp::new(full_exit, 23, 5);
+        assert_eq!(resp.op_type, "FullExit");
+        assert_eq!(resp.amount, None);
+    }
+
+    #[tokio::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn pending_priority_ops() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+
+        let mut eth_client = MockEthereum::default();
+        eth_client.set_block_number(30u64.into()).await?;
+
+        let app = RpcApp::new(
+            cfg.pool.clone(),
+            mpsc::channel(1).0,
+            dummy_fee_ticker(&[], None),
+            &cfg.config.api.common,
+            &cfg.config.api.token_config,
+            &cfg.config.api.json_rpc,
+            5,
+            mpsc::channel(1).0,
+            cfg.config.chain.state_keeper.block_chunk_sizes.clone(),
+            EthereumGateway::Mock(eth_client),
+        );
+
+        let address = Address::random();
+        let ops = app
+            .clone()
+            ._impl_pending_priority_ops(address)
+            .await
+            .unwrap();
+        assert!(ops.is_empty());
+
+        let deposit = PriorityOp {
+            serial_id: 100,
+            data: ZkSyncPriorityOp::Deposit(Deposit {
+                from: Address::random(),
+                token: TokenId(0),
+                amount: BigUint::from(500u32),
+                to: address,
+            }),
+            deadline_block: 0,
+            eth_hash: H256::random(),
+            eth_block: 27,
+            eth_block_index: Some(1),
+        };
+        let full_exit = PriorityOp {
+            serial_id: 101,
+            data: ZkSyncPriorityOp::FullExit(FullExit {
+                account_id: Default::default(),
+                eth_address: address,
+                token: TokenId(0),
+                is_legacy: false,
+            }),
+            eth_hash: H256::random(),
+            eth_block: 20,
+            ..deposit.clone()
+        };
+        cfg.pool
+            .access_storage()
+            .await?
+            .chain()
+            .mempool_schema()
+            .insert_priority_ops(&[deposit, full_exit], false)
+            .await?;
+
+        let ops = app._impl_pending_priority_ops(address).await.unwrap();
+        assert_eq!(ops.len(), 2);
+        assert_eq!(ops[0].serial_id, 100);
+        assert_eq!(ops[0].op_type, "Deposit");
+        assert_eq!(ops[0].current_confirmations, 3);
+        assert_eq!(ops[0].required_confirmations, 5);
+        assert_eq!(ops[0].expected_accept_block, 32);
+        assert_eq!(ops[1].serial_id, 101);
+        assert_eq!(ops[1].op_type, "FullExit");
+        assert_eq!(ops[1].current_confirmations, 10);
+        assert_eq!(ops[1].amount, None);
+
+        cfg.pool
+            .access_storage()
+            .await?
+            .chain()
+            .mempool_schema()
+            .remove_priority_ops_from_mempool(&[100, 101])
+            .await?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn ethop_block() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+
+        let app = RpcApp::new(
+            cfg.pool.clone(),
+            mpsc::channel(1).0,
+            dummy_fee_ticker(&[], None),
+            &cfg.config.api.common,
+            &cfg.config.api.token_config,
+            &cfg.config.api.json_rpc,
+            0,
+            mpsc::channel(1).0,
+            cfg.config.chain.state_keeper.block_chunk_sizes.clone(),
+            EthereumGateway::Mock(MockEthereum::default()),
+        );
+
+        let block = app
+            .clone()
+            ._impl_ethop_block(VERIFIED_OP_SERIAL_ID as u32)
+            .await
+            .unwrap();
+        assert_eq!(block, Some(BlockNumber(2)));
+
+        let block = app
+            .clone()
+            ._impl_ethop_block(COMMITTED_OP_SERIAL_ID as u32)
+            .await
+            .unwrap();
+        assert_eq!(block, Some(BlockNumber(EXECUTED_BLOCKS_COUNT + 1)));
+
+        // Operation which is not processed yet.
+        let block = app
+            ._impl_ethop_block(COMMITTED_OP_SERIAL_ID as u32 + 1)
+            .await
+            .unwrap();
+        assert_eq!(block, None);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn account_info_nonzero() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+
+        let app = RpcApp::new(
+            cfg.pool.clone(),
+            mpsc::channel(1).0,
+            dummy_fee_ticker(&[], None),
+            &cfg.config.api.common,
+            &cfg.config.api.token_config,
+            &cfg.config.api.json_rpc,
+            0,
+            mpsc::channel(1).0,
+            cfg.config.chain.state_keeper.block_chunk_sizes.clone(),
+            EthereumGateway::Mock(MockEthereum::default()),
+        );
+
+        // Account without any balances.
+        let address = Address::random();
+
+        let info = app
+            .clone()
+            ._impl_account_info_nonzero(address, None)
+            .await
+            .unwrap();
+        assert!(info.committed.balances.is_empty());
+        assert!(info.verified.balances.is_empty());
+
+        let info = app
+            ._impl_account_info_nonzero(address, Some(true))
+            .await
+            .unwrap();
+        assert_eq!(info.committed.balances.len(), 1);
+        assert_eq!(info.committed.balances["ETH"], BigUint::from(0u32).into());
+        assert_eq!(info.verified.balances.len(), 1);
+        assert_eq!(info.verified.balances["ETH"], BigUint::from(0u32).into());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn account_info_cache() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+
+        let app = RpcApp::new(
+            cfg.pool.clone(),
+            mpsc::channel(1).0,
+            dummy_fee_ticker(&[], None),
+            &cfg.config.api.common,
+            &cfg.config.api.token_config,
+            &cfg.config.api.json_rpc,
+            0,
+            mpsc::channel(1).0,
+            cfg.config.chain.state_keeper.block_chunk_sizes.clone(),
+            EthereumGateway::Mock(MockEthereum::default()),
+        );
+
+        // Pick an account touched by the committed blocks.
+        let (address, last_committed_block) = {
+            let mut storage = cfg.pool.access_storage().await?;
+            let last_committed_block = storage
+                .chain()
+                .block_schema()
+                .get_last_committed_block()
+                .await?;
+            let account_id = storage
+                .chain()
+                .block_schema()
+                .accounts_changed_between(BlockNumber(0), last_committed_block)
+                .await?
+                .into_iter()
+                .min()
+                .expect("Committed blocks should change accounts");
+            let address = storage
+                .chain()
+                .account_schema()
+                .account_address_by_id(account_id)
+                .await?
+                .expect("Changed account should exist");
+            (address, last_committed_block)
+        };
+
+        let info = app.clone()._impl_account_info(address).await.unwrap();
+
+        // Replace the cached response to make sure that the next request is served from the cache.
+        let mut cached_info = info.clone();
+        cached_info.committed.nonce = info.committed.nonce + 1;
+        app.cache_account_info(cached_info).await;
+        let served_info = app.clone()._impl_account_info(address).await.unwrap();
+        assert_eq!(served_info.committed.nonce, info.committed.nonce + 1);
+
+        // Blocks that didn't touch the account don't invalidate its response.
+        app.invalidate_account_info_cache(last_committed_block, last_committed_block)
+            .await?;
+        let served_info = app.clone()._impl_account_info(address).await.unwrap();
+        assert_eq!(served_info.committed.nonce, info.committed.nonce + 1);
+
+        // Once the block touching the account is committed, the response is loaded from the storage.
+        app.invalidate_account_info_cache(BlockNumber(0), last_committed_block)
+            .await?;
+        let served_info = app._impl_account_info(address).await.unwrap();
+        assert_eq!(served_info.committed.nonce, info.committed.nonce);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn token_registered_after_startup() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+
+        let app = RpcApp::new(
+            cfg.pool.clone(),
+            mpsc::channel(1).0,
+            dummy_fee_ticker(&[], None),
+            &cfg.config.api.common,
+            &cfg.config.api.token_config,
+            &cfg.config.api.json_rpc,
+            0,
+            mpsc::channel(1).0,
+            cfg.config.chain.state_keeper.block_chunk_sizes.clone(),
+            EthereumGateway::Mock(MockEthereum::default()),
+        );
+
+        let tokens = app.clone()._impl_tokens().await.unwrap();
+
+        let token_id = {
+            let mut storage = cfg.pool.access_storage().await?;
+            let token_id = storage.tokens_schema().get_max_erc20_token_id().await? + 1;
+            storage
+                .tokens_schema()
+                .store_token(Token::new(
+                    TokenId(token_id),
+                    Address::random(),
+                    &format!("NEW{}", token_id),
+                    18,
+                    TokenKind::ERC20,
+                ))
+                .await?;
+            token_id
+        };
+        let symbol = format!("NEW{}", token_id);
+        assert!(!tokens.contains_key(&symbol));
+
+        // The cached list is served until the registration is noticed.
+        let tokens = app.clone()._impl_tokens().await.unwrap();
+        assert!(!tokens.contains_key(&symbol));
+
+        app.invalidate_tokens_cache_on_registration().await?;
+        let tokens = app._impl_tokens().await.unwrap();
+        assert_eq!(tokens[&symbol].id, TokenId(token_id));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn accounts_info_limit() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+
+        let app = RpcApp::new(
+            cfg.pool.clone(),
+            mpsc::channel(1).0,
+            dummy_fee_ticker(&[], None),
+            &cfg.config.api.common,
+            &cfg.config.api.token_config,
+            &cfg.config.api.json_rpc,
+            0,
+            mpsc::channel(1).0,
+            cfg.config.chain.state_keeper.block_chunk_sizes.clone(),
+            EthereumGateway::Mock(MockEthereum::default()),
+        );
+
+        let addresses: Vec<_> = (0..MAX_ACCOUNTS_INFO_ADDRESSES)
+            .map(|_| Address::random())
+            .collect();
+        let accounts = app
+            .clone()
+            ._impl_accounts_info(addresses.clone())
+            .await
+            .unwrap();
+        assert_eq!(accounts.len(), MAX_ACCOUNTS_INFO_ADDRESSES);
+        for (account, address) in accounts.iter().zip(&addresses) {
+            assert_eq!(account.address, *address);
+        }
+
+        let mut addresses = addresses;
+        addresses.push(Address::random());
+        let error = app._impl_accounts_info(addresses).await.unwrap_err();
+        assert_eq!(error.code, ErrorCode::InvalidParams);
+        assert_eq!(
+            error.data,
+            Some(serde_json::json!({
+                "limit": MAX_ACCOUNTS_INFO_ADDRESSES,
+                "requested": MAX_ACCOUNTS_INFO_ADDRESSES + 1,
+            }))
+        );
+
+        Ok(())
+    }
 }
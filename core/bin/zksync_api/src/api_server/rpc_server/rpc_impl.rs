@@ -2,35 +2,49 @@ use std::collections::HashMap;
 use std::time::Instant;
 // External uses
 use bigdecimal::BigDecimal;
-use jsonrpc_core::{Error, Result};
+use futures::{StreamExt, TryStreamExt};
+use jsonrpc_core::{Error, ErrorCode, Result};
 // Workspace uses
 use zksync_api_types::{
     v02::{
         fee::ApiTxFeeTypes,
+        pagination::PaginationDirection,
         token::ApiNFT,
         transaction::{Toggle2FA, Toggle2FAResponse},
     },
-    TxWithSignature,
+    TxWithSignature, MAX_ACCOUNTS_INFO_ADDRESSES,
 };
 use zksync_crypto::params::MIN_NFT_TOKEN_ID;
 use zksync_types::{
-    tx::{EthBatchSignatures, TxEthSignatureVariant, TxHash},
-    AccountId, Address, Fee, Token, TokenId, TokenLike, TotalFee, TxFeeTypes, ZkSyncTx,
+    tokens::ETH_TOKEN_ID,
+    tx::{error::TxAddError, EthBatchSignatures, TxEthSignatureVariant, TxHash},
+    AccountId, Address, BlockNumber, Fee, PriorityOpId, Token, TokenId, TokenLike, TotalFee,
+    TxFeeTypes, ZkSyncTx,
 };
 // Local uses
 use crate::{
     api_server::{
         helpers::get_depositing, rpc_server::error::RpcErrorCodes, tx_sender::SubmitError,
     },
-    fee_ticker::TokenPriceRequestType,
+    fee_ticker::{FeeParams, TokenPriceRequestType},
 };
 
 use super::{types::*, RpcApp};
 
+/// Maximum amount of accounts loaded simultaneously by a single `accounts_info` call.
+const ACCOUNTS_INFO_CONCURRENCY: usize = 10;
+/// Maximum amount of operations returned by the `pending_priority_ops` method.
+pub const MAX_PENDING_PRIORITY_OPS: u32 = 100;
+
 impl RpcApp {
     pub async fn _impl_account_info(self, address: Address) -> Result<AccountInfoResp> {
         let start = Instant::now();
 
+        if let Some(info) = self.get_cached_account_info(address).await {
+            metrics::histogram!("api", start.elapsed(), "type" => "rpc", "endpoint_name" => "account_info");
+            return Ok(info);
+        }
+
         let account_state = self.get_account_state(address).await?;
 
         let mut storage = self.access_storage().await?;
@@ -51,8 +65,8 @@ impl RpcApp {
             );
             Error::internal_error()
         })?;
-        let account_type = if let Some(account_id) = account_state.account_id {
-            storage
+        let (account_type, created_at_block) = if let Some(account_id) = account_state.account_id {
+            let account_type = storage
                 .chain()
                 .account_schema()
                 .account_type_by_id(account_id)
@@ -67,25 +81,112 @@ impl RpcApp {
                     );
                     Error::internal_error()
                 })?
-                .map(|t| t.into())
+                .map(|t| t.into());
+            let created_at_block = storage
+                .chain()
+                .account_schema()
+                .account_creation_block(account_id)
+                .await
+                .map_err(|err| {
+                    vlog::warn!(
+                        "[{}:{}:{}] Internal Server Error: '{}'; input: N/A",
+                        file!(),
+                        line!(),
+                        column!(),
+                        err
+                    );
+                    Error::internal_error()
+                })?;
+            (account_type, created_at_block)
         } else {
-            None
+            (None, None)
         };
 
-        metrics::histogram!("api", start.elapsed(), "type" => "rpc", "endpoint_name" => "account_info");
-        Ok(AccountInfoResp {
+        let info = AccountInfoResp {
             address,
             id: account_state.account_id,
             committed: account_state.committed,
             verified: account_state.verified,
             depositing,
             account_type,
-        })
+            created_at_block,
+        };
+        self.cache_account_info(info.clone()).await;
+
+        metrics::histogram!("api", start.elapsed(), "type" => "rpc", "endpoint_name" => "account_info");
+        Ok(info)
+    }
+
+    pub async fn _impl_account_info_nonzero(
+        self,
+        address: Address,
+        include_eth: Option<bool>,
+    ) -> Result<AccountInfoResp> {
+        let start = Instant::now();
+        // Balances in `account_info` are already non-zero, so only the ETH entry has to be added.
+        let eth_symbol = if include_eth.unwrap_or(false) {
+            let mut storage = self.access_storage().await?;
+            let symbol = self
+                .tx_sender
+                .tokens
+                .token_symbol(&mut storage, ETH_TOKEN_ID)
+                .await
+                .map_err(|_| Error::internal_error())?
+                .ok_or_else(Error::internal_error)?;
+            Some(symbol)
+        } else {
+            None
+        };
+
+        let mut info = self._impl_account_info(address).await?;
+        if let Some(eth_symbol) = eth_symbol {
+            info.committed
+                .balances
+                .entry(eth_symbol.clone())
+                .or_default();
+            info.verified.balances.entry(eth_symbol).or_default();
+        }
+
+        metrics::histogram!("api", start.elapsed(), "type" => "rpc", "endpoint_name" => "account_info_nonzero");
+        Ok(info)
+    }
+
+    pub async fn _impl_accounts_info(
+        self,
+        addresses: Vec<Address>,
+    ) -> Result<Vec<AccountInfoResp>> {
+        let start = Instant::now();
+        if addresses.len() > MAX_ACCOUNTS_INFO_ADDRESSES {
+            return Err(Error {
+                code: ErrorCode::InvalidParams,
+                message: format!(
+                    "Too many addresses requested: {}, the limit is {}",
+                    addresses.len(),
+                    MAX_ACCOUNTS_INFO_ADDRESSES
+                ),
+                data: Some(serde_json::json!({
+                    "limit": MAX_ACCOUNTS_INFO_ADDRESSES,
+                    "requested": addresses.len(),
+                })),
+            });
+        }
+
+        // Accounts are loaded concurrently, but a single request must not occupy the whole connection pool.
+        let accounts: Vec<_> = futures::stream::iter(addresses)
+            .map(|address| self.clone()._impl_account_info(address))
+            .buffered(ACCOUNTS_INFO_CONCURRENCY)
+            .try_collect()
+            .await?;
+
+        metrics::histogram!("api", start.elapsed(), "type" => "rpc", "endpoint_name" => "accounts_info");
+        Ok(accounts)
     }
 
     pub async fn _impl_ethop_info(self, serial_id: u32) -> Result<ETHOpInfoResp> {
         let start = Instant::now();
-        let executed_op = self.get_executed_priority_operation(serial_id).await?;
+        let executed_op = self
+            .get_executed_priority_operation(PriorityOpId(u64::from(serial_id)))
+            .await?;
         let result = if let Some(executed_op) = executed_op {
             let block = self.get_block_info(executed_op.block_number).await?;
             ETHOpInfoResp {
@@ -107,6 +208,78 @@ impl RpcApp {
         Ok(result)
     }
 
+    pub async fn _impl_ethop_block(self, serial_id: u32) -> Result<Option<BlockNumber>> {
+        let start = Instant::now();
+        let block_number = self
+            .get_executed_priority_operation(PriorityOpId(u64::from(serial_id)))
+            .await?
+            .map(|executed_op| BlockNumber(executed_op.block_number as u32));
+
+        metrics::histogram!("api", start.elapsed(), "type" => "rpc", "endpoint_name" => "ethop_block");
+        Ok(block_number)
+    }
+
+    pub async fn _impl_pending_priority_ops(
+        self,
+        address: Address,
+    ) -> Result<Vec<PendingPriorityOpResp>> {
+        let start = Instant::now();
+        let mut storage = self.access_storage().await?;
+        let ops = storage
+            .chain()
+            .mempool_schema()
+            .get_pending_deposits_for(
+                address,
+                0,
+                MAX_PENDING_PRIORITY_OPS,
+                PaginationDirection::Newer,
+            )
+            .await
+            .map_err(|err| {
+                vlog::warn!(
+                    "[{}:{}:{}] Internal Server Error: '{}'; input: {:?}",
+                    file!(),
+                    line!(),
+                    column!(),
+                    err,
+                    address,
+                );
+                Error::internal_error()
+            })?;
+
+        // There is nothing to count the confirmations for, so the Ethereum node is not queried.
+        if ops.is_empty() {
+            metrics::histogram!("api", start.elapsed(), "type" => "rpc", "endpoint_name" => "pending_priority_ops");
+            return Ok(Vec::new());
+        }
+
+        let current_eth_block = self
+            .eth_client
+            .block_number()
+            .await
+            .map_err(|err| {
+                vlog::warn!(
+                    "[{}:{}:{}] Internal Server Error: '{}'; input: N/A",
+                    file!(),
+                    line!(),
+                    column!(),
+                    err
+                );
+                Error::internal_error()
+            })?
+            .as_u64();
+
+        let result = ops
+            .into_iter()
+            .map(|op| {
+                PendingPriorityOpResp::new(op, current_eth_block, self.confirmations_for_eth_event)
+            })
+            .collect();
+
+        metrics::histogram!("api", start.elapsed(), "type" => "rpc", "endpoint_name" => "pending_priority_ops");
+        Ok(result)
+    }
+
     pub async fn _impl_get_confirmations_for_eth_op_amount(self) -> Result<u64> {
         Ok(self.confirmations_for_eth_event)
     }
@@ -159,6 +332,7 @@ impl RpcApp {
             let err_label = match err {
                 SubmitError::IncorrectTx(err) => err.clone(),
                 SubmitError::TxAdd(err) => err.to_string(),
+                SubmitError::TxFeeTooLow { .. } => TxAddError::TxFeeTooLow.to_string(),
                 _ => "other".to_string(),
             };
             let labels = vec![("stage", "api".to_string()), ("error", err_label)];
@@ -193,6 +367,7 @@ impl RpcApp {
             let err_label = match err {
                 SubmitError::IncorrectTx(err) => err.clone(),
                 SubmitError::TxAdd(err) => err.to_string(),
+                SubmitError::TxFeeTooLow { .. } => TxAddError::TxFeeTooLow.to_string(),
                 _ => "other".to_string(),
             };
             let labels = vec![("stage", "api".to_string()), ("error", err_label)];
@@ -251,25 +426,16 @@ impl RpcApp {
 
     pub async fn _impl_tokens(self) -> Result<HashMap<String, Token>> {
         let start = Instant::now();
-        let mut storage = self.access_storage().await?;
-        let mut tokens = storage.tokens_schema().load_tokens().await.map_err(|err| {
-            vlog::warn!("Internal Server Error: '{}'; input: N/A", err);
-            Error::internal_error()
-        })?;
-
-        let result: HashMap<_, _> = tokens
-            .drain()
-            .map(|(id, token)| {
-                if *id == 0 {
-                    ("ETH".to_string(), token)
-                } else {
-                    (token.symbol.clone(), token)
-                }
-            })
-            .collect();
-
+        let result = self.get_tokens().await;
         metrics::histogram!("api", start.elapsed(), "type" => "rpc", "endpoint_name" => "tokens");
-        Ok(result)
+        result
+    }
+
+    pub async fn _impl_status(self) -> Result<StatusResp> {
+        let start = Instant::now();
+        let result = self.get_status().await;
+        metrics::histogram!("api", start.elapsed(), "type" => "rpc", "endpoint_name" => "status");
+        result
     }
 
     pub async fn _impl_get_tx_fee(
@@ -280,6 +446,38 @@ impl RpcApp {
         extracted_request_metadata: Option<RequestMetadata>,
     ) -> Result<Fee> {
         let start = Instant::now();
+        let fee = self
+            .get_fee(tx_type.into(), address, token, extracted_request_metadata)
+            .await?;
+        metrics::histogram!("api", start.elapsed(), "type" => "rpc", "endpoint_name" => "get_tx_fee");
+        Ok(fee)
+    }
+
+    pub async fn _impl_get_tx_fee_for_tx(
+        self,
+        tx: ZkSyncTx,
+        token: TokenLike,
+        extracted_request_metadata: Option<RequestMetadata>,
+    ) -> Result<Fee> {
+        let start = Instant::now();
+        // The recipient address is used by the ticker to tell `Transfer` from `TransferToNew`.
+        let (tx_type, _, address, _) = tx.get_fee_info().ok_or_else(|| {
+            Error::invalid_params("Fee is not applicable for the provided transaction")
+        })?;
+        let fee = self
+            .get_fee(tx_type, address, token, extracted_request_metadata)
+            .await?;
+        metrics::histogram!("api", start.elapsed(), "type" => "rpc", "endpoint_name" => "get_tx_fee_for_tx");
+        Ok(fee)
+    }
+
+    async fn get_fee(
+        &self,
+        tx_type: TxFeeTypes,
+        address: Address,
+        token: TokenLike,
+        extracted_request_metadata: Option<RequestMetadata>,
+    ) -> Result<Fee> {
         let token_allowed = self
             .tx_sender
             .ticker
@@ -293,7 +491,7 @@ impl RpcApp {
         let result = self
             .tx_sender
             .ticker
-            .get_fee_from_ticker_in_wei(tx_type.into(), token.clone(), address)
+            .get_fee_from_ticker_in_wei(tx_type, token.clone(), address)
             .await
             .map_err(SubmitError::Internal)?;
 
@@ -312,8 +510,6 @@ impl RpcApp {
         } else {
             result.normal_fee
         };
-
-        metrics::histogram!("api", start.elapsed(), "type" => "rpc", "endpoint_name" => "get_tx_fee");
         Ok(fee)
     }
 
@@ -381,16 +577,34 @@ impl RpcApp {
 
     pub async fn _impl_get_token_price(self, token: TokenLike) -> Result<BigDecimal> {
         let start = Instant::now();
+        if let Some(price) = self.get_cached_token_price(&token).await {
+            metrics::histogram!("api", start.elapsed(), "type" => "rpc", "endpoint_name" => "get_token_price");
+            return Ok(price);
+        }
+
         let result = self
             .tx_sender
             .ticker
-            .get_token_price(token, TokenPriceRequestType::USDForOneToken)
+            .get_token_price(token.clone(), TokenPriceRequestType::USDForOneToken)
             .await
             .map_err(|_| Error::internal_error());
+        if let Ok(price) = &result {
+            self.cache_token_price(token, price.clone()).await;
+        }
         metrics::histogram!("api", start.elapsed(), "type" => "rpc", "endpoint_name" => "get_token_price");
         result
     }
 
+    pub async fn _impl_fee_params(self) -> Result<FeeParams> {
+        let start = Instant::now();
+        let result = self.tx_sender.ticker.fee_params().await.map_err(|err| {
+            vlog::warn!("Internal Server Error: '{}'; input: N/A", err);
+            Error::internal_error()
+        });
+        metrics::histogram!("api", start.elapsed(), "type" => "rpc", "endpoint_name" => "fee_params");
+        result
+    }
+
     pub async fn _impl_get_eth_tx_for_withdrawal(
         self,
         withdrawal_hash: TxHash,
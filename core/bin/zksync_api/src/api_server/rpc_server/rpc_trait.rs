@@ -1,7 +1,14 @@
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    future::Future,
+    panic::AssertUnwindSafe,
+    sync::atomic::{AtomicU64, Ordering},
+    time::Duration,
+};
 // External uses
 use bigdecimal::BigDecimal;
-use jsonrpc_core::{BoxFuture, Result};
+use futures::FutureExt;
+use jsonrpc_core::{BoxFuture, Error, ErrorCode, Result};
 use jsonrpc_derive::rpc;
 
 // Workspace uses
@@ -16,18 +23,82 @@ use zksync_api_types::{
 use zksync_crypto::params::ZKSYNC_VERSION;
 use zksync_types::{
     tx::{EthBatchSignatures, TxEthSignatureVariant, TxHash},
-    AccountId, Address, Fee, Token, TokenId, TokenLike, TotalFee, ZkSyncTx,
+    AccountId, Address, BlockNumber, Fee, Token, TokenId, TokenLike, TotalFee, ZkSyncTx,
 };
 
 // Local uses
-use super::{types::*, RpcApp};
+use super::{error::RpcErrorCodes, types::*, HandlerKind, RpcApp};
+use crate::fee_ticker::FeeParams;
 
 pub type BoxFutureResult<T> = BoxFuture<Result<T>>;
 
+/// Counter used to correlate the error returned to the client with the server log entry.
+static PANIC_CORRELATION_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Wraps the handler future so that a panic inside of it is turned into an internal
+/// JSON-RPC error instead of tearing down the whole request processing task.
+fn catch_handler_panic<T: Send + 'static>(
+    method: &'static str,
+    handler: impl Future<Output = Result<T>> + Send + 'static,
+) -> BoxFutureResult<T> {
+    Box::pin(AssertUnwindSafe(handler).catch_unwind().map(move |result| {
+        result.unwrap_or_else(|panic| {
+            let correlation_id = PANIC_CORRELATION_ID.fetch_add(1, Ordering::Relaxed);
+            let reason = panic
+                .downcast_ref::<&str>()
+                .map(|reason| reason.to_string())
+                .or_else(|| panic.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "unknown reason".to_string());
+            vlog::error!(
+                "RPC handler '{}' panicked: {}; correlation id: {}",
+                method,
+                reason,
+                correlation_id
+            );
+
+            Err(Error {
+                code: ErrorCode::InternalError,
+                message: "Internal error".to_string(),
+                data: Some(serde_json::json!({ "correlation_id": correlation_id })),
+            })
+        })
+    }))
+}
+
+/// Limits the handler execution time, if the `timeout` is set. The handler future is dropped
+/// once the time is up, so the work it was doing is cancelled at the next `.await` point.
+async fn with_timeout<T>(
+    method: &'static str,
+    timeout: Option<Duration>,
+    handler: impl Future<Output = Result<T>>,
+) -> Result<T> {
+    let timeout = match timeout {
+        Some(timeout) => timeout,
+        None => return handler.await,
+    };
+    tokio::time::timeout(timeout, handler)
+        .await
+        .unwrap_or_else(|_| {
+            vlog::warn!("RPC handler '{}' timed out after {:?}", method, timeout);
+            Err(Error {
+                code: RpcErrorCodes::RequestTimeout.into(),
+                message: "Request timed out".to_string(),
+                data: None,
+            })
+        })
+}
+
 macro_rules! spawn {
-    ($self: ident.$method: ident($($args: expr),*)) => {{
+    ($self: ident.$method: ident($($args: expr),*)) => {
+        spawn!($self.$method($($args),*), HandlerKind::Query)
+    };
+    ($self: ident.$method: ident($($args: expr),*), $kind: expr) => {{
         let self_ = $self.clone();
-        Box::pin(self_.$method($($args),*))
+        let timeout = self_.request_timeout($kind);
+        catch_handler_panic(
+            stringify!($method),
+            with_timeout(stringify!($method), timeout, self_.$method($($args),*)),
+        )
     }}
 }
 
@@ -36,9 +107,34 @@ pub trait Rpc {
     #[rpc(name = "account_info", returns = "AccountInfoResp")]
     fn account_info(&self, addr: Address) -> BoxFutureResult<AccountInfoResp>;
 
+    /// Same as `account_info` (which only includes the tokens with positive balance),
+    /// but if `include_eth` is set, the ETH balance is always included, even if it's zero.
+    #[rpc(name = "account_info_nonzero", returns = "AccountInfoResp")]
+    fn account_info_nonzero(
+        &self,
+        addr: Address,
+        include_eth: Option<bool>,
+    ) -> BoxFutureResult<AccountInfoResp>;
+
+    /// Returns the account info for each of the requested addresses, in the same order.
+    /// The amount of addresses is limited by `MAX_ACCOUNTS_INFO_ADDRESSES`.
+    #[rpc(name = "accounts_info", returns = "Vec<AccountInfoResp>")]
+    fn accounts_info(&self, addrs: Vec<Address>) -> BoxFutureResult<Vec<AccountInfoResp>>;
+
     #[rpc(name = "ethop_info", returns = "ETHOpInfoResp")]
     fn ethop_info(&self, serial_id: u32) -> BoxFutureResult<ETHOpInfoResp>;
 
+    /// Returns the number of the block which processed the priority operation,
+    /// or `None` if the operation is not processed yet.
+    #[rpc(name = "ethop_block", returns = "Option<BlockNumber>")]
+    fn ethop_block(&self, serial_id: u32) -> BoxFutureResult<Option<BlockNumber>>;
+
+    /// Returns the priority operations (deposits and full exits) of the account which are
+    /// received from Ethereum but not processed yet, ordered by the serial ID.
+    /// The amount of operations is limited by `MAX_PENDING_PRIORITY_OPS`.
+    #[rpc(name = "pending_priority_ops", returns = "Vec<PendingPriorityOpResp>")]
+    fn pending_priority_ops(&self, addr: Address) -> BoxFutureResult<Vec<PendingPriorityOpResp>>;
+
     #[rpc(name = "tx_info", returns = "ETHOpInfoResp")]
     fn tx_info(&self, hash: TxHash) -> BoxFutureResult<TransactionInfoResp>;
 
@@ -76,6 +172,16 @@ pub trait Rpc {
         extracted_request_metadata: Option<RequestMetadata>,
     ) -> BoxFutureResult<Fee>;
 
+    /// Returns the fee for the provided transaction paid in `token_like`.
+    /// Unlike `get_tx_fee`, the fee type is derived from the transaction itself.
+    #[rpc(name = "get_tx_fee_for_tx", returns = "Fee")]
+    fn get_tx_fee_for_tx(
+        &self,
+        tx: Box<ZkSyncTx>,
+        token_like: TokenLike,
+        extracted_request_metadata: Option<RequestMetadata>,
+    ) -> BoxFutureResult<Fee>;
+
     // _addresses argument is left for the backward compatibility.
     #[rpc(name = "get_txs_batch_fee_in_wei", returns = "TotalFee")]
     fn get_txs_batch_fee_in_wei(
@@ -89,6 +195,11 @@ pub trait Rpc {
     #[rpc(name = "get_token_price", returns = "BigDecimal")]
     fn get_token_price(&self, token_like: TokenLike) -> BoxFutureResult<BigDecimal>;
 
+    /// Returns the current parameters of the fee calculation: gas prices, coefficients and
+    /// the costs of the operations. Allows clients to estimate fees without calling `get_tx_fee`.
+    #[rpc(name = "fee_params", returns = "FeeParams")]
+    fn fee_params(&self) -> BoxFutureResult<FeeParams>;
+
     #[rpc(name = "get_confirmations_for_eth_op_amount", returns = "u64")]
     fn get_confirmations_for_eth_op_amount(&self) -> BoxFutureResult<u64>;
 
@@ -99,6 +210,10 @@ pub trait Rpc {
     #[rpc(name = "get_zksync_version", returns = "String")]
     fn get_zksync_version(&self) -> Result<String>;
 
+    /// Returns the sizes of the blocks (in chunks) that can be created by the server.
+    #[rpc(name = "block_sizes", returns = "Vec<usize>")]
+    fn block_sizes(&self) -> Result<Vec<usize>>;
+
     #[rpc(name = "get_nft", returns = "Option<ApiNFT>")]
     fn get_nft(&self, id: TokenId) -> BoxFutureResult<Option<ApiNFT>>;
 
@@ -110,6 +225,11 @@ pub trait Rpc {
 
     #[rpc(name = "get_nft_id_by_tx_hash", returns = "Option<TokenId>")]
     fn get_nft_id_by_tx_hash(&self, tx_hash: TxHash) -> BoxFutureResult<Option<TokenId>>;
+
+    /// Returns the server status. The response may be cached for a few seconds,
+    /// see `updated_at` field for the moment it was loaded.
+    #[rpc(name = "status", returns = "StatusResp")]
+    fn status(&self) -> BoxFutureResult<StatusResp>;
 }
 
 impl Rpc for RpcApp {
@@ -117,10 +237,30 @@ impl Rpc for RpcApp {
         spawn!(self._impl_account_info(addr))
     }
 
+    fn account_info_nonzero(
+        &self,
+        addr: Address,
+        include_eth: Option<bool>,
+    ) -> BoxFutureResult<AccountInfoResp> {
+        spawn!(self._impl_account_info_nonzero(addr, include_eth))
+    }
+
+    fn accounts_info(&self, addrs: Vec<Address>) -> BoxFutureResult<Vec<AccountInfoResp>> {
+        spawn!(self._impl_accounts_info(addrs))
+    }
+
     fn ethop_info(&self, serial_id: u32) -> BoxFutureResult<ETHOpInfoResp> {
         spawn!(self._impl_ethop_info(serial_id))
     }
 
+    fn ethop_block(&self, serial_id: u32) -> BoxFutureResult<Option<BlockNumber>> {
+        spawn!(self._impl_ethop_block(serial_id))
+    }
+
+    fn pending_priority_ops(&self, addr: Address) -> BoxFutureResult<Vec<PendingPriorityOpResp>> {
+        spawn!(self._impl_pending_priority_ops(addr))
+    }
+
     fn tx_info(&self, hash: TxHash) -> BoxFutureResult<TransactionInfoResp> {
         spawn!(self._impl_tx_info(hash))
     }
@@ -133,7 +273,10 @@ impl Rpc for RpcApp {
         fast_processing: Option<bool>,
         meta: Option<RequestMetadata>,
     ) -> BoxFutureResult<TxHash> {
-        spawn!(self._impl_tx_submit(tx, signature, fast_processing, meta))
+        spawn!(
+            self._impl_tx_submit(tx, signature, fast_processing, meta),
+            HandlerKind::Submit
+        )
     }
 
     // Important: the last parameter should have name `meta` and be of type `RequestMetadata`
@@ -143,7 +286,10 @@ impl Rpc for RpcApp {
         eth_signatures: Option<EthBatchSignatures>,
         meta: Option<RequestMetadata>,
     ) -> BoxFutureResult<Vec<TxHash>> {
-        spawn!(self._impl_submit_txs_batch(txs, eth_signatures, meta))
+        spawn!(
+            self._impl_submit_txs_batch(txs, eth_signatures, meta),
+            HandlerKind::Submit
+        )
     }
 
     fn contract_address(&self) -> BoxFutureResult<ContractAddressResp> {
@@ -165,6 +311,16 @@ impl Rpc for RpcApp {
         spawn!(self._impl_get_tx_fee(tx_type, address, token_like, meta))
     }
 
+    // Important: the last parameter should have name `meta` and be of type `RequestMetadata`
+    fn get_tx_fee_for_tx(
+        &self,
+        tx: Box<ZkSyncTx>,
+        token_like: TokenLike,
+        meta: Option<RequestMetadata>,
+    ) -> BoxFutureResult<Fee> {
+        spawn!(self._impl_get_tx_fee_for_tx(*tx, token_like, meta))
+    }
+
     // Important: the last parameter should have name `meta` and be of type `RequestMetadata`
     fn get_txs_batch_fee_in_wei(
         &self,
@@ -180,6 +336,10 @@ impl Rpc for RpcApp {
         spawn!(self._impl_get_token_price(token_like))
     }
 
+    fn fee_params(&self) -> BoxFutureResult<FeeParams> {
+        spawn!(self._impl_fee_params())
+    }
+
     fn get_confirmations_for_eth_op_amount(&self) -> BoxFutureResult<u64> {
         spawn!(self._impl_get_confirmations_for_eth_op_amount())
     }
@@ -195,6 +355,10 @@ impl Rpc for RpcApp {
         Ok(String::from(ZKSYNC_VERSION))
     }
 
+    fn block_sizes(&self) -> Result<Vec<usize>> {
+        Ok(self.tx_sender.block_chunk_sizes.clone())
+    }
+
     fn get_nft(&self, id: TokenId) -> BoxFutureResult<Option<ApiNFT>> {
         spawn!(self._impl_get_nft(id))
     }
@@ -210,4 +374,53 @@ impl Rpc for RpcApp {
     fn get_nft_id_by_tx_hash(&self, tx_hash: TxHash) -> BoxFutureResult<Option<TokenId>> {
         spawn!(self._impl_get_nft_id_by_tx_hash(tx_hash))
     }
+
+    fn status(&self) -> BoxFutureResult<StatusResp> {
+        spawn!(self._impl_status())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn handler_panic_becomes_internal_error() {
+        let response: Result<()> = catch_handler_panic("test", async {
+            panic!("handler failure");
+        })
+        .await;
+
+        let error = response.unwrap_err();
+        assert_eq!(error.code, ErrorCode::InternalError);
+        assert!(error.data.unwrap().get("correlation_id").is_some());
+
+        // Handlers that do not panic are passed through as is.
+        let response = catch_handler_panic("test", async { Ok(42) }).await;
+        assert_eq!(response.unwrap(), 42);
+    }
+
+    #[tokio::test]
+    async fn handler_timeout() {
+        let response: Result<()> = with_timeout("test", Some(Duration::from_millis(10)), async {
+            tokio::time::sleep(Duration::from_secs(10)).await;
+            Ok(())
+        })
+        .await;
+        assert_eq!(
+            response.unwrap_err().code,
+            ErrorCode::from(RpcErrorCodes::RequestTimeout)
+        );
+
+        let response = with_timeout("test", Some(Duration::from_secs(10)), async { Ok(42) }).await;
+        assert_eq!(response.unwrap(), 42);
+
+        // Handlers without the time limit are never cancelled.
+        let response = with_timeout("test", None, async {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            Ok(42)
+        })
+        .await;
+        assert_eq!(response.unwrap(), 42);
+    }
 }
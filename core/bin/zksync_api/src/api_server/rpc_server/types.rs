@@ -1,6 +1,7 @@
 use std::collections::HashMap;
 
 // External uses
+use chrono::{DateTime, Utc};
 use jsonrpc_core::{Error, Result};
 use serde::{Deserialize, Serialize};
 
@@ -9,10 +10,17 @@ use zksync_api_types::v02::{
     account::{DepositingAccountBalances, EthAccountType},
     token::NFT,
 };
-use zksync_crypto::params::{MIN_NFT_TOKEN_ID, NFT_TOKEN_ID_VAL};
+use zksync_crypto::{
+    params::{MIN_NFT_TOKEN_ID, NFT_TOKEN_ID_VAL},
+    serialization::FrSerde,
+    Fr,
+};
 use zksync_storage::StorageProcessor;
 use zksync_token_db_cache::TokenDBCache;
-use zksync_types::{Account, AccountId, Address, Nonce, PubKeyHash, TokenId};
+use zksync_types::{
+    block::Block, Account, AccountId, ActionType, Address, BlockNumber, Nonce, PriorityOp,
+    PubKeyHash, SerialId, TokenId, H256,
+};
 use zksync_utils::BigUintSerdeWrapper;
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -84,7 +92,7 @@ pub struct AccountStateInfo {
     pub verified: ResponseAccountState,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct AccountInfoResp {
     pub address: Address,
@@ -93,6 +101,8 @@ pub struct AccountInfoResp {
     pub committed: ResponseAccountState,
     pub verified: ResponseAccountState,
     pub account_type: Option<EthAccountType>,
+    /// Number of the block in which the account was created.
+    pub created_at_block: Option<BlockNumber>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -103,6 +113,32 @@ pub struct BlockInfo {
     pub verified: bool,
 }
 
+/// Notification sent to the block subscribers once a block is committed or verified.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockInfoResp {
+    pub block_number: BlockNumber,
+    pub action: ActionType,
+    #[serde(with = "FrSerde")]
+    pub new_state_root: Fr,
+    pub block_size: u64,
+    pub transactions_count: usize,
+    pub timestamp: u64,
+}
+
+impl BlockInfoResp {
+    pub fn new(block: &Block, action: ActionType) -> Self {
+        Self {
+            block_number: block.block_number,
+            action,
+            new_state_root: block.new_root_hash,
+            block_size: block.block_chunks_size as u64,
+            transactions_count: block.block_transactions.len(),
+            timestamp: block.timestamp,
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct TransactionInfoResp {
@@ -119,6 +155,47 @@ pub struct ETHOpInfoResp {
     pub block: Option<BlockInfo>,
 }
 
+/// Priority operation received from Ethereum but not processed yet.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingPriorityOpResp {
+    pub serial_id: SerialId,
+    /// Either `Deposit` or `FullExit`.
+    pub op_type: String,
+    pub token_id: TokenId,
+    /// Deposited amount, `None` for full exits since the withdrawn amount is not known in advance.
+    pub amount: Option<BigUintSerdeWrapper>,
+    pub eth_hash: H256,
+    /// Number of the Ethereum block in which the operation was received.
+    pub received_on_block: u64,
+    /// Number of Ethereum blocks mined since the operation was received.
+    pub current_confirmations: u64,
+    /// Number of Ethereum confirmations required before the operation is processed.
+    pub required_confirmations: u64,
+    /// Number of the Ethereum block after which the operation is expected to be processed.
+    pub expected_accept_block: u64,
+}
+
+impl PendingPriorityOpResp {
+    pub fn new(op: PriorityOp, current_eth_block: u64, required_confirmations: u64) -> Self {
+        let amount = op
+            .data
+            .try_get_deposit()
+            .map(|deposit| deposit.amount.into());
+        Self {
+            serial_id: op.serial_id,
+            op_type: op.data.variance_name(),
+            token_id: op.data.token_id(),
+            amount,
+            eth_hash: op.eth_hash,
+            received_on_block: op.eth_block,
+            current_confirmations: current_eth_block.saturating_sub(op.eth_block),
+            required_confirmations,
+            expected_accept_block: op.eth_block + required_confirmations,
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct ContractAddressResp {
@@ -126,6 +203,18 @@ pub struct ContractAddressResp {
     pub gov_contract: String,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct StatusResp {
+    pub last_committed_block: BlockNumber,
+    pub last_verified_block: BlockNumber,
+    pub mempool_size: u32,
+    /// Whether all the committed blocks have their commit transactions confirmed on Ethereum.
+    pub network_synced: bool,
+    /// Moment at which the status was loaded from the database.
+    pub updated_at: DateTime<Utc>,
+}
+
 /// The metadata of the JSON-RPC call retrieved from the HTTP request of the call
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct RequestMetadata {
@@ -12,6 +12,7 @@ use jsonrpc_ws_server::RequestContext;
 use tokio::task::JoinHandle;
 // Workspace uses
 use zksync_config::configs::api::{CommonApiConfig, JsonRpcConfig, TokenConfig};
+use zksync_eth_client::EthereumGateway;
 use zksync_mempool::MempoolTransactionRequest;
 use zksync_storage::ConnectionPool;
 use zksync_types::{tx::TxHash, ActionType, Address};
@@ -20,7 +21,9 @@ use zksync_utils::panic_notify::{spawn_panic_handler, ThreadPanicNotify};
 use crate::fee_ticker::FeeTicker;
 use crate::{
     api_server::event_notify::{start_sub_notifier, EventNotifierRequest, EventSubscribeRequest},
-    api_server::rpc_server::types::{ETHOpInfoResp, ResponseAccountState, TransactionInfoResp},
+    api_server::rpc_server::types::{
+        BlockInfoResp, ETHOpInfoResp, ResponseAccountState, TransactionInfoResp,
+    },
     signature_checker::VerifySignatureRequest,
 };
 
@@ -82,6 +85,27 @@ pub trait RpcPubSub {
         meta: Option<Self::Metadata>,
         subscription: SubscriptionId,
     ) -> Result<bool>;
+
+    /// Notifies about every new block. If `action_type` is not provided,
+    /// both committed and verified blocks are sent.
+    #[pubsub(
+        subscription = "blocks",
+        subscribe,
+        name = "blocks_subscribe",
+        alias("blocks_sub")
+    )]
+    fn subscribe_blocks(
+        &self,
+        meta: Self::Metadata,
+        subscriber: Subscriber<BlockInfoResp>,
+        action_type: Option<ActionType>,
+    );
+    #[pubsub(subscription = "blocks", unsubscribe, name = "blocks_unsubscribe")]
+    fn unsubscribe_blocks(
+        &self,
+        meta: Option<Self::Metadata>,
+        subscription: SubscriptionId,
+    ) -> Result<bool>;
 }
 
 impl RpcPubSub for RpcSubApp {
@@ -170,6 +194,33 @@ impl RpcPubSub for RpcSubApp {
             .unwrap_or_default();
         Ok(true)
     }
+
+    fn subscribe_blocks(
+        &self,
+        _meta: Self::Metadata,
+        subscriber: Subscriber<BlockInfoResp>,
+        action: Option<ActionType>,
+    ) {
+        self.event_sub_sender
+            .clone()
+            .try_send(EventNotifierRequest::Sub(EventSubscribeRequest::Block {
+                action,
+                subscriber,
+            }))
+            .unwrap_or_default();
+    }
+
+    fn unsubscribe_blocks(
+        &self,
+        _meta: Option<Self::Metadata>,
+        id: SubscriptionId,
+    ) -> Result<bool> {
+        self.event_sub_sender
+            .clone()
+            .try_send(EventNotifierRequest::Unsub(id))
+            .unwrap_or_default();
+        Ok(true)
+    }
 }
 
 struct RpcSubApp {
@@ -188,6 +239,8 @@ pub fn start_ws_server(
     miniblock_iteration_interval: Duration,
     mempool_tx_sender: mpsc::Sender<MempoolTransactionRequest>,
     confirmations_for_eth_event: u64,
+    block_chunk_sizes: Vec<usize>,
+    eth_client: EthereumGateway,
 ) -> JoinHandle<()> {
     let addr = config.ws_bind_addr();
 
@@ -207,8 +260,11 @@ pub fn start_ws_server(
         ticker,
         common_config,
         token_config,
+        config,
         confirmations_for_eth_event,
         mempool_tx_sender,
+        block_chunk_sizes,
+        eth_client,
     );
 
     let (handler, panic_sender) = spawn_panic_handler();
@@ -76,6 +76,8 @@ pub struct TxSender {
     // Limit the number of both transactions and Ethereum signatures per batch.
     pub max_number_of_transactions_per_batch: usize,
     pub max_number_of_authors_per_batch: usize,
+    /// Sizes of the blocks (in chunks) that can be created by the server.
+    pub block_chunk_sizes: Vec<usize>,
 
     pub current_subsidy_type: String,
     pub max_subsidy_usd: Ratio<BigUint>,
@@ -94,6 +96,20 @@ pub enum SubmitError {
     IncorrectTx(String),
     #[error("Transaction adding error: {0}.")]
     TxAdd(#[from] TxAddError),
+    #[error("Batch contains {submitted} transactions, while at most {limit} are allowed.")]
+    BatchTooBig { limit: usize, submitted: usize },
+    #[error(
+        "Batch requires at least {required} chunks, while the largest block has {max_block_chunks} chunks."
+    )]
+    BatchDoesNotFitBlock {
+        max_block_chunks: usize,
+        required: usize,
+    },
+    #[error("Transaction fee is too low: provided {provided}, required {required}.")]
+    TxFeeTooLow {
+        provided: BigUint,
+        required: BigUint,
+    },
     #[error("Chosen token is not suitable for paying fees.")]
     InappropriateFeeToken,
     // Not all TxAddErrors would apply to Toggle2FA, but
@@ -149,6 +165,7 @@ impl TxSender {
         config: &CommonApiConfig,
         token_config: &TokenConfig,
         mempool_tx_sender: mpsc::Sender<MempoolTransactionRequest>,
+        block_chunk_sizes: Vec<usize>,
     ) -> Self {
         let max_number_of_transactions_per_batch =
             config.max_number_of_transactions_per_batch as usize;
@@ -169,6 +186,7 @@ impl TxSender {
             fee_free_accounts: HashSet::from_iter(config.fee_free_accounts.clone()),
             max_number_of_transactions_per_batch,
             max_number_of_authors_per_batch,
+            block_chunk_sizes,
             current_subsidy_type: config.subsidy_name.clone(),
             max_subsidy_usd: config.max_subsidy_usd(),
             subsidized_ips: config.subsidized_ips.clone().into_iter().collect(),
@@ -186,7 +204,7 @@ impl TxSender {
     }
     async fn get_address_by_id(&self, id: AccountId) -> Result<Address, anyhow::Error> {
         self.pool
-            .access_storage()
+            .access_storage_tagged(super::API_CONNECTION_TAG)
             .await?
             .chain()
             .account_schema()
@@ -204,7 +222,7 @@ impl TxSender {
     async fn get_sender_type(&self, id: AccountId) -> Result<EthAccountType, anyhow::Error> {
         Ok(self
             .pool
-            .access_storage()
+            .access_storage_tagged(super::API_CONNECTION_TAG)
             .await?
             .chain()
             .account_schema()
@@ -243,7 +261,7 @@ impl TxSender {
             .await?;
 
         self.pool
-            .access_storage()
+            .access_storage_tagged(super::API_CONNECTION_TAG)
             .await
             .map_err(|_| SubmitError::Toggle2FA(Toggle2FAError::DbError))?
             .chain()
@@ -402,7 +420,7 @@ impl TxSender {
     ) -> Result<bool, anyhow::Error> {
         let subsidized_already = self
             .pool
-            .access_storage()
+            .access_storage_tagged(super::API_CONNECTION_TAG)
             .await?
             .misc_schema()
             .get_total_used_subsidy_for_type(&self.current_subsidy_type)
@@ -472,7 +490,7 @@ impl TxSender {
         };
 
         self.pool
-            .access_storage()
+            .access_storage_tagged(super::API_CONNECTION_TAG)
             .await?
             .misc_schema()
             .store_subsidy(subsidy)
@@ -556,11 +574,14 @@ impl TxSender {
 
             // Converting `BitUint` to `BigInt` is safe.
             let required_fee: BigDecimal = required_fee_data.total_fee.to_bigint().unwrap().into();
-            let provided_fee: BigDecimal = provided_fee.to_bigint().unwrap().into();
+            let provided_fee_decimal: BigDecimal = provided_fee.to_bigint().unwrap().into();
             // Scaling the fee required since the price may change between signing the transaction and sending it to the server.
-            let scaled_provided_fee = scale_user_fee_up(provided_fee);
+            let scaled_provided_fee = scale_user_fee_up(provided_fee_decimal);
             if required_fee >= scaled_provided_fee && should_enforce_fee {
-                return Err(SubmitError::TxAdd(TxAddError::TxFeeTooLow));
+                return Err(SubmitError::TxFeeTooLow {
+                    provided: provided_fee,
+                    required: required_fee_data.total_fee,
+                });
             }
         }
 
@@ -646,7 +667,23 @@ impl TxSender {
         // we don't want to verify huge batches as long as this operation
         // is expensive.
         if txs.len() > self.max_number_of_transactions_per_batch {
-            return Err(SubmitError::TxAdd(TxAddError::BatchTooBig));
+            return Err(SubmitError::BatchTooBig {
+                limit: self.max_number_of_transactions_per_batch,
+                submitted: txs.len(),
+            });
+        }
+        // The exact number of chunks depends on the state (e.g. whether the transfer
+        // recipient exists), so here we only check the lower bound. The precise check
+        // is done by the mempool.
+        let max_block_chunks = self.block_chunk_sizes.iter().max().copied();
+        let required_chunks: usize = txs.iter().map(|tx| tx.tx.min_chunks()).sum();
+        if let Some(max_block_chunks) = max_block_chunks {
+            if required_chunks > max_block_chunks {
+                return Err(SubmitError::BatchDoesNotFitBlock {
+                    max_block_chunks,
+                    required: required_chunks,
+                });
+            }
         }
 
         for tx in &txs {
@@ -942,7 +979,7 @@ impl TxSender {
     ) -> Result<(), SubmitError> {
         let mut storage = self
             .pool
-            .access_storage()
+            .access_storage_tagged(super::API_CONNECTION_TAG)
             .await
             .map_err(SubmitError::internal)?;
 
@@ -1001,7 +1038,7 @@ impl TxSender {
         // from the db.
         let mut storage = self
             .pool
-            .access_storage()
+            .access_storage_tagged(super::API_CONNECTION_TAG)
             .await
             .map_err(SubmitError::internal)?;
 
@@ -55,7 +55,7 @@ impl Web3RpcApp {
 
     async fn access_storage(&self) -> Result<StorageProcessor<'_>> {
         self.connection_pool
-            .access_storage()
+            .access_storage_tagged(super::API_CONNECTION_TAG)
             .await
             .map_err(|_| Error::internal_error())
     }
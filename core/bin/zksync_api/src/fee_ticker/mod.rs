@@ -8,6 +8,8 @@ use std::collections::{HashMap, HashSet};
 use std::convert::TryFrom;
 use std::fmt::Display;
 use std::iter::FromIterator;
+#[cfg(any(test, feature = "ticker_price_override"))]
+use std::sync::{Arc, RwLock};
 use std::time::Duration;
 
 // External deps
@@ -29,10 +31,10 @@ use zksync_storage::ConnectionPool;
 use zksync_token_db_cache::TokenDBCache;
 use zksync_types::{
     gas_counter::GasCounter, tokens::ChangePubKeyFeeTypeArg, tx::ChangePubKeyType, Address,
-    BatchFee, ChangePubKeyOp, Fee, MintNFTOp, OutputFeeType, SwapOp, Token, TokenId, TokenLike,
-    TransferOp, TransferToNewOp, TxFeeTypes, WithdrawNFTOp, WithdrawOp,
+    BatchFee, Fee, OutputFeeType, Token, TokenId, TokenLike, TokenPrice, TransferToNewOp,
+    TxFeeTypes,
 };
-use zksync_utils::{big_decimal_to_ratio, ratio_to_big_decimal};
+use zksync_utils::{big_decimal_to_ratio, ratio_to_big_decimal, BigUintSerdeAsRadix10Str};
 
 // Local deps
 use crate::fee_ticker::constants::AMORTIZED_COST_PER_CHUNK;
@@ -45,6 +47,8 @@ use crate::fee_ticker::{
     },
     validator::{watcher::UniswapTokenWatcher, MarketUpdater},
 };
+#[cfg(any(test, feature = "ticker_price_override"))]
+use crate::fee_ticker::{ticker_info::FixedTickerInfo, validator::cache::TokenInMemoryCache};
 
 mod constants;
 mod ticker_api;
@@ -135,6 +139,22 @@ impl GasOperationsCost {
     }
 }
 
+/// Returns the number of chunks occupied by the operation of the given fee type.
+fn output_fee_type_chunks(fee_type: OutputFeeType) -> usize {
+    let tx_type = match fee_type {
+        OutputFeeType::TransferToNew => return TransferToNewOp::CHUNKS,
+        OutputFeeType::Transfer => TxFeeTypes::Transfer,
+        OutputFeeType::Withdraw => TxFeeTypes::Withdraw,
+        OutputFeeType::FastWithdraw => TxFeeTypes::FastWithdraw,
+        OutputFeeType::WithdrawNFT => TxFeeTypes::WithdrawNFT,
+        OutputFeeType::FastWithdrawNFT => TxFeeTypes::FastWithdrawNFT,
+        OutputFeeType::Swap => TxFeeTypes::Swap,
+        OutputFeeType::MintNFT => TxFeeTypes::MintNFT,
+        OutputFeeType::ChangePubKey(arg) => TxFeeTypes::ChangePubKey(arg),
+    };
+    tx_type.chunks()
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct TickerConfig {
     pub zkp_cost_chunk_usd: Ratio<BigUint>,
@@ -145,6 +165,37 @@ pub struct TickerConfig {
     pub subsidy_cpk_price_usd: Ratio<BigUint>,
 }
 
+/// Parameters used by the ticker to calculate the fees, see `FeeTicker::fee_params`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FeeParams {
+    /// Current gas price on L1.
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub gas_price_wei: BigUint,
+    /// Gas price used in the fee calculation. It's greater than the current gas price
+    /// to account for the gas price changes before the operation is sent to L1.
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub scaled_gas_price_wei: BigUint,
+    /// Coefficient applied to the gas part of the fee of the L2 operations (transfers, swaps and NFT minting).
+    pub scale_fee_coefficient: BigDecimal,
+    /// Cost of the zero-knowledge proof for a single chunk, in USD.
+    pub zkp_cost_chunk_usd: BigDecimal,
+    /// Costs of the operations, ordered by the amount of chunks.
+    pub op_costs: Vec<OpFeeParams>,
+}
+
+/// Cost of a single operation type, see `FeeParams`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct OpFeeParams {
+    pub fee_type: OutputFeeType,
+    pub chunks: usize,
+    /// Gas amount paid for the operation on L1. For fast withdrawals this is
+    /// an estimation, the actual cost depends on the current block.
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub gas_cost: BigUint,
+}
+
 #[derive(Debug, PartialEq, Eq)]
 pub enum TokenPriceRequestType {
     USDForOneWei,
@@ -194,6 +245,9 @@ pub struct FeeTicker {
     info: Box<dyn FeeTickerInfo>,
     config: TickerConfig,
     validator: FeeTokenValidator,
+    /// Prices set via `FeeTicker::set_token_price`. Shared between the ticker clones.
+    #[cfg(any(test, feature = "ticker_price_override"))]
+    price_overrides: Arc<RwLock<HashMap<TokenId, Ratio<BigUint>>>>,
 }
 
 const CPK_CREATE2_FEE_TYPE: OutputFeeType = OutputFeeType::ChangePubKey(
@@ -255,9 +309,23 @@ impl FeeTicker {
             info,
             config,
             validator,
+            #[cfg(any(test, feature = "ticker_price_override"))]
+            price_overrides: Default::default(),
         }
     }
 
+    /// Makes the ticker use the fixed USD price for one token (not for one wei) instead of the one
+    /// provided by the ticker info, so the fee computation becomes deterministic.
+    ///
+    /// Test-only: available with the `ticker_price_override` feature and must never be used in production.
+    #[cfg(any(test, feature = "ticker_price_override"))]
+    pub fn set_token_price(&self, token: TokenId, usd_price: Ratio<BigUint>) {
+        self.price_overrides
+            .write()
+            .expect("price overrides lock is poisoned")
+            .insert(token, usd_price);
+    }
+
     pub fn new_with_default_validator(
         info: Box<dyn FeeTickerInfo>,
         config: zksync_config::TickerConfig,
@@ -265,7 +333,53 @@ impl FeeTicker {
         connection_pool: ConnectionPool,
     ) -> Self {
         let cache = (connection_pool, TokenDBCache::new(TOKEN_INVALIDATE_CACHE));
-        let ticker_config = TickerConfig {
+        let ticker_config = Self::ticker_config(&config, max_blocks_to_aggregate);
+        let validator = FeeTokenValidator::new(
+            cache,
+            chrono::Duration::seconds(config.available_liquidity_seconds as i64),
+            BigDecimal::try_from(config.liquidity_volume).expect("Valid f64 for decimal"),
+            HashSet::from_iter(config.unconditionally_valid_tokens),
+        );
+        Self::new(info, ticker_config, validator)
+    }
+
+    /// Creates a ticker that depends neither on the database nor on the token price APIs:
+    /// it only knows the provided tokens (all of them are accepted for fees), considers every account
+    /// existing and uses the fixed gas price. Token prices must be set via `set_token_price`.
+    ///
+    /// Test-only: available with the `ticker_price_override` feature and must never be used in production.
+    #[cfg(any(test, feature = "ticker_price_override"))]
+    pub fn new_with_fixed_prices(
+        tokens: Vec<Token>,
+        gas_price_wei: BigUint,
+        config: &zksync_config::TickerConfig,
+        max_blocks_to_aggregate: u32,
+    ) -> Self {
+        let fee_tokens = tokens.iter().map(|token| token.address).collect();
+        let cache = TokenInMemoryCache::new().with_tokens(
+            tokens
+                .iter()
+                .map(|token| (TokenLike::Id(token.id), token.clone()))
+                .collect(),
+        );
+        let validator = FeeTokenValidator::new(
+            cache,
+            chrono::Duration::zero(),
+            BigDecimal::from(0),
+            fee_tokens,
+        );
+        Self::new(
+            Box::new(FixedTickerInfo::new(tokens, gas_price_wei)),
+            Self::ticker_config(config, max_blocks_to_aggregate),
+            validator,
+        )
+    }
+
+    fn ticker_config(
+        config: &zksync_config::TickerConfig,
+        max_blocks_to_aggregate: u32,
+    ) -> TickerConfig {
+        TickerConfig {
             zkp_cost_chunk_usd: Ratio::from_integer(BigUint::from(10u32).pow(3u32)).inv(),
             gas_cost_tx: GasOperationsCost::from_constants(config.fast_processing_coeff),
             tokens_risk_factors: HashMap::new(),
@@ -275,14 +389,7 @@ impl FeeTicker {
             ),
             max_blocks_to_aggregate,
             subsidy_cpk_price_usd: config.subsidy_cpk_price_usd(),
-        };
-        let validator = FeeTokenValidator::new(
-            cache,
-            chrono::Duration::seconds(config.available_liquidity_seconds as i64),
-            BigDecimal::try_from(config.liquidity_volume).expect("Valid f64 for decimal"),
-            HashSet::from_iter(config.unconditionally_valid_tokens),
-        );
-        Self::new(info, ticker_config, validator)
+        }
     }
 }
 
@@ -294,6 +401,33 @@ impl FeeTicker {
         gas_price * BigUint::from(130u32) / BigUint::from(100u32)
     }
 
+    /// Returns the current parameters of the fee calculation.
+    pub async fn fee_params(&self) -> anyhow::Result<FeeParams> {
+        let gas_price_wei = self.info.get_gas_price_wei().await?;
+        let scaled_gas_price_wei = Self::risk_gas_price_estimate(gas_price_wei.clone());
+
+        let mut op_costs: Vec<_> = self
+            .config
+            .gas_cost_tx
+            .standard_cost
+            .iter()
+            .map(|(fee_type, gas_cost)| OpFeeParams {
+                fee_type: *fee_type,
+                chunks: output_fee_type_chunks(*fee_type),
+                gas_cost: gas_cost.clone(),
+            })
+            .collect();
+        op_costs.sort_by(|a, b| (a.chunks, &a.gas_cost).cmp(&(b.chunks, &b.gas_cost)));
+
+        Ok(FeeParams {
+            gas_price_wei,
+            scaled_gas_price_wei,
+            scale_fee_coefficient: ratio_to_big_decimal(&self.config.scale_fee_coefficient, 18),
+            zkp_cost_chunk_usd: ratio_to_big_decimal(&self.config.zkp_cost_chunk_usd, 18),
+            op_costs,
+        })
+    }
+
     pub async fn get_token_price(
         &self,
         token: TokenLike,
@@ -314,8 +448,7 @@ impl FeeTicker {
         };
 
         let res = self
-            .info
-            .get_last_token_price(token)
+            .last_token_price(token)
             .await
             .map(|price| ratio_to_big_decimal(&(price.usd_price / factor), 100));
         metrics::histogram!("ticker.get_token_price", start.elapsed());
@@ -521,8 +654,7 @@ impl FeeTicker {
     pub async fn wei_price_usd(&self) -> anyhow::Result<Ratio<BigUint>> {
         let start = Instant::now();
         let res = self
-            .info
-            .get_last_token_price(TokenLike::Id(TokenId(0)))
+            .last_token_price(TokenLike::Id(TokenId(0)))
             .await?
             .usd_price
             / BigUint::from(10u32).pow(18u32);
@@ -540,8 +672,7 @@ impl FeeTicker {
             .unwrap_or_else(|| Ratio::from_integer(1u32.into()));
 
         let token_price_usd = self
-            .info
-            .get_last_token_price(TokenLike::Id(token.id))
+            .last_token_price(TokenLike::Id(token.id))
             .await?
             .usd_price
             / BigUint::from(10u32).pow(u32::from(token.decimals));
@@ -552,6 +683,36 @@ impl FeeTicker {
             .ok_or_else(|| anyhow::format_err!("Token is not acceptable for fee"))
     }
 
+    async fn last_token_price(&self, token: TokenLike) -> Result<TokenPrice, PriceError> {
+        #[cfg(any(test, feature = "ticker_price_override"))]
+        {
+            let token_id = match &token {
+                TokenLike::Id(token_id) => *token_id,
+                _ => {
+                    self.info
+                        .get_token(token.clone())
+                        .await
+                        .map_err(PriceError::db_error)?
+                        .id
+                }
+            };
+            let usd_price = self
+                .price_overrides
+                .read()
+                .expect("price overrides lock is poisoned")
+                .get(&token_id)
+                .cloned();
+            if let Some(usd_price) = usd_price {
+                return Ok(TokenPrice {
+                    usd_price,
+                    last_updated: chrono::Utc::now(),
+                });
+            }
+        }
+
+        self.info.get_last_token_price(token).await
+    }
+
     /// Returns `true` if account does not yet exist in the zkSync network.
     pub async fn is_account_new(&self, address: Address) -> anyhow::Result<bool> {
         self.info.is_account_new(address).await
@@ -563,23 +724,27 @@ impl FeeTicker {
         recipient: Address,
     ) -> anyhow::Result<(OutputFeeType, BigUint, BigUint)> {
         let start = Instant::now();
-        let (fee_type, op_chunks) = match tx_type {
-            TxFeeTypes::Withdraw => (OutputFeeType::Withdraw, WithdrawOp::CHUNKS),
-            TxFeeTypes::FastWithdraw => (OutputFeeType::FastWithdraw, WithdrawOp::CHUNKS),
-            TxFeeTypes::WithdrawNFT => (OutputFeeType::WithdrawNFT, WithdrawNFTOp::CHUNKS),
-            TxFeeTypes::FastWithdrawNFT => (OutputFeeType::FastWithdrawNFT, WithdrawNFTOp::CHUNKS),
+        let fee_type = match tx_type {
+            TxFeeTypes::Withdraw => OutputFeeType::Withdraw,
+            TxFeeTypes::FastWithdraw => OutputFeeType::FastWithdraw,
+            TxFeeTypes::WithdrawNFT => OutputFeeType::WithdrawNFT,
+            TxFeeTypes::FastWithdrawNFT => OutputFeeType::FastWithdrawNFT,
             TxFeeTypes::Transfer => {
                 if self.is_account_new(recipient).await? {
-                    (OutputFeeType::TransferToNew, TransferToNewOp::CHUNKS)
+                    OutputFeeType::TransferToNew
                 } else {
-                    (OutputFeeType::Transfer, TransferOp::CHUNKS)
+                    OutputFeeType::Transfer
                 }
             }
-            TxFeeTypes::Swap => (OutputFeeType::Swap, SwapOp::CHUNKS),
-            TxFeeTypes::ChangePubKey(arg) => {
-                (OutputFeeType::ChangePubKey(arg), ChangePubKeyOp::CHUNKS)
-            }
-            TxFeeTypes::MintNFT => (OutputFeeType::MintNFT, MintNFTOp::CHUNKS),
+            TxFeeTypes::Swap => OutputFeeType::Swap,
+            TxFeeTypes::ChangePubKey(arg) => OutputFeeType::ChangePubKey(arg),
+            TxFeeTypes::MintNFT => OutputFeeType::MintNFT,
+        };
+        // Transfer to a new account is executed as `TransferToNewOp`, see `TxFeeTypes::chunks`.
+        let op_chunks = if fee_type == OutputFeeType::TransferToNew {
+            TransferToNewOp::CHUNKS
+        } else {
+            tx_type.chunks()
         };
 
         let gas_tx_amount = if matches!(
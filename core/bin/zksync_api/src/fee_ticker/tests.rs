@@ -708,3 +708,101 @@ fn test_zero_price_token_fee() {
     ))
     .unwrap_err();
 }
+
+#[test]
+fn test_token_price_override() {
+    let validator = FeeTokenValidator::new(
+        TokenInMemoryCache::new(),
+        chrono::Duration::seconds(100),
+        BigDecimal::from(100),
+        Default::default(),
+    );
+
+    let config = get_test_ticker_config();
+    let ticker = FeeTicker::new(Box::new(MockTickerInfo::default()), config, validator);
+
+    // Both tokens have the same risk factor and precision, so with the same price
+    // the fees should be equal.
+    let zero_price_token = TestToken::zero_price();
+    let expensive_token = TestToken::expensive();
+
+    ticker.set_token_price(zero_price_token.id, expensive_token.price_usd.clone());
+
+    let token_price = block_on(ticker.get_token_price(
+        zero_price_token.id.into(),
+        TokenPriceRequestType::USDForOneToken,
+    ))
+    .unwrap();
+    assert_eq!(
+        token_price,
+        ratio_to_big_decimal(&expensive_token.price_usd, 100)
+    );
+
+    for tx_type in &[TxFeeTypes::Transfer, TxFeeTypes::Withdraw] {
+        let fee = block_on(ticker.get_fee_from_ticker_in_wei(
+            *tx_type,
+            zero_price_token.id.into(),
+            Address::default(),
+        ))
+        .unwrap();
+        let expected_fee = block_on(ticker.get_fee_from_ticker_in_wei(
+            *tx_type,
+            expensive_token.id.into(),
+            Address::default(),
+        ))
+        .unwrap();
+        assert_eq!(fee.normal_fee.total_fee, expected_fee.normal_fee.total_fee);
+    }
+}
+
+#[test]
+fn test_fee_params() {
+    let validator = FeeTokenValidator::new(
+        TokenInMemoryCache::new(),
+        chrono::Duration::seconds(100),
+        BigDecimal::from(100),
+        Default::default(),
+    );
+
+    let config = get_test_ticker_config();
+    let ticker = FeeTicker::new(Box::new(MockTickerInfo::default()), config, validator);
+
+    let params = block_on(ticker.fee_params()).unwrap();
+    let gas_price_wei = BigUint::from(10u32).pow(7u32);
+    assert_eq!(
+        params.scaled_gas_price_wei,
+        &gas_price_wei * BigUint::from(130u32) / BigUint::from(100u32)
+    );
+    assert_eq!(params.gas_price_wei, gas_price_wei);
+    assert_eq!(
+        params.scale_fee_coefficient,
+        BigDecimal::from_str("1.5").unwrap()
+    );
+    assert_eq!(
+        params.zkp_cost_chunk_usd,
+        BigDecimal::from_str("0.001").unwrap()
+    );
+
+    let transfer = params
+        .op_costs
+        .iter()
+        .find(|op| op.fee_type == OutputFeeType::Transfer)
+        .unwrap();
+    assert_eq!(transfer.chunks, TxFeeTypes::Transfer.chunks());
+    assert_eq!(
+        transfer.gas_cost,
+        BigUint::from(constants::BASE_TRANSFER_COST)
+    );
+
+    let transfer_to_new = params
+        .op_costs
+        .iter()
+        .find(|op| op.fee_type == OutputFeeType::TransferToNew)
+        .unwrap();
+    assert_eq!(transfer_to_new.chunks, TransferToNewOp::CHUNKS);
+
+    assert!(params
+        .op_costs
+        .windows(2)
+        .all(|pair| pair[0].chunks <= pair[1].chunks));
+}
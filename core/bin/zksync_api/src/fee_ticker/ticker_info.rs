@@ -65,6 +65,77 @@ pub trait FeeTickerInfo: FeeTickerClone + Send + Sync + 'static {
     fn into_any(self: Box<Self>) -> Box<dyn Any>;
 }
 
+/// Ticker info with the fixed set of tokens and gas price, which doesn't access the database.
+/// It doesn't know any token prices, so they must be set via `FeeTicker::set_token_price`.
+///
+/// Test-only: available with the `ticker_price_override` feature, see `FeeTicker::new_with_fixed_prices`.
+#[cfg(any(test, feature = "ticker_price_override"))]
+#[derive(Debug, Clone)]
+pub struct FixedTickerInfo {
+    tokens: Vec<Token>,
+    gas_price_wei: BigUint,
+}
+
+#[cfg(any(test, feature = "ticker_price_override"))]
+impl FixedTickerInfo {
+    pub fn new(tokens: Vec<Token>, gas_price_wei: BigUint) -> Self {
+        Self {
+            tokens,
+            gas_price_wei,
+        }
+    }
+}
+
+#[cfg(any(test, feature = "ticker_price_override"))]
+#[async_trait]
+impl FeeTickerInfo for FixedTickerInfo {
+    async fn is_account_new(&self, _address: Address) -> anyhow::Result<bool> {
+        Ok(false)
+    }
+
+    async fn blocks_in_future_aggregated_operations(
+        &self,
+    ) -> anyhow::Result<BlocksInFutureAggregatedOperations> {
+        Ok(BlocksInFutureAggregatedOperations {
+            blocks_to_commit: 0,
+            blocks_to_prove: 0,
+            blocks_to_execute: 0,
+        })
+    }
+
+    async fn remaining_chunks_in_pending_block(&self) -> anyhow::Result<Option<usize>> {
+        Ok(None)
+    }
+
+    async fn get_last_token_price(&self, token: TokenLike) -> Result<TokenPrice, PriceError> {
+        Err(PriceError::token_not_found(format!(
+            "Price of the token {:?} is not set",
+            token
+        )))
+    }
+
+    async fn get_gas_price_wei(&self) -> Result<BigUint, anyhow::Error> {
+        Ok(self.gas_price_wei.clone())
+    }
+
+    async fn get_token(&self, token: TokenLike) -> Result<Token, anyhow::Error> {
+        self.tokens
+            .iter()
+            .find(|known| match &token {
+                TokenLike::Id(id) => known.id == *id,
+                TokenLike::Address(address) => known.address == *address,
+                TokenLike::Symbol(symbol) => known.symbol.eq_ignore_ascii_case(symbol),
+            })
+            .cloned()
+            .ok_or_else(|| format_err!("Token not found: {:?}", token))
+    }
+
+    #[cfg(test)]
+    fn into_any(self: Box<Self>) -> Box<dyn Any> {
+        self
+    }
+}
+
 #[derive(Clone)]
 pub struct TickerInfo {
     db: ConnectionPool,
@@ -1,6 +1,6 @@
-#[cfg(test)]
+#[cfg(any(test, feature = "ticker_price_override"))]
 use std::{collections::HashMap, sync::Arc};
-#[cfg(test)]
+#[cfg(any(test, feature = "ticker_price_override"))]
 use tokio::sync::Mutex;
 use zksync_token_db_cache::TokenDBCache;
 
@@ -10,7 +10,7 @@ use zksync_types::{tokens::TokenMarketVolume, Token, TokenId, TokenLike};
 #[derive(Debug, Clone)]
 pub(crate) enum TokenCacheWrapper {
     DB(TokenInDBCache),
-    #[cfg(test)]
+    #[cfg(any(test, feature = "ticker_price_override"))]
     Memory(TokenInMemoryCache),
 }
 
@@ -21,7 +21,7 @@ pub(crate) struct TokenInDBCache {
 }
 
 #[derive(Debug, Clone, Default)]
-#[cfg(test)]
+#[cfg(any(test, feature = "ticker_price_override"))]
 pub struct TokenInMemoryCache {
     tokens: Arc<Mutex<HashMap<TokenLike, Token>>>,
     market: Arc<Mutex<HashMap<TokenId, TokenMarketVolume>>>,
@@ -33,7 +33,7 @@ impl TokenInDBCache {
     }
 }
 
-#[cfg(test)]
+#[cfg(any(test, feature = "ticker_price_override"))]
 impl TokenInMemoryCache {
     pub fn new() -> Self {
         Self::default()
@@ -54,7 +54,7 @@ impl TokenInMemoryCache {
     }
 }
 
-#[cfg(test)]
+#[cfg(any(test, feature = "ticker_price_override"))]
 impl From<TokenInMemoryCache> for TokenCacheWrapper {
     fn from(cache: TokenInMemoryCache) -> Self {
         Self::Memory(cache)
@@ -87,7 +87,7 @@ impl TokenCacheWrapper {
                     .get_token(&mut cache.pool.access_storage().await?, token_like)
                     .await
             }
-            #[cfg(test)]
+            #[cfg(any(test, feature = "ticker_price_override"))]
             Self::Memory(cache) => Ok(cache.tokens.lock().await.get(&token_like).cloned()),
         }
     }
@@ -104,7 +104,7 @@ impl TokenCacheWrapper {
                 )
                 .await
             }
-            #[cfg(test)]
+            #[cfg(any(test, feature = "ticker_price_override"))]
             Self::Memory(cache) => Ok(cache.market.lock().await.get(&token_id).cloned()),
         }
     }
@@ -123,7 +123,7 @@ impl TokenCacheWrapper {
                 )
                 .await
             }
-            #[cfg(test)]
+            #[cfg(any(test, feature = "ticker_price_override"))]
             Self::Memory(cache) => {
                 cache.market.lock().await.insert(token_id, market_volume);
                 Ok(())
@@ -135,7 +135,7 @@ impl TokenCacheWrapper {
             Self::DB(cache) => {
                 TokenDBCache::get_all_tokens(&mut cache.pool.access_storage().await?).await
             }
-            #[cfg(test)]
+            #[cfg(any(test, feature = "ticker_price_override"))]
             Self::Memory(cache) => Ok(cache
                 .tokens
                 .lock()
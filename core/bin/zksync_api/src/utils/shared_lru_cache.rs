@@ -47,4 +47,7 @@ impl<K: Eq + Hash, V: Clone> AsyncLruCache<K, V> {
     pub async fn get(&self, key: &K) -> Option<V> {
         self.0.lock().await.get_mut(key).cloned()
     }
+    pub async fn remove(&self, key: &K) -> Option<V> {
+        self.0.lock().await.remove(key)
+    }
 }
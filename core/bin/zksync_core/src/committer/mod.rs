@@ -21,6 +21,9 @@ mod aggregated_committer;
 // Usually we don't change them, so we can invalidate the cache once an hour.
 const TOKEN_INVALIDATE_CACHE: Duration = Duration::from_secs(60 * 60);
 
+/// Tag of the database connections held by the committer.
+const COMMITTER_CONNECTION_TAG: &str = "committer";
+
 #[derive(Debug)]
 pub enum CommitRequest {
     PendingBlock((PendingBlock, AppliedUpdatesRequest)),
@@ -58,7 +61,12 @@ async fn handle_new_commit_task(mut rx_for_ops: Receiver<CommitRequest>, pool: C
     vlog::info!("Run committer");
     let mut token_db_cache = TokenDBCache::new(TOKEN_INVALIDATE_CACHE);
     token_db_cache
-        .fill_token_cache(&mut pool.access_storage().await.unwrap())
+        .fill_token_cache(
+            &mut pool
+                .access_storage_tagged(COMMITTER_CONNECTION_TAG)
+                .await
+                .unwrap(),
+        )
         .await;
     while let Some(request) = rx_for_ops.next().await {
         match request {
@@ -87,7 +95,7 @@ async fn handle_new_commit_task(mut rx_for_ops: Receiver<CommitRequest>, pool: C
 async fn remove_reverted_block(block_number: BlockNumber, pool: &ConnectionPool) {
     let start = Instant::now();
     let mut storage = pool
-        .access_storage()
+        .access_storage_tagged(COMMITTER_CONNECTION_TAG)
         .await
         .expect("db connection fail for committer");
     storage
@@ -106,7 +114,7 @@ async fn save_pending_block(
 ) {
     let start = Instant::now();
     let mut storage = pool
-        .access_storage()
+        .access_storage_tagged(COMMITTER_CONNECTION_TAG)
         .await
         .expect("db connection fail for committer");
 
@@ -158,7 +166,7 @@ async fn seal_incomplete_block(
     } = block_commit_request;
 
     let mut storage = pool
-        .access_storage()
+        .access_storage_tagged(COMMITTER_CONNECTION_TAG)
         .await
         .expect("db connection fail for committer");
 
@@ -233,7 +241,7 @@ async fn finish_block(request: BlockFinishRequest, pool: &ConnectionPool) {
     } = request;
 
     let mut storage = pool
-        .access_storage()
+        .access_storage_tagged(COMMITTER_CONNECTION_TAG)
         .await
         .expect("db connection fail for committer");
 
@@ -287,7 +295,7 @@ async fn poll_for_new_proofs_task(pool: ConnectionPool, config: ChainConfig) {
         timer.tick().await;
 
         let mut storage = pool
-            .access_storage()
+            .access_storage_tagged(COMMITTER_CONNECTION_TAG)
             .await
             .expect("db connection failed for committer");
 
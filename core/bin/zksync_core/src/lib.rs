@@ -3,7 +3,10 @@ use crate::state_keeper::ZkSyncStateInitParams;
 use crate::{
     committer::run_committer,
     eth_watch::start_eth_watch,
-    state_keeper::{start_root_hash_calculator, start_state_keeper, ZkSyncStateKeeper},
+    state_keeper::{
+        start_root_hash_calculator, start_state_keeper, ZkSyncStateKeeper,
+        STATE_KEEPER_CONNECTION_TAG,
+    },
     token_handler::run_token_handler,
 };
 use futures::{channel::mpsc, future};
@@ -130,7 +133,9 @@ pub async fn run_core(
     .await;
 
     // Insert pending withdrawals into database (if required)
-    let mut storage_processor = connection_pool.access_storage().await?;
+    let mut storage_processor = connection_pool
+        .access_storage_tagged(STATE_KEEPER_CONNECTION_TAG)
+        .await?;
 
     // Start state keeper and root hash calculator.
     let state_keeper_init = ZkSyncStateInitParams::restore_from_db(
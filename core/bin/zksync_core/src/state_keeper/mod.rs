@@ -7,6 +7,8 @@ use futures::{
     stream::StreamExt,
     SinkExt,
 };
+#[cfg(feature = "testkit")]
+use num::BigUint;
 use tokio::task::JoinHandle;
 use tokio::time;
 // Workspace uses
@@ -20,8 +22,10 @@ use zksync_types::{
     gas_counter::GasCounter,
     mempool::SignedTxVariant,
     tx::ZkSyncTx,
-    Address, PriorityOp, SignedZkSyncTx,
+    Address, PriorityOp, SignedZkSyncTx, ZkSyncOp,
 };
+#[cfg(feature = "testkit")]
+use zksync_types::{AccountId, AccountUpdate, Fr, TokenId};
 // Local uses
 use self::{
     pending_block::PendingBlock,
@@ -50,6 +54,9 @@ mod utils;
 #[cfg(test)]
 mod tests;
 
+/// Tag of the database connections used to initialize the state keeper.
+pub const STATE_KEEPER_CONNECTION_TAG: &str = "state_keeper";
+
 /// Responsible for tx processing and block forming.
 pub struct ZkSyncStateKeeper {
     /// Current plasma state
@@ -270,10 +277,61 @@ impl ZkSyncStateKeeper {
                 StateKeeperTestkitRequest::ExecuteMiniBlock(block) => {
                     self.execute_proposed_block(block).await;
                 }
+                StateKeeperTestkitRequest::ExecuteMiniBlockWithTimestamp(block, timestamp) => {
+                    assert!(
+                        self.pending_block.success_operations.is_empty(),
+                        "Timestamp of the pending block can't be changed after operations were executed"
+                    );
+                    self.pending_block.timestamp = timestamp;
+                    self.apply_proposed_block(block).await;
+                }
+                StateKeeperTestkitRequest::SetFeeAccount(fee_account_id) => {
+                    self.config.fee_account_id = fee_account_id;
+                }
+                StateKeeperTestkitRequest::SetMaxWithdrawalsPerBlock(max_withdrawals) => {
+                    self.config.max_withdrawals_per_block = max_withdrawals;
+                }
+                StateKeeperTestkitRequest::GetRoot(sender) => {
+                    sender.send(self.state.root_hash()).unwrap_or_default();
+                }
+                StateKeeperTestkitRequest::SetBalance {
+                    account_id,
+                    token,
+                    amount,
+                    resp,
+                } => {
+                    let root_hash = self.set_balance(account_id, token, amount);
+                    resp.send(root_hash).unwrap_or_default();
+                }
             }
         }
     }
 
+    /// Sets the balance of the account bypassing the transaction processing and returns the new root hash.
+    /// The update is added to the pending block, so it's persisted and applied by the root hash calculator
+    /// together with the rest of the block updates. Such a block cannot be proven, thus it's only
+    /// available for the testkit.
+    #[cfg(feature = "testkit")]
+    fn set_balance(
+        &mut self,
+        account_id: AccountId,
+        token: TokenId,
+        amount: BigUint,
+    ) -> Option<Fr> {
+        let account = self.state.get_account(account_id)?;
+        let updates = vec![(
+            account_id,
+            AccountUpdate::UpdateBalance {
+                old_nonce: account.nonce,
+                new_nonce: account.nonce,
+                balance_update: (token, account.get_balance(token), amount),
+            },
+        )];
+        self.state.apply_account_updates(updates.clone());
+        self.pending_block.account_updates.extend(updates);
+        Some(self.state.root_hash())
+    }
+
     // Generate and execute new miniblock every miniblock_interval
     async fn run(mut self, miniblock_interval: Duration) {
         let mut timer = time::interval(miniblock_interval);
@@ -375,14 +433,19 @@ impl ZkSyncStateKeeper {
     }
 
     async fn execute_proposed_block(&mut self, proposed_block: ProposedBlock) {
-        let start = Instant::now();
-        let mut executed_ops = Vec::new();
-
         // If pending block is empty we update timestamp
         if self.pending_block.success_operations.is_empty() {
             self.pending_block.timestamp = system_time_timestamp();
         }
 
+        self.apply_proposed_block(proposed_block).await;
+    }
+
+    /// Executes the proposed block with the current timestamp of the pending block.
+    async fn apply_proposed_block(&mut self, proposed_block: ProposedBlock) {
+        let start = Instant::now();
+        let mut executed_ops = Vec::new();
+
         // We want to store this variable before moving anything from the pending block.
         let empty_proposed_block = proposed_block.is_empty();
 
@@ -580,6 +643,10 @@ impl ZkSyncStateKeeper {
             return ApplyOutcome::NotIncluded;
         }
 
+        if self.withdrawals_limit_reached(&ops) {
+            return ApplyOutcome::NotIncluded;
+        }
+
         let all_updates = self
             .state
             .execute_txs_batch(txs, self.pending_block.timestamp);
@@ -646,6 +713,21 @@ impl ZkSyncStateKeeper {
         ApplyOutcome::Included(executed_operations)
     }
 
+    /// Checks whether the withdrawals among `ops` exceed the configured limit of withdrawals in the pending block.
+    fn withdrawals_limit_reached(&self, ops: &[ZkSyncOp]) -> bool {
+        let max_withdrawals = match self.config.max_withdrawals_per_block {
+            Some(max_withdrawals) => max_withdrawals,
+            None => return false,
+        };
+        let new_withdrawals = ops
+            .iter()
+            .filter(|op| op.withdrawal_data().is_some())
+            .count();
+
+        new_withdrawals > 0
+            && self.pending_block.withdrawals_count() + new_withdrawals > max_withdrawals
+    }
+
     fn apply_tx(&mut self, tx: &SignedZkSyncTx) -> ApplyOutcome<ExecutedOperations> {
         let start = Instant::now();
         let chunks_needed = self.state.chunks_for_tx(tx);
@@ -662,6 +744,10 @@ impl ZkSyncStateKeeper {
         if let Ok(non_executed_op) = non_executed_op {
             // We only care about successful conversions, since if conversion failed,
             // then transaction will fail as well (as it shares the same code base).
+            if self.withdrawals_limit_reached(std::slice::from_ref(&non_executed_op)) {
+                // The block has the maximum allowed amount of withdrawals, seal it.
+                return ApplyOutcome::NotIncluded;
+            }
             if !self
                 .pending_block
                 .gas_counter
@@ -77,6 +77,15 @@ impl PendingBlock {
         self.failed_txs.is_empty() && self.success_operations.is_empty()
     }
 
+    /// Returns the amount of successfully executed withdrawal operations.
+    pub(super) fn withdrawals_count(&self) -> usize {
+        self.success_operations
+            .iter()
+            .filter_map(|op| op.get_executed_op())
+            .filter(|op| op.withdrawal_data().is_some())
+            .count()
+    }
+
     pub(super) fn should_seal(&self, miniblock_iterations: usize) -> bool {
         // `>=` in condition since iterations start with 0.
         self.chunks_left == 0 || self.pending_block_iteration >= miniblock_iterations
@@ -41,6 +41,35 @@ fn gas_limit_reached() {
     }
 }
 
+/// Checks that the withdrawal is not included into the block once the configured withdrawals limit is reached.
+#[test]
+fn withdrawals_limit_reached() {
+    const MAX_WITHDRAWALS: usize = 2;
+    let mut tester = StateKeeperTester::new(6 * (MAX_WITHDRAWALS + 1), 1, 1);
+    tester.state_keeper.config.max_withdrawals_per_block = Some(MAX_WITHDRAWALS);
+
+    for i in 1..=MAX_WITHDRAWALS + 1 {
+        let withdrawal = create_account_and_withdrawal(
+            &mut tester,
+            TokenId(0),
+            AccountId(i as u32),
+            200u32,
+            145u32,
+            Default::default(),
+        );
+        let result = tester.state_keeper.apply_tx(&withdrawal);
+        if i <= MAX_WITHDRAWALS {
+            assert!(result.is_included(), "i: {}", i);
+        } else {
+            assert!(result.is_not_included(), "i: {}", i);
+        }
+    }
+    assert_eq!(
+        tester.state_keeper.pending_block.withdrawals_count(),
+        MAX_WITHDRAWALS
+    );
+}
+
 /// Checks that execution of failed transaction shouldn't change gas count.
 #[tokio::test]
 async fn gas_count_change() {
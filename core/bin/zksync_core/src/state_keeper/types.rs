@@ -1,9 +1,10 @@
 // External uses
 use futures::channel::oneshot;
 use itertools::Itertools;
+use num::BigUint;
 // Workspace uses
 use zksync_mempool::ProposedBlock;
-use zksync_types::{Account, AccountId, Address};
+use zksync_types::{Account, AccountId, Address, Fr, TokenId};
 // Local uses
 use crate::state_keeper::init_params::ZkSyncStateInitParams;
 
@@ -12,9 +13,27 @@ use crate::state_keeper::init_params::ZkSyncStateInitParams;
 #[cfg(feature = "testkit")]
 pub enum StateKeeperTestkitRequest {
     ExecuteMiniBlock(ProposedBlock),
+    /// Same as `ExecuteMiniBlock`, but the pending block gets the provided timestamp
+    /// instead of the system time. The pending block must have no executed operations.
+    ExecuteMiniBlockWithTimestamp(ProposedBlock, u64),
     GetAccount(Address, oneshot::Sender<Option<(AccountId, Account)>>),
     SealBlock,
     GetCurrentState(oneshot::Sender<ZkSyncStateInitParams>),
+    /// Changes the account that collects fees, starting from the current pending block.
+    SetFeeAccount(AccountId),
+    /// Limits the amount of withdrawals in a block, starting from the current pending block.
+    /// `None` removes the limit.
+    SetMaxWithdrawalsPerBlock(Option<usize>),
+    /// Returns the root hash of the current state, including the pending block.
+    GetRoot(oneshot::Sender<Fr>),
+    /// Sets the balance of the account directly, bypassing the transaction processing.
+    /// Responds with the new state root hash, or `None` if the account does not exist.
+    SetBalance {
+        account_id: AccountId,
+        token: TokenId,
+        amount: BigUint,
+        resp: oneshot::Sender<Option<Fr>>,
+    },
 }
 
 #[derive(Debug)]
@@ -48,6 +67,9 @@ pub(super) struct StateKeeperConfig {
     pub(super) available_block_chunk_sizes: Vec<usize>,
     pub(super) max_miniblock_iterations: usize,
     pub(super) fast_miniblock_iterations: usize,
+    /// Maximum amount of withdrawal operations in a block, on top of the size and gas limits.
+    /// Not set by default, only the testkit uses it to exercise the block sealing on small limits.
+    pub(super) max_withdrawals_per_block: Option<usize>,
     max_block_size: usize,
 }
 
@@ -81,6 +103,7 @@ impl StateKeeperConfig {
             available_block_chunk_sizes,
             max_miniblock_iterations,
             fast_miniblock_iterations,
+            max_withdrawals_per_block: None,
             max_block_size,
         }
     }
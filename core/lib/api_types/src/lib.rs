@@ -7,6 +7,9 @@ use zksync_types::{
 
 pub mod v02;
 
+/// Maximum amount of addresses which can be requested in a single `accounts_info` call.
+pub const MAX_ACCOUNTS_INFO_ADDRESSES: usize = 100;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TxWithSignature {
@@ -210,6 +210,16 @@ pub struct JsonRpcConfig {
     pub ws_port: u16,
     /// URL to access WebSocket RPC server.
     pub ws_url: String,
+    /// Time limit for processing a single request, in milliseconds.
+    /// Transaction submission requests are not limited, since they must not be cancelled
+    /// once the transactions are accepted by the mempool.
+    pub request_timeout_ms: u64,
+    /// Maximum number of the `account_info` responses kept in the cache.
+    pub account_info_cache_size: usize,
+    /// Time during which the cached `account_info` response can be served, in milliseconds.
+    pub account_info_cache_lifetime_ms: u64,
+    /// Time during which the cached `get_token_price` response can be served, in milliseconds.
+    pub token_price_cache_lifetime_ms: u64,
 }
 
 impl JsonRpcConfig {
@@ -220,6 +230,18 @@ impl JsonRpcConfig {
     pub fn ws_bind_addr(&self) -> SocketAddr {
         SocketAddr::new("0.0.0.0".parse().unwrap(), self.ws_port)
     }
+
+    pub fn request_timeout(&self) -> Duration {
+        Duration::from_millis(self.request_timeout_ms)
+    }
+
+    pub fn account_info_cache_lifetime(&self) -> Duration {
+        Duration::from_millis(self.account_info_cache_lifetime_ms)
+    }
+
+    pub fn token_price_cache_lifetime(&self) -> Duration {
+        Duration::from_millis(self.token_price_cache_lifetime_ms)
+    }
 }
 
 #[derive(Debug, Deserialize, Clone, PartialEq)]
@@ -278,6 +300,10 @@ mod tests {
                 http_url: "http://127.0.0.1:3030".into(),
                 ws_port: 3031,
                 ws_url: "ws://127.0.0.1:3031".into(),
+                request_timeout_ms: 10000,
+                account_info_cache_size: 10000,
+                account_info_cache_lifetime_ms: 1000,
+                token_price_cache_lifetime_ms: 5000,
             },
             web3: Web3Config {
                 port: 3002,
@@ -323,6 +349,10 @@ API_JSON_RPC_HTTP_PORT="3030"
 API_JSON_RPC_HTTP_URL="http://127.0.0.1:3030"
 API_JSON_RPC_WS_PORT="3031"
 API_JSON_RPC_WS_URL="ws://127.0.0.1:3031"
+API_JSON_RPC_REQUEST_TIMEOUT_MS="10000"
+API_JSON_RPC_ACCOUNT_INFO_CACHE_SIZE="10000"
+API_JSON_RPC_ACCOUNT_INFO_CACHE_LIFETIME_MS="1000"
+API_JSON_RPC_TOKEN_PRICE_CACHE_LIFETIME_MS="5000"
 API_WEB3_PORT="3002"
 API_WEB3_URL="http://127.0.0.1:3002"
 API_WEB3_CHAIN_ID="240"
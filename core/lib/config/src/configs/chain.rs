@@ -3,6 +3,7 @@ use serde::Deserialize;
 /// Built-in uses
 use std::time::Duration;
 // Local uses
+use zksync_types::block::validate_block_sizes;
 use zksync_types::network::Network;
 use zksync_types::Address;
 
@@ -20,11 +21,22 @@ pub struct ChainConfig {
 
 impl ChainConfig {
     pub fn from_env() -> Self {
-        Self {
+        let config = Self {
             circuit: envy_load!("circuit", "CHAIN_CIRCUIT_"),
             eth: envy_load!("eth", "CHAIN_ETH_"),
             state_keeper: envy_load!("state_keeper", "CHAIN_STATE_KEEPER_"),
-        }
+        };
+
+        validate_block_sizes(&config.circuit.supported_block_chunks_sizes).unwrap_or_else(|err| {
+            panic!(
+                "Invalid config <circuit>: supported_block_chunks_sizes: {}",
+                err
+            )
+        });
+        validate_block_sizes(&config.state_keeper.block_chunk_sizes).unwrap_or_else(|err| {
+            panic!("Invalid config <state_keeper>: block_chunk_sizes: {}", err)
+        });
+        config
     }
     pub fn max_blocks_to_aggregate(&self) -> u32 {
         std::cmp::max(
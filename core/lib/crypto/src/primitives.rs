@@ -4,14 +4,14 @@ use std::{convert::TryInto, mem};
 use crate::franklin_crypto::bellman::pairing::{
     bn256::Bn256,
     ff::{PrimeField, PrimeFieldRepr, ScalarEngine},
-    CurveAffine, Engine,
+    CurveAffine, EncodedPoint, Engine,
 };
 use num::{BigUint, ToPrimitive};
 use zksync_basic_types::U256;
 // Workspace deps
 use crate::{
     circuit::utils::append_le_fixed_width,
-    error::PackingError,
+    error::{ConversionError, PackingError},
     merkle_tree::{hasher::Hasher, rescue_hasher::BabyRescueHasher},
     params,
 };
@@ -91,6 +91,32 @@ impl EthereumSerializer {
             .expect("get new root BE bytes");
         U256::from_big_endian(&be_bytes[..])
     }
+
+    /// Inverse of `serialize_g1`: `(0, 0)` is decoded as the point at infinity.
+    pub fn deserialize_g1(
+        (x, y): (U256, U256),
+    ) -> Result<<Bn256 as Engine>::G1Affine, ConversionError> {
+        if x.is_zero() && y.is_zero() {
+            return Ok(<Bn256 as Engine>::G1Affine::zero());
+        }
+        let mut uncompressed = <<Bn256 as Engine>::G1Affine as CurveAffine>::Uncompressed::empty();
+        let uncompressed_slice = uncompressed.as_mut();
+        x.to_big_endian(&mut uncompressed_slice[0..32]);
+        y.to_big_endian(&mut uncompressed_slice[32..64]);
+
+        Ok(uncompressed.into_affine()?)
+    }
+
+    /// Inverse of `serialize_fe`.
+    pub fn deserialize_fe(value: U256) -> Result<<Bn256 as ScalarEngine>::Fr, ConversionError> {
+        let mut be_bytes = [0u8; 32];
+        value.to_big_endian(&mut be_bytes);
+        let mut repr = <<Bn256 as ScalarEngine>::Fr as PrimeField>::Repr::default();
+        repr.read_be(&be_bytes[..])
+            .map_err(ConversionError::ParsingError)?;
+
+        Ok(<Bn256 as ScalarEngine>::Fr::from_repr(repr)?)
+    }
 }
 
 // Resulting iterator is little endian: lowest bit first
@@ -561,4 +587,22 @@ mod test {
         let out: Vec<bool> = BitIteratorLe::new(&test_vector).collect();
         assert_eq!(reference, out);
     }
+
+    #[test]
+    fn test_ethereum_serializer_roundtrip() {
+        use crate::franklin_crypto::bellman::pairing::bn256::{Fr, G1Affine};
+
+        let fe = Fr::from_str("123456789").unwrap();
+        let encoded = EthereumSerializer::serialize_fe(&fe);
+        assert_eq!(EthereumSerializer::deserialize_fe(encoded).unwrap(), fe);
+
+        let point = G1Affine::one();
+        let encoded = EthereumSerializer::serialize_g1(&point);
+        assert_eq!(EthereumSerializer::deserialize_g1(encoded).unwrap(), point);
+
+        let encoded = EthereumSerializer::serialize_g1(&G1Affine::zero());
+        assert!(EthereumSerializer::deserialize_g1(encoded)
+            .unwrap()
+            .is_zero());
+    }
 }
@@ -9,6 +9,8 @@ use crate::{
         better_cs::{cs::PlonkCsWidth4WithNextStepParams, keys::Proof as OldProof},
     },
     convert::FeConvert,
+    error::ConversionError,
+    pairing::Engine as EngineTrait,
     primitives::EthereumSerializer,
     proof::EncodedSingleProof,
     recursive_aggregation_circuit::circuit::RecursiveAggregationCircuitBn256,
@@ -391,9 +393,66 @@ pub fn serialize_single_proof(
     }
 }
 
+/// Number of `U256` words in a proof encoded by `serialize_single_proof`.
+const SINGLE_PROOF_ENCODED_LEN: usize = 33;
+
+/// Decodes a proof previously encoded with `serialize_single_proof`.
+///
+/// The encoding does not contain the domain size, so `n` of the returned proof is left
+/// as zero and has to be taken from the verification key the proof is checked against.
+pub fn deserialize_single_proof(
+    encoded: &EncodedSingleProof,
+) -> Result<OldProof<Engine, PlonkCsWidth4WithNextStepParams>, ConversionError> {
+    if encoded.proof.len() != SINGLE_PROOF_ENCODED_LEN {
+        return Err(ConversionError::IncorrectInputSize {
+            size: encoded.proof.len(),
+            expected_size: SINGLE_PROOF_ENCODED_LEN,
+        });
+    }
+
+    let mut proof = OldProof::<Engine, PlonkCsWidth4WithNextStepParams>::empty();
+    proof.num_inputs = encoded.inputs.len();
+    proof.input_values = encoded
+        .inputs
+        .iter()
+        .map(|input| EthereumSerializer::deserialize_fe(*input))
+        .collect::<Result<_, _>>()?;
+
+    let mut words = encoded.proof.iter().copied();
+    let words = &mut words;
+
+    proof.wire_commitments = (0..4).map(|_| next_g1(words)).collect::<Result<_, _>>()?;
+    proof.grand_product_commitment = next_g1(words)?;
+    proof.quotient_poly_commitments = (0..4).map(|_| next_g1(words)).collect::<Result<_, _>>()?;
+    proof.wire_values_at_z = (0..4).map(|_| next_fe(words)).collect::<Result<_, _>>()?;
+    proof.wire_values_at_z_omega = vec![next_fe(words)?];
+    proof.grand_product_at_z_omega = next_fe(words)?;
+    proof.quotient_polynomial_at_z = next_fe(words)?;
+    proof.linearization_polynomial_at_z = next_fe(words)?;
+    proof.permutation_polynomials_at_z =
+        (0..3).map(|_| next_fe(words)).collect::<Result<_, _>>()?;
+    proof.opening_at_z_proof = next_g1(words)?;
+    proof.opening_at_z_omega_proof = next_g1(words)?;
+
+    Ok(proof)
+}
+
+fn next_g1(
+    words: &mut impl Iterator<Item = U256>,
+) -> Result<<Engine as EngineTrait>::G1Affine, ConversionError> {
+    let x = words.next().expect("encoded proof length is checked");
+    let y = words.next().expect("encoded proof length is checked");
+    EthereumSerializer::deserialize_g1((x, y))
+}
+
+fn next_fe(words: &mut impl Iterator<Item = U256>) -> Result<Fr, ConversionError> {
+    EthereumSerializer::deserialize_fe(words.next().expect("encoded proof length is checked"))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{ff::PrimeField, pairing::CurveAffine};
     use serde::{Deserialize, Serialize};
     use serde_json::json;
 
@@ -459,4 +518,42 @@ mod tests {
 
         assert_eq!(serialized_fr, expected.to_string());
     }
+
+    #[test]
+    fn test_single_proof_round_trip() {
+        let point = <Engine as EngineTrait>::G1Affine::one();
+        let fe = |value: u64| Fr::from_str(&value.to_string()).unwrap();
+
+        let mut proof = OldProof::<Engine, PlonkCsWidth4WithNextStepParams>::empty();
+        proof.num_inputs = 1;
+        proof.input_values = vec![fe(1)];
+        proof.wire_commitments = vec![point; 4];
+        proof.grand_product_commitment = point;
+        proof.quotient_poly_commitments = vec![point; 4];
+        proof.wire_values_at_z = (2..6).map(fe).collect();
+        proof.wire_values_at_z_omega = vec![fe(6)];
+        proof.grand_product_at_z_omega = fe(7);
+        proof.quotient_polynomial_at_z = fe(8);
+        proof.linearization_polynomial_at_z = fe(9);
+        proof.permutation_polynomials_at_z = (10..13).map(fe).collect();
+        proof.opening_at_z_proof = <Engine as EngineTrait>::G1Affine::zero();
+        proof.opening_at_z_omega_proof = point;
+
+        let encoded = serialize_single_proof(&proof);
+        let decoded = deserialize_single_proof(&encoded).expect("Deserialization failed");
+        assert_eq!(decoded.input_values, proof.input_values);
+        assert_eq!(decoded.wire_commitments, proof.wire_commitments);
+        assert_eq!(decoded.wire_values_at_z, proof.wire_values_at_z);
+        assert_eq!(
+            decoded.permutation_polynomials_at_z,
+            proof.permutation_polynomials_at_z
+        );
+        assert_eq!(decoded.opening_at_z_proof, proof.opening_at_z_proof);
+        assert_eq!(serialize_single_proof(&decoded), encoded);
+
+        // Proof of an unexpected length is rejected.
+        let mut truncated = encoded;
+        truncated.proof.pop();
+        assert!(deserialize_single_proof(&truncated).is_err());
+    }
 }
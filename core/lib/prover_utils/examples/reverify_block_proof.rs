@@ -0,0 +1,41 @@
+//! Re-verifies a stored block proof against the verification key of the given block size.
+//! Proof is expected to be a JSON-serialized `SingleProof`, as stored in the database.
+
+use std::fs::File;
+use std::path::PathBuf;
+use structopt::StructOpt;
+use zksync_crypto::proof::SingleProof;
+use zksync_prover_utils::reverify_block_proof;
+
+#[derive(StructOpt)]
+#[structopt(
+    name = "zkSync block proof verifier",
+    author = "Matter Labs",
+    rename_all = "snake_case"
+)]
+struct Opt {
+    /// Size of the block in chunks
+    #[structopt(long)]
+    block_chunks: usize,
+
+    /// Path to the JSON file with the block proof
+    #[structopt(long)]
+    proof: PathBuf,
+}
+
+fn main() {
+    let _vlog_guard = vlog::init();
+
+    let opt = Opt::from_args();
+
+    let proof_file = File::open(&opt.proof).expect("Failed to open proof file");
+    let proof: SingleProof = serde_json::from_reader(proof_file).expect("Failed to parse proof");
+
+    match reverify_block_proof(opt.block_chunks, &proof) {
+        Ok(()) => vlog::info!("Block proof is valid"),
+        Err(err) => {
+            vlog::error!("Block proof verification failed: {}", err);
+            std::process::exit(1);
+        }
+    }
+}
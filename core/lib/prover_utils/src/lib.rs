@@ -19,8 +19,9 @@ use zksync_crypto::franklin_crypto::rescue::bn256::Bn256RescueParams;
 use zksync_crypto::franklin_crypto::rescue::rescue_transcript::RescueTranscriptForRNS;
 use zksync_crypto::pairing::Engine as EngineTrait;
 use zksync_crypto::params::RECURSIVE_CIRCUIT_VK_TREE_DEPTH;
-use zksync_crypto::proof::SingleProof;
+use zksync_crypto::proof::{EncodedSingleProof, SingleProof};
 use zksync_crypto::recursive_aggregation_circuit::circuit::create_vks_tree;
+use zksync_crypto::serialization::deserialize_single_proof;
 use zksync_crypto::{Engine, Fr};
 
 use crate::fs_utils::{get_block_verification_key_path, get_exodus_verification_key_path};
@@ -66,13 +67,43 @@ impl PlonkVerificationKey {
     }
 }
 
-pub struct SetupForStepByStepProver {
-    setup_polynomials: SetupPolynomials<Engine, PlonkCsWidth4WithNextStepParams>,
-    hints: Vec<(usize, TranspilationVariant)>,
+/// Universal setup borrowed from the `UNIVERSAL_SETUP_CACHE`.
+///
+/// Setup is returned to the cache once the last reference to it is dropped.
+struct CachedUniversalSetup {
     setup_power_of_two: u32,
     key_monomial_form: Option<Crs<Engine, CrsForMonomialForm>>,
 }
 
+impl CachedUniversalSetup {
+    fn key_monomial_form(&self) -> &Crs<Engine, CrsForMonomialForm> {
+        self.key_monomial_form
+            .as_ref()
+            .expect("Setup should have universal setup struct")
+    }
+}
+
+impl Drop for CachedUniversalSetup {
+    fn drop(&mut self) {
+        let setup = self
+            .key_monomial_form
+            .take()
+            .expect("Setup should have universal setup struct");
+        UNIVERSAL_SETUP_CACHE.put_setup_struct(self.setup_power_of_two, setup);
+    }
+}
+
+/// Prepared setup for the step-by-step prover.
+///
+/// Cloning is cheap, since all the data is shared between clones, so the same setup
+/// can be used by several concurrent proof jobs for the circuits of the same shape.
+#[derive(Clone)]
+pub struct SetupForStepByStepProver {
+    setup_polynomials: Arc<SetupPolynomials<Engine, PlonkCsWidth4WithNextStepParams>>,
+    hints: Arc<Vec<(usize, TranspilationVariant)>>,
+    universal_setup: Arc<CachedUniversalSetup>,
+}
+
 impl SetupForStepByStepProver {
     pub fn prepare_setup_for_step_by_step_prover<C: Circuit<Engine> + Clone>(
         circuit: C,
@@ -90,10 +121,12 @@ impl SetupForStepByStepProver {
         )?);
         metrics::histogram!("prover", start.elapsed(), "stage" => "prepare_setup");
         Ok(SetupForStepByStepProver {
-            setup_polynomials,
-            hints,
-            setup_power_of_two,
-            key_monomial_form,
+            setup_polynomials: Arc::new(setup_polynomials),
+            hints: Arc::new(hints),
+            universal_setup: Arc::new(CachedUniversalSetup {
+                setup_power_of_two,
+                key_monomial_form,
+            }),
         })
     }
 
@@ -113,9 +146,7 @@ impl SetupForStepByStepProver {
             &self.hints,
             &self.setup_polynomials,
             None,
-            self.key_monomial_form
-                .as_ref()
-                .expect("Setup should have universal setup struct"),
+            self.universal_setup.key_monomial_form(),
             Some(transcript_params),
         )?;
         metrics::histogram!("prover", start.elapsed(), "stage" => "create_proof", "type" => "single_proof");
@@ -144,16 +175,6 @@ impl SetupForStepByStepProver {
     }
 }
 
-impl Drop for SetupForStepByStepProver {
-    fn drop(&mut self) {
-        let setup = self
-            .key_monomial_form
-            .take()
-            .expect("Setup should have universal setup struct");
-        UNIVERSAL_SETUP_CACHE.put_setup_struct(self.setup_power_of_two, setup);
-    }
-}
-
 /// Generates proof for exit given circuit using step-by-step algorithm.
 pub fn gen_verified_proof_for_exit_circuit<C: Circuit<Engine> + Clone>(
     circuit: C,
@@ -185,6 +206,66 @@ pub fn gen_verified_proof_for_exit_circuit<C: Circuit<Engine> + Clone>(
     Ok(proof.into())
 }
 
+/// Verifies an encoded exit proof against the exit circuit verification key.
+///
+/// Allows to check a proof before sending it to the contract, so that an invalid
+/// proof is reported locally instead of as a reverted `exit` transaction.
+pub fn verify_exit_proof(proof: &EncodedSingleProof) -> Result<(), anyhow::Error> {
+    let vk = PlonkVerificationKey::read_verification_key_for_exit_circuit()
+        .map_err(|e| anyhow::format_err!("Failed to load exit verification key: {}", e))?;
+
+    let mut proof = deserialize_single_proof(proof)
+        .map_err(|e| anyhow::format_err!("Failed to decode exit proof: {}", e))?;
+    anyhow::ensure!(
+        proof.num_inputs == vk.0.num_inputs,
+        "exit proof should have {} public inputs, got {}",
+        vk.0.num_inputs,
+        proof.num_inputs
+    );
+    proof.n = vk.0.n;
+
+    let valid = verify::<_, _, RollingKeccakTranscript<Fr>>(&proof, &vk.0, None)?;
+    anyhow::ensure!(valid, "proof for exit is invalid");
+    Ok(())
+}
+
+/// Re-verifies a stored block proof against the verification key for the given block size.
+///
+/// Intended to be used for audit purposes, when the proof has to be checked again
+/// without running the full prover.
+pub fn reverify_block_proof(block_chunks: usize, proof: &SingleProof) -> Result<(), anyhow::Error> {
+    anyhow::ensure!(
+        proof.0.input_values.len() == 1,
+        "block proof should have exactly one public input, got {}",
+        proof.0.input_values.len()
+    );
+
+    let vk = PlonkVerificationKey::read_verification_key_for_main_circuit(block_chunks).map_err(
+        |e| {
+            anyhow::format_err!(
+                "Failed to load verification key for block size {}: {}",
+                block_chunks,
+                e
+            )
+        },
+    )?;
+
+    let rns_params =
+        RnsParameters::<Engine, <Engine as EngineTrait>::Fq>::new_for_field(68, 110, 4);
+    let rescue_params = Bn256RescueParams::new_checked_2_into_1();
+    let transcript_params = (&rescue_params, &rns_params);
+
+    let valid =
+        verify::<_, _, RescueTranscriptForRNS<Engine>>(&proof.0, &vk.0, Some(transcript_params))
+            .map_err(|e| anyhow::format_err!("Failed to verify block proof: {}", e))?;
+    anyhow::ensure!(
+        valid,
+        "block proof is invalid for block size {}",
+        block_chunks
+    );
+    Ok(())
+}
+
 /// Reads universal setup from disk or downloads from network.
 pub fn get_universal_setup_monomial_form(
     power_of_two: u32,
@@ -245,3 +326,27 @@ impl UniversalSetupCache {
 lazy_static! {
     static ref UNIVERSAL_SETUP_CACHE: UniversalSetupCache = UniversalSetupCache::new();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fs_utils::load_precomputed_proofs;
+    use zksync_crypto::ff::Field;
+
+    #[test]
+    fn reverify_sample_block_proof() {
+        let sample_proofs = load_precomputed_proofs().expect("Failed to load sample proofs");
+        let (proof, block_chunks) = sample_proofs.single_proofs[0].clone();
+
+        reverify_block_proof(block_chunks, &proof).expect("Sample proof should be valid");
+    }
+
+    #[test]
+    fn reverify_tampered_block_proof() {
+        let sample_proofs = load_precomputed_proofs().expect("Failed to load sample proofs");
+        let (mut proof, block_chunks) = sample_proofs.single_proofs[0].clone();
+        proof.0.input_values[0].add_assign(&Fr::one());
+
+        assert!(reverify_block_proof(block_chunks, &proof).is_err());
+    }
+}
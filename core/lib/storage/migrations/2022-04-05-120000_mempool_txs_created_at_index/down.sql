@@ -0,0 +1 @@
+DROP INDEX IF EXISTS mempool_txs_created_at_idx;
@@ -0,0 +1 @@
+CREATE INDEX IF NOT EXISTS mempool_txs_created_at_idx ON mempool_txs (created_at);
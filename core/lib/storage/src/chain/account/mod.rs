@@ -448,6 +448,29 @@ impl<'a, 'c> AccountSchema<'a, 'c> {
         Ok(address)
     }
 
+    /// Returns the number of the block in which the account was created.
+    /// Returns `None` if the account creation is not committed yet.
+    pub async fn account_creation_block(
+        &mut self,
+        account_id: AccountId,
+    ) -> QueryResult<Option<BlockNumber>> {
+        let start = Instant::now();
+        let block_number = sqlx::query!(
+            r#"
+                SELECT MIN(block_number) FROM account_creates
+                WHERE account_id = $1 AND is_create = true
+            "#,
+            i64::from(*account_id)
+        )
+        .fetch_one(self.0.conn())
+        .await?
+        .min
+        .map(|block_number| BlockNumber(block_number as u32));
+
+        metrics::histogram!("sql.chain.account.account_creation_block", start.elapsed());
+        Ok(block_number)
+    }
+
     /// Obtains the last committed block that affects the account.
     pub async fn last_committed_block_with_update_for_acc(
         &mut self,
@@ -1,4 +1,5 @@
 // Built-in deps
+use std::collections::HashSet;
 use std::time::{Instant, SystemTime, UNIX_EPOCH};
 // External imports
 // Workspace imports
@@ -120,7 +121,7 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
         }
 
         transaction.commit().await?;
-        metrics::histogram!("sql.chain.block.save_block_transactions", start.elapsed());
+        query_histogram!("sql.chain.block.save_block_transactions", start.elapsed());
         Ok(())
     }
 
@@ -138,7 +139,7 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
         .fetch_optional(self.0.conn())
         .await?;
 
-        metrics::histogram!("sql.chain.block.get_storage_block", start.elapsed());
+        query_histogram!("sql.chain.block.get_storage_block", start.elapsed());
 
         Ok(block)
     }
@@ -179,7 +180,7 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
             stored_block.timestamp.unwrap_or_default() as u64,
         ));
 
-        metrics::histogram!("sql.chain.block.get_block", start.elapsed());
+        query_histogram!("sql.chain.block.get_block", start.elapsed());
 
         Ok(result)
     }
@@ -200,7 +201,7 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
         .fetch_optional(self.0.conn())
         .await?;
 
-        metrics::histogram!("sql.chain.block.get_block_metadata", start.elapsed());
+        query_histogram!("sql.chain.block.get_block_metadata", start.elapsed());
 
         let result = db_result.map(|md| BlockMetadata {
             fast_processing: md.fast_processing,
@@ -221,7 +222,7 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
                 ExecutedOperations::PriorityOp(priorop) => Some(priorop.op),
             })
             .collect();
-        metrics::histogram!("sql.chain.block.get_block_operations", start.elapsed());
+        query_histogram!("sql.chain.block.get_block_operations", start.elapsed());
         Ok(result)
     }
 
@@ -282,7 +283,7 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
         .fetch_all(self.0.conn())
         .await?;
 
-        metrics::histogram!("sql.chain.block.get_block_transactions", start.elapsed());
+        query_histogram!("sql.chain.block.get_block_transactions", start.elapsed());
         Ok(block_txs)
     }
 
@@ -345,10 +346,51 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
             }
         });
 
-        metrics::histogram!("sql.chain.block.get_block_executed_ops", start.elapsed());
+        query_histogram!("sql.chain.block.get_block_executed_ops", start.elapsed());
         Ok(executed_operations)
     }
 
+    /// Returns the set of accounts modified in the committed blocks after `from` up to `to` inclusively,
+    /// i.e. accounts changed since the block `from`. Blocks after the last committed one are ignored.
+    /// Returns an empty set if `to` is not greater than `from`.
+    pub async fn accounts_changed_between(
+        &mut self,
+        from: BlockNumber,
+        to: BlockNumber,
+    ) -> QueryResult<HashSet<AccountId>> {
+        let start = Instant::now();
+        let last_committed_block = self.get_last_committed_block().await?;
+        let to = std::cmp::min(to, last_committed_block);
+        if to <= from {
+            return Ok(HashSet::new());
+        }
+
+        // Account updates of the block (including the collected fees) are stored in the
+        // state tables, so the changed accounts are collected from them.
+        let accounts = sqlx::query!(
+            r#"
+            SELECT account_id AS "account_id!" FROM account_balance_updates
+            WHERE block_number > $1 AND block_number <= $2
+            UNION
+            SELECT account_id AS "account_id!" FROM account_creates
+            WHERE block_number > $1 AND block_number <= $2
+            UNION
+            SELECT account_id AS "account_id!" FROM account_pubkey_updates
+            WHERE block_number > $1 AND block_number <= $2
+            "#,
+            i64::from(*from),
+            i64::from(*to)
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .map(|record| AccountId(record.account_id as u32))
+        .collect();
+
+        query_histogram!("sql.chain.block.accounts_changed_between", start.elapsed());
+        Ok(accounts)
+    }
+
     /// Loads the block headers for the given amount of blocks in the descending order.
     pub async fn load_block_range_desc(
         &mut self,
@@ -409,7 +451,7 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
         ).fetch_all(self.0.conn())
         .await?;
 
-        metrics::histogram!("sql.chain.block.load_block_range", start.elapsed());
+        query_histogram!("sql.chain.block.load_block_range", start.elapsed());
         Ok(details)
     }
 
@@ -473,7 +515,7 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
         ).fetch_all(self.0.conn())
         .await?;
 
-        metrics::histogram!("sql.chain.block.load_block_range_asc", start.elapsed());
+        query_histogram!("sql.chain.block.load_block_range_asc", start.elapsed());
         Ok(details)
     }
 
@@ -609,7 +651,7 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
             .ok()
             .flatten();
 
-        metrics::histogram!(
+        query_histogram!(
             "sql.chain.block.find_block_by_height_or_hash",
             start.elapsed()
         );
@@ -624,7 +666,7 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
             .await?
             .max
             .unwrap_or(0);
-        metrics::histogram!("sql.chain.block.get_last_committed_block", start.elapsed());
+        query_histogram!("sql.chain.block.get_last_committed_block", start.elapsed());
         Ok(BlockNumber(count as u32))
     }
 
@@ -634,7 +676,7 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
         let result = OperationsSchema(self.0)
             .get_last_block_by_aggregated_action(AggregatedActionType::CommitBlocks, None)
             .await;
-        metrics::histogram!("sql.chain.block.get_last_committed_block", start.elapsed());
+        query_histogram!("sql.chain.block.get_last_committed_block", start.elapsed());
         result
     }
 
@@ -652,7 +694,7 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
             .await?
             .max
             .map(|block| BlockNumber(block as u32));
-        metrics::histogram!("sql.chain.block.get_last_incomplete_block", start.elapsed());
+        query_histogram!("sql.chain.block.get_last_incomplete_block", start.elapsed());
         Ok(result)
     }
 
@@ -662,7 +704,7 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
         let result = OperationsSchema(self.0)
             .get_last_block_by_aggregated_action(AggregatedActionType::CommitBlocks, Some(true))
             .await;
-        metrics::histogram!(
+        query_histogram!(
             "sql.chain.block.get_last_committed_confirmed_block",
             start.elapsed()
         );
@@ -679,7 +721,7 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
         let result = OperationsSchema(self.0)
             .get_last_block_by_aggregated_action(AggregatedActionType::ExecuteBlocks, None)
             .await;
-        metrics::histogram!("sql.chain.block.get_last_verified_block", start.elapsed());
+        query_histogram!("sql.chain.block.get_last_verified_block", start.elapsed());
         result
     }
 
@@ -692,7 +734,7 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
                 Some(true),
             )
             .await;
-        metrics::histogram!(
+        query_histogram!(
             "sql.chain.block.get_last_proven_confirmed_block",
             start.elapsed()
         );
@@ -706,7 +748,7 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
         let result = OperationsSchema(self.0)
             .get_last_block_by_aggregated_action(AggregatedActionType::ExecuteBlocks, Some(true))
             .await;
-        metrics::histogram!(
+        query_histogram!(
             "sql.chain.block.get_last_verified_confirmed_block",
             start.elapsed()
         );
@@ -731,7 +773,7 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
         )
         .fetch_optional(self.0.conn())
         .await?;
-        metrics::histogram!("sql.chain.block.pending_block_chunks_left", start.elapsed());
+        query_histogram!("sql.chain.block.pending_block_chunks_left", start.elapsed());
 
         Ok(maybe_block_chunks.map(|val| val.chunks_left as usize))
     }
@@ -747,7 +789,7 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
         )
         .fetch_optional(self.0.conn())
         .await?;
-        metrics::histogram!(
+        query_histogram!(
             "sql.chain.block.load_storage_pending_block",
             start.elapsed()
         );
@@ -799,7 +841,7 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
 
         transaction.commit().await?;
 
-        metrics::histogram!("sql.chain.block.load_pending_block", start.elapsed());
+        query_histogram!("sql.chain.block.load_pending_block", start.elapsed());
         Ok(Some(result))
     }
 
@@ -808,7 +850,7 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
         let start = Instant::now();
         let result = self.load_storage_pending_block().await?.is_some();
 
-        metrics::histogram!("sql.chain.block.pending_block_exists", start.elapsed());
+        query_histogram!("sql.chain.block.pending_block_exists", start.elapsed());
         Ok(result)
     }
 
@@ -855,7 +897,7 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
             .await?;
 
         transaction.commit().await?;
-        metrics::histogram!("sql.chain.block.load_pending_block", start.elapsed());
+        query_histogram!("sql.chain.block.load_pending_block", start.elapsed());
 
         Ok(())
     }
@@ -870,7 +912,7 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
         .await?
         .count;
 
-        metrics::histogram!("sql.chain.block.count_rejected_txs", start.elapsed());
+        query_histogram!("sql.chain.block.count_rejected_txs", start.elapsed());
         Ok(count)
     }
     /// Returns the number of aggregated operations with the given `action_type` and `is_confirmed` status.
@@ -889,7 +931,7 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
         .await?
         .count;
 
-        metrics::histogram!("sql.chain.block.count_operations", start.elapsed());
+        query_histogram!("sql.chain.block.count_operations", start.elapsed());
         Ok(count)
     }
 
@@ -935,7 +977,7 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
 
         transaction.commit().await?;
 
-        metrics::histogram!("sql.chain.block.save_block", start.elapsed());
+        query_histogram!("sql.chain.block.save_block", start.elapsed());
         Ok(())
     }
 
@@ -991,7 +1033,7 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
 
         transaction.commit().await?;
 
-        metrics::histogram!("sql.chain.block.save_incomplete_block", start.elapsed());
+        query_histogram!("sql.chain.block.save_incomplete_block", start.elapsed());
         Ok(())
     }
 
@@ -1050,7 +1092,7 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
         .map(|val| val as u64)
         .unwrap_or_default();
 
-        metrics::histogram!("sql.chain.block.next_expected_serial_id", start.elapsed());
+        query_histogram!("sql.chain.block.next_expected_serial_id", start.elapsed());
         Ok(next_expected_serial_id)
     }
 
@@ -1080,7 +1122,7 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
             }
         };
 
-        metrics::histogram!("sql.chain.block.incomplete_blocks_range", start.elapsed());
+        query_histogram!("sql.chain.block.incomplete_blocks_range", start.elapsed());
         Ok(block_numbers)
     }
 
@@ -1098,7 +1140,7 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
         .fetch_optional(self.0.conn())
         .await?;
 
-        metrics::histogram!(
+        query_histogram!(
             "sql.chain.block.get_storage_incomplete_block",
             start.elapsed()
         );
@@ -1152,7 +1194,7 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
         .await?
         .map(|entry| FeConvert::from_bytes(&entry.root_hash).expect("Unparsable root hash"));
 
-        metrics::histogram!(
+        query_histogram!(
             "sql.chain.block.get_data_to_complete_block",
             start.elapsed()
         );
@@ -1178,7 +1220,7 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
         .execute(self.0.conn())
         .await?;
 
-        metrics::histogram!("sql.chain.block.save_block_metadata", start.elapsed());
+        query_histogram!("sql.chain.block.save_block_metadata", start.elapsed());
         Ok(())
     }
 
@@ -1376,7 +1418,7 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
         };
         transaction.commit().await?;
 
-        metrics::histogram!(
+        query_histogram!(
             "sql.chain.block.get_block_transactions_page",
             start.elapsed()
         );
@@ -1407,7 +1449,7 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
         .count;
         transaction.commit().await?;
 
-        metrics::histogram!(
+        query_histogram!(
             "sql.chain.block.get_block_transactions_count",
             start.elapsed()
         );
@@ -1461,7 +1503,7 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
         .execute(transaction.conn())
         .await?;
         transaction.commit().await?;
-        metrics::histogram!("sql.chain.block.remove_blocks", start.elapsed());
+        query_histogram!("sql.chain.block.remove_blocks", start.elapsed());
         Ok(())
     }
 
@@ -1472,7 +1514,7 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
             .execute(self.0.conn())
             .await?;
 
-        metrics::histogram!("sql.chain.block.remove_pending_block", start.elapsed());
+        query_histogram!("sql.chain.block.remove_pending_block", start.elapsed());
         Ok(())
     }
 
@@ -1518,7 +1560,7 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
         .await?;
         transaction.commit().await?;
 
-        metrics::histogram!(
+        query_histogram!(
             "sql.chain.block.store_factories_for_block_withdraw_nfts",
             start.elapsed()
         );
@@ -1535,7 +1577,7 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
             .await?;
         let block_number = record.map(|r| BlockNumber(r.number as u32));
 
-        metrics::histogram!("sql.chain.block.get_block_number_by_hash", start.elapsed());
+        query_histogram!("sql.chain.block.get_block_number_by_hash", start.elapsed());
         Ok(block_number)
     }
 
@@ -1569,7 +1611,7 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
         .await?;
         let hashes = records.into_iter().map(|record| record.tx_hash).collect();
 
-        metrics::histogram!(
+        query_histogram!(
             "sql.chain.block.get_block_transactions_hashes",
             start.elapsed()
         );
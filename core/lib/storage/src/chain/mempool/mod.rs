@@ -1,6 +1,7 @@
 // Built-in deps
 use std::{collections::VecDeque, convert::TryFrom, str::FromStr, time::Instant};
 // External imports
+use chrono::{DateTime, Utc};
 use itertools::Itertools;
 // Workspace imports
 use zksync_api_types::v02::pagination::PaginationDirection;
@@ -113,10 +114,98 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
             }
         }
 
-        metrics::histogram!("sql.chain.mempool.load_txs", start.elapsed());
+        query_histogram!("sql.chain.mempool.load_txs", start.elapsed());
         Ok(txs.into())
     }
 
+    /// Loads pending transactions to be included into the next block, in the order
+    /// they were added to the mempool, containing at most `max_txs` transactions in total.
+    ///
+    /// Batches are never split: if a batch doesn't fit into the limit, it is deferred
+    /// along with all the transactions that follow it, so the relative order of the
+    /// transactions is preserved.
+    pub async fn fetch_pending_for_block(
+        &mut self,
+        max_txs: usize,
+    ) -> QueryResult<Vec<SignedTxVariant>> {
+        let start = Instant::now();
+        let pending = self.load_txs(&[]).await?;
+
+        let mut txs_count = 0;
+        let mut result = Vec::new();
+        for variant in pending {
+            let variant_size = match &variant {
+                SignedTxVariant::Tx(_) => 1,
+                SignedTxVariant::Batch(batch) => batch.txs.len(),
+            };
+            if txs_count + variant_size > max_txs {
+                break;
+            }
+            txs_count += variant_size;
+            result.push(variant);
+        }
+
+        query_histogram!("sql.chain.mempool.fetch_pending_for_block", start.elapsed());
+        Ok(result)
+    }
+
+    /// Loads pending transactions that have no Ethereum signature attached,
+    /// although their type requires one.
+    /// Transactions from batches are not included, since batch signatures are stored separately.
+    pub async fn get_txs_missing_eth_sign(&mut self) -> QueryResult<Vec<SignedZkSyncTx>> {
+        let start = Instant::now();
+        let txs: Vec<MempoolTx> = sqlx::query_as!(
+            MempoolTx,
+            "SELECT * FROM mempool_txs
+            WHERE reverted = false AND batch_id = 0 AND eth_sign_data IS NULL
+            ORDER BY id"
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        let mut result = Vec::new();
+        for tx in txs {
+            let tx = SignedZkSyncTx::try_from(tx)?;
+            if tx.tx.is_eth_sign_data_required() {
+                result.push(tx);
+            }
+        }
+
+        query_histogram!(
+            "sql.chain.mempool.get_txs_missing_eth_sign",
+            start.elapsed()
+        );
+        Ok(result)
+    }
+
+    /// Loads pending transactions that were added to the mempool within the `[from, to)`
+    /// time range, ordered by their creation time.
+    pub async fn get_txs_in_range(
+        &mut self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> QueryResult<Vec<SignedZkSyncTx>> {
+        let start = Instant::now();
+        let txs: Vec<MempoolTx> = sqlx::query_as!(
+            MempoolTx,
+            "SELECT * FROM mempool_txs
+            WHERE reverted = false AND created_at >= $1 AND created_at < $2
+            ORDER BY created_at, id",
+            from,
+            to
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        let txs = txs
+            .into_iter()
+            .map(SignedZkSyncTx::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        query_histogram!("sql.chain.mempool.get_txs_in_range", start.elapsed());
+        Ok(txs)
+    }
+
     pub async fn remove_reverted_block(&mut self, block_number: BlockNumber) -> QueryResult<()> {
         let start = Instant::now();
         let mut transaction = self.0.start_transaction().await?;
@@ -133,7 +222,7 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
         .execute(transaction.conn())
         .await?;
         transaction.commit().await?;
-        metrics::histogram!("sql.chain.mempool.remove_reverted_block", start.elapsed());
+        query_histogram!("sql.chain.mempool.remove_reverted_block", start.elapsed());
         Ok(())
     }
 
@@ -236,7 +325,7 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
 
         transaction.commit().await?;
 
-        metrics::histogram!("sql.chain.mempool.insert_batch", start.elapsed());
+        query_histogram!("sql.chain.mempool.insert_batch", start.elapsed());
         Ok(batch_id)
     }
 
@@ -264,7 +353,7 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
         .execute(self.0.conn())
         .await?;
 
-        metrics::histogram!("sql.chain.mempool.insert_tx", start.elapsed());
+        query_histogram!("sql.chain.mempool.insert_tx", start.elapsed());
         Ok(())
     }
 
@@ -280,7 +369,7 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
         .execute(self.0.conn())
         .await?;
 
-        metrics::histogram!("sql.chain.mempool.remove_tx", start.elapsed());
+        query_histogram!("sql.chain.mempool.remove_tx", start.elapsed());
         Ok(())
     }
 
@@ -296,7 +385,7 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
         .execute(self.0.conn())
         .await?;
 
-        metrics::histogram!("sql.chain.mempool.remove_txs", start.elapsed());
+        query_histogram!("sql.chain.mempool.remove_txs", start.elapsed());
         Ok(())
     }
 
@@ -317,7 +406,7 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
 
         let contains = row.filter(|&counter| counter > 0).is_some();
 
-        metrics::histogram!("sql.chain", start.elapsed(), "mempool" => "contains_tx");
+        query_histogram!("sql.chain", start.elapsed(), "mempool" => "contains_tx");
         Ok(contains)
     }
 
@@ -327,7 +416,7 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
 
         let mempool_tx = self.get_mempool_tx(tx_hash).await?;
 
-        metrics::histogram!("sql.chain", start.elapsed(), "mempool" => "get_tx");
+        query_histogram!("sql.chain", start.elapsed(), "mempool" => "get_tx");
         mempool_tx
             .map(SignedZkSyncTx::try_from)
             .transpose()
@@ -349,7 +438,7 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
         .fetch_optional(self.0.conn())
         .await?;
 
-        metrics::histogram!("sql.chain", start.elapsed(), "mempool" => "get_tx");
+        query_histogram!("sql.chain", start.elapsed(), "mempool" => "get_tx");
         Ok(mempool_tx)
     }
 
@@ -399,7 +488,7 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
 
         self.remove_txs(&tx_hashes_to_remove).await?;
 
-        metrics::histogram!("sql.chain.mempool.collect_garbage", start.elapsed());
+        query_histogram!("sql.chain.mempool.collect_garbage", start.elapsed());
         Ok(())
     }
 
@@ -463,7 +552,7 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
             .await?;
         }
         transaction.commit().await?;
-        metrics::histogram!("sql.chain", start.elapsed(), "schema" => "mempool", "method" => "insert_priority_ops");
+        query_histogram!("sql.chain", start.elapsed(), "schema" => "mempool", "method" => "insert_priority_ops");
         Ok(())
     }
 
@@ -477,6 +566,30 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
         Ok(ops.into_iter().map(|op| op.into()).collect())
     }
 
+    /// Loads the confirmed priority operations which are not processed yet while their deadline
+    /// has already come at `current_eth_block`, see `is_priority_op_expired`.
+    /// Presence of such operations allows to activate the exodus mode.
+    pub async fn get_expired_unprocessed_priority_ops(
+        &mut self,
+        current_eth_block: u64,
+    ) -> QueryResult<Vec<PriorityOp>> {
+        let start = Instant::now();
+        let ops: Vec<MempoolPriorityOp> = sqlx::query_as(
+            "SELECT serial_id,data,deadline_block,eth_hash,tx_hash,eth_block,eth_block_index,created_at FROM mempool_priority_operations
+            WHERE confirmed AND reverted = false AND deadline_block <= $1
+            ORDER BY serial_id",
+        )
+        .bind(current_eth_block as i64)
+        .fetch_all(self.0.conn())
+        .await?;
+
+        query_histogram!(
+            "sql.chain.mempool.get_expired_unprocessed_priority_ops",
+            start.elapsed()
+        );
+        Ok(ops.into_iter().map(|op| op.into()).collect())
+    }
+
     pub async fn remove_priority_op_from_mempool(&mut self, id: i64) -> QueryResult<()> {
         sqlx::query!(
             "DELETE FROM mempool_priority_operations WHERE serial_id=$1",
@@ -584,7 +697,7 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
             .await?
             .count;
 
-        metrics::histogram!("sql.chain", start.elapsed(), "mempool" => "get_mempool_size");
+        query_histogram!("sql.chain", start.elapsed(), "mempool" => "get_mempool_size");
         Ok(size.unwrap_or(0) as u32)
     }
 
@@ -630,7 +743,7 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
             None
         };
 
-        metrics::histogram!("sql.chain", start.elapsed(), "mempool" => "get_queued_batch_info");
+        query_histogram!("sql.chain", start.elapsed(), "mempool" => "get_queued_batch_info");
         Ok(result)
     }
 
@@ -739,6 +852,57 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
         Ok(incomplete_blocks)
     }
 
+    /// Returns an executed transaction back to the mempool, preserving its Ethereum
+    /// signature and batch membership.
+    /// Does nothing if the transaction is already in the mempool.
+    pub async fn requeue_tx(&mut self, tx: &ExecutedTx) -> QueryResult<()> {
+        let start = Instant::now();
+        self.insert_requeued_tx(tx, None, false).await?;
+        query_histogram!("sql.chain.mempool.requeue_tx", start.elapsed());
+        Ok(())
+    }
+
+    /// Inserts an executed transaction into the mempool unless it's already there.
+    /// Returns `false` if the transaction was skipped.
+    async fn insert_requeued_tx(
+        &mut self,
+        executed_tx: &ExecutedTx,
+        next_priority_op_serial_id: Option<SerialId>,
+        reverted: bool,
+    ) -> QueryResult<bool> {
+        let tx_hash = executed_tx.signed_tx.hash();
+        if self.contains_tx(tx_hash).await? {
+            return Ok(false);
+        }
+
+        let tx_hash = hex::encode(tx_hash.as_ref());
+        let tx_value = serde_json::to_value(&executed_tx.signed_tx.tx)?;
+        let eth_sign_data = executed_tx
+            .signed_tx
+            .eth_sign_data
+            .as_ref()
+            .map(serde_json::to_value)
+            .transpose()?;
+        // Special case: batch_id == 0 <==> transaction is not a part of some batch
+        let batch_id = executed_tx.batch_id.unwrap_or(0);
+
+        sqlx::query!(
+            "INSERT INTO mempool_txs (tx_hash, tx, created_at, eth_sign_data, batch_id, next_priority_op_serial_id, reverted)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)",
+            tx_hash,
+            tx_value,
+            executed_tx.created_at,
+            eth_sign_data,
+            batch_id,
+            next_priority_op_serial_id.map(|id| id as i64),
+            reverted,
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        Ok(true)
+    }
+
     // Returns executed txs back to mempool for blocks with number greater than `last_block`
     pub async fn return_executed_txs_to_mempool(
         &mut self,
@@ -857,14 +1021,17 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
         }
 
         for (reverted_tx, block_number, next_priority_op_serial_id) in reverted_txs {
+            MempoolSchema(&mut transaction)
+                .insert_requeued_tx(&reverted_tx, Some(next_priority_op_serial_id), true)
+                .await?;
+
             let ExecutedTx {
                 signed_tx,
                 success,
-                created_at,
-                batch_id,
                 op,
                 block_index,
                 fail_reason,
+                ..
             } = *reverted_tx;
 
             let block_index = block_index.map(|b| b as i32);
@@ -873,19 +1040,10 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
             let to_account = signed_tx.to_account().map(|a| a.as_bytes().to_vec());
             let primary_account_address = signed_tx.account().as_bytes().to_vec();
 
-            let SignedZkSyncTx {
-                tx, eth_sign_data, ..
-            } = signed_tx;
-
-            let tx_hash_bytes = tx.hash().as_ref().to_vec();
+            let tx_hash_bytes = signed_tx.hash().as_ref().to_vec();
             let tx_hash = hex::encode(&tx_hash_bytes);
-            let tx_value =
-                serde_json::to_value(tx).expect("Failed to serialize reverted transaction");
             let operation =
                 serde_json::to_value(op).expect("Failed to serialize reverted transaction");
-            let eth_sign_data = eth_sign_data.as_ref().map(|sign_data| {
-                serde_json::to_value(sign_data).expect("Failed to serialize Ethereum sign data")
-            });
 
             sqlx::query!(
                 r#"INSERT INTO mempool_reverted_txs_meta (
@@ -908,19 +1066,6 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
             .execute(transaction.conn())
             .await?;
 
-            sqlx::query!(
-                "INSERT INTO mempool_txs (tx_hash, tx, created_at, eth_sign_data, batch_id, next_priority_op_serial_id, reverted)
-                VALUES ($1, $2, $3, $4, $5, $6, true)",
-                tx_hash,
-                tx_value,
-                created_at,
-                eth_sign_data,
-                batch_id.unwrap_or(0i64),
-                next_priority_op_serial_id as i64,
-            )
-            .execute(transaction.conn())
-            .await?;
-
             sqlx::query!(
                 "DELETE FROM tx_filters
                 WHERE tx_hash = $1",
@@ -1020,7 +1165,7 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
         .await?;
         transaction.commit().await?;
 
-        metrics::histogram!(
+        query_histogram!(
             "sql.chain.mempool.return_executed_txs_to_mempool",
             start.elapsed()
         );
@@ -6,7 +6,7 @@ use chrono::{Duration, Utc};
 use zksync_types::{
     aggregated_operations::{AggregatedActionType, AggregatedOperation},
     tx::TxHash,
-    BlockNumber, PriorityOp, SerialId, H256,
+    BlockNumber, PriorityOp, PriorityOpId, SerialId, H256,
 };
 // Local imports
 use self::records::{
@@ -20,6 +20,15 @@ use crate::{chain::mempool::MempoolSchema, QueryResult, StorageProcessor};
 
 pub mod records;
 
+/// Checks whether the deadline of the priority operation has come at `current_eth_block`.
+/// Once the deadline of an unprocessed operation comes, the exodus mode can be activated.
+pub fn is_priority_op_expired(
+    op: &StoredExecutedPriorityOperation,
+    current_eth_block: u64,
+) -> bool {
+    current_eth_block as i64 >= op.deadline_block
+}
+
 /// Operations schema is capable of storing and loading the transactions.
 /// Every kind of transaction (non-executed, executed, and executed priority tx)
 /// can be either saved or loaded from the database.
@@ -44,7 +53,7 @@ impl<'a, 'c> OperationsSchema<'a, 'c> {
         .max
         .unwrap_or(0);
 
-        metrics::histogram!(
+        query_histogram!(
             "sql.chain.operations.get_last_block_by_aggregated_action",
             start.elapsed()
         );
@@ -69,7 +78,7 @@ impl<'a, 'c> OperationsSchema<'a, 'c> {
         .ok()
         .flatten();
 
-        metrics::histogram!(
+        query_histogram!(
             "sql.chain.operations.get_stored_aggregated_operations",
             start.elapsed()
         );
@@ -90,7 +99,7 @@ impl<'a, 'c> OperationsSchema<'a, 'c> {
         .fetch_optional(self.0.conn())
         .await?;
 
-        metrics::histogram!(
+        query_histogram!(
             "sql.chain.operations.get_executed_operation",
             start.elapsed()
         );
@@ -100,18 +109,18 @@ impl<'a, 'c> OperationsSchema<'a, 'c> {
     /// Retrieves priority operation from the database given its ID.
     pub async fn get_executed_priority_operation(
         &mut self,
-        priority_op_id: u32,
+        priority_op_id: PriorityOpId,
     ) -> QueryResult<Option<StoredExecutedPriorityOperation>> {
         let start = Instant::now();
         let op = sqlx::query_as!(
             StoredExecutedPriorityOperation,
             "SELECT * FROM executed_priority_operations WHERE priority_op_serialid = $1",
-            i64::from(priority_op_id)
+            *priority_op_id as i64
         )
         .fetch_optional(self.0.conn())
         .await?;
 
-        metrics::histogram!(
+        query_histogram!(
             "sql.chain.operations.get_executed_priority_operation",
             start.elapsed()
         );
@@ -132,7 +141,7 @@ impl<'a, 'c> OperationsSchema<'a, 'c> {
         .fetch_optional(self.0.conn())
         .await?;
 
-        metrics::histogram!(
+        query_histogram!(
             "sql.chain.operations.get_executed_priority_operation_by_eth_hash",
             start.elapsed()
         );
@@ -157,7 +166,7 @@ impl<'a, 'c> OperationsSchema<'a, 'c> {
         )
         .execute(self.0.conn())
         .await?;
-        metrics::histogram!(
+        query_histogram!(
             "sql.chain.operations.confirm_aggregated_operations",
             start.elapsed()
         );
@@ -256,7 +265,7 @@ impl<'a, 'c> OperationsSchema<'a, 'c> {
         .await?;
 
         transaction.commit().await?;
-        metrics::histogram!("sql.chain.operations.store_executed_tx", start.elapsed());
+        query_histogram!("sql.chain.operations.store_executed_tx", start.elapsed());
         // It's almost impossible situation, but it could be triggered in tests
         let tx_duration = (Utc::now() - operation.created_at)
             .to_std()
@@ -298,7 +307,7 @@ impl<'a, 'c> OperationsSchema<'a, 'c> {
 
         transaction.commit().await?;
 
-        metrics::histogram!(
+        query_histogram!(
             "sql.chain.operations.remove_rejected_transactions",
             start.elapsed()
         );
@@ -362,7 +371,7 @@ impl<'a, 'c> OperationsSchema<'a, 'c> {
         .await?;
 
         transaction.commit().await?;
-        metrics::histogram!(
+        query_histogram!(
             "sql.chain.operations.store_executed_priority_op",
             start.elapsed()
         );
@@ -380,7 +389,7 @@ impl<'a, 'c> OperationsSchema<'a, 'c> {
         .await?;
         let max_serial_id = max_serial_id.max.map(|record| record as u64);
 
-        metrics::histogram!(
+        query_histogram!(
             "sql.chain.operations.get_max_priority_op_serial_id",
             start.elapsed()
         );
@@ -427,7 +436,7 @@ impl<'a, 'c> OperationsSchema<'a, 'c> {
             None => None,
         };
 
-        metrics::histogram!(
+        query_histogram!(
             "sql.chain.operations.eth_withdraw_tx_for_complete_withdrawal",
             start.elapsed()
         );
@@ -455,7 +464,7 @@ impl<'a, 'c> OperationsSchema<'a, 'c> {
             .aggregated_op_final_hash(block_number)
             .await?;
 
-        metrics::histogram!(
+        query_histogram!(
             "sql.chain.operations.eth_withdraw_tx_for_execute_block",
             start.elapsed()
         );
@@ -482,7 +491,7 @@ impl<'a, 'c> OperationsSchema<'a, 'c> {
         let eth_tx_hash =
             eth_withdraw_tx_for_execute_block.or(eth_withdraw_tx_for_complete_withdrawal);
 
-        metrics::histogram!(
+        query_histogram!(
             "sql.chain.operations.eth_tx_for_withdrawal",
             start.elapsed()
         );
@@ -602,7 +611,7 @@ impl<'a, 'c> OperationsSchema<'a, 'c> {
             .execute(self.0.conn())
             .await?;
 
-        metrics::histogram!(
+        query_histogram!(
             "sql.chain.operations.remove_eth_unprocessed_aggregated_ops",
             start.elapsed()
         );
@@ -645,7 +654,7 @@ impl<'a, 'c> OperationsSchema<'a, 'c> {
         .await?;
 
         transaction.commit().await?;
-        metrics::histogram!(
+        query_histogram!(
             "sql.chain.operations.remove_executed_priority_operations",
             start.elapsed()
         );
@@ -666,7 +675,7 @@ impl<'a, 'c> OperationsSchema<'a, 'c> {
         .execute(transaction.conn())
         .await?;
 
-        metrics::histogram!(
+        query_histogram!(
             "sql.chain.operations.remove_aggregate_operations",
             start.elapsed()
         );
@@ -729,7 +738,7 @@ impl<'a, 'c> OperationsSchema<'a, 'c> {
         .await?;
         transaction.commit().await?;
 
-        metrics::histogram!(
+        query_histogram!(
             "sql.chain.operations.remove_aggregate_operations_and_bindings",
             start.elapsed()
         );
@@ -17,8 +17,8 @@ use zksync_api_types::{
 };
 use zksync_crypto::params;
 use zksync_types::{
-    aggregated_operations::AggregatedActionType, tx::TxHash, Address, BlockNumber, TokenId,
-    ZkSyncOp, ZkSyncTx, H256,
+    aggregated_operations::AggregatedActionType, tx::TxHash, Address, BlockNumber, PriorityOpId,
+    TokenId, ZkSyncOp, ZkSyncTx, H256,
 };
 
 // Local imports
@@ -345,7 +345,7 @@ impl<'a, 'c> OperationsExtSchema<'a, 'c> {
     ) -> QueryResult<PriorityOpReceiptResponse> {
         let start = Instant::now();
         let stored_executed_prior_op = OperationsSchema(self.0)
-            .get_executed_priority_operation(op_id)
+            .get_executed_priority_operation(PriorityOpId(u64::from(op_id)))
             .await?;
 
         let result = match stored_executed_prior_op {
@@ -4,12 +4,12 @@ use std::fmt;
 use sqlx::{postgres::Postgres, PgConnection, Transaction};
 // Workspace imports
 // Local imports
-use super::PooledConnection;
+use super::{tags::ConnectionTagGuard, PooledConnection};
 
 /// Connection holder unifies the type of underlying connection, which
 /// can be either pooled or direct.
 pub enum ConnectionHolder<'a> {
-    Pooled(PooledConnection),
+    Pooled(PooledConnection, ConnectionTagGuard),
     Direct(PgConnection),
     Transaction(Transaction<'a, Postgres>),
 }
@@ -17,7 +17,7 @@ pub enum ConnectionHolder<'a> {
 impl<'a> fmt::Debug for ConnectionHolder<'a> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Self::Pooled(_) => write!(f, "Pooled connection"),
+            Self::Pooled(_, tag) => write!(f, "Pooled connection ({})", tag.tag()),
             Self::Direct(_) => write!(f, "Direct connection"),
             Self::Transaction(_) => write!(f, "Database Transaction"),
         }
@@ -1,17 +1,23 @@
 // Built-in deps
-use std::{fmt, time::Duration, time::Instant};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    time::Duration,
+    time::Instant,
+};
 // External imports
 use async_trait::async_trait;
-use deadpool::managed::{Manager, PoolConfig, RecycleResult, Timeouts};
+use deadpool::managed::{Manager, PoolConfig, PoolError, RecycleResult, Timeouts};
 use deadpool::Runtime;
 use sqlx::{Connection, Error as SqlxError, PgConnection};
-use tokio::time;
+use tokio::{task::JoinHandle, time};
 // Local imports
 // use self::recoverable_connection::RecoverableConnection;
-use crate::{get_database_replica_url, get_database_url, StorageProcessor};
-use zksync_utils::parse_env;
+use self::tags::{ConnectionTags, DEFAULT_CONNECTION_TAG};
+use crate::{get_database_url, StorageProcessor};
 
 pub mod holder;
+pub mod tags;
 
 type Pool = deadpool::managed::Pool<DbPool>;
 
@@ -19,6 +25,62 @@ pub type PooledConnection = deadpool::managed::Object<DbPool>;
 
 pub const DB_CONNECTION_RETRIES: u32 = 3;
 
+/// Size of the connection pool used when neither the size is provided explicitly
+/// nor the `DATABASE_POOL_SIZE` environment variable is set.
+pub const DEFAULT_DB_POOL_SIZE: u32 = 10;
+
+/// Pool size above which a warning is emitted. Equals to the default Postgres `max_connections` value,
+/// so a larger pool is likely to exhaust the database connections (especially with several pools in use).
+pub const DB_POOL_SIZE_WARNING_THRESHOLD: u32 = 100;
+
+/// Returns the pool size to use: the explicitly provided one, the value of the `DATABASE_POOL_SIZE`
+/// environment variable, or `DEFAULT_DB_POOL_SIZE` if the variable is not set.
+///
+/// Panics with a descriptive message if the size is invalid.
+fn db_pool_size(pool_max_size: Option<u32>) -> u32 {
+    let size = pool_max_size.unwrap_or_else(|| match std::env::var("DATABASE_POOL_SIZE") {
+        Ok(value) => value.parse().unwrap_or_else(|e| {
+            panic!(
+                "DATABASE_POOL_SIZE must be a positive integer, got '{}': {}",
+                value, e
+            )
+        }),
+        Err(_) => {
+            vlog::info!(
+                "DATABASE_POOL_SIZE is not set, using the default pool size {}",
+                DEFAULT_DB_POOL_SIZE
+            );
+            DEFAULT_DB_POOL_SIZE
+        }
+    });
+    validate_db_pool_size(size).unwrap_or_else(|e| panic!("{}", e))
+}
+
+/// Checks that the pool size is usable: zero is rejected, while sizes above
+/// `DB_POOL_SIZE_WARNING_THRESHOLD` are allowed, but reported.
+fn validate_db_pool_size(size: u32) -> anyhow::Result<u32> {
+    anyhow::ensure!(
+        size > 0,
+        "Database pool size must be positive, set DATABASE_POOL_SIZE to the number of connections"
+    );
+    if size > DB_POOL_SIZE_WARNING_THRESHOLD {
+        vlog::warn!(
+            "Database pool size {} is above {}, the database may run out of connections. \
+             Check the `max_connections` setting of Postgres",
+            size,
+            DB_POOL_SIZE_WARNING_THRESHOLD
+        );
+    }
+    Ok(size)
+}
+
+/// Returns the URL of the database replica, if it's configured and differs from the main database URL.
+fn configured_replica_url() -> Option<String> {
+    std::env::var("DATABASE_REPLICA_URL")
+        .ok()
+        .filter(|replica_url| *replica_url != get_database_url())
+}
+
 #[derive(Clone)]
 pub struct DbPool {
     url: String,
@@ -55,6 +117,23 @@ impl Manager for DbPool {
 #[derive(Clone)]
 pub struct ConnectionPool {
     pool: Pool,
+    /// Pool of connections to the other database instance (primary for the replica pool and vice versa).
+    /// Only used by `access_storage_read_only` when the database of `pool` is unreachable.
+    fallback_pool: Option<Pool>,
+    tags: ConnectionTags,
+}
+
+/// Snapshot of the connection pool state.
+#[derive(Debug, Clone)]
+pub struct ConnectionPoolStats {
+    /// Maximum number of connections in the pool.
+    pub max_size: usize,
+    /// Number of connections currently created by the pool.
+    pub size: usize,
+    /// Number of idle connections. Negative value means that there are tasks waiting for a connection.
+    pub available: isize,
+    /// Number of acquired connections for each tag.
+    pub active_tags: HashMap<&'static str, usize>,
 }
 
 impl fmt::Debug for ConnectionPool {
@@ -66,28 +145,43 @@ impl fmt::Debug for ConnectionPool {
 impl ConnectionPool {
     /// Establishes a pool of the connections to the database and
     /// creates a new `ConnectionPool` object.
-    /// pool_max_size - number of connections in pool, if not set env variable "DATABASE_POOL_SIZE" is going to be used.
+    /// pool_max_size - number of connections in pool, if not set env variable "DATABASE_POOL_SIZE" is going to be used
+    /// (or `DEFAULT_DB_POOL_SIZE` if the variable is not set either).
     pub fn new(pool_max_size: Option<u32>) -> Self {
-        let database_url = get_database_url();
-        let max_size = pool_max_size.unwrap_or_else(|| parse_env("DATABASE_POOL_SIZE"));
-
-        let pool = DbPool::create(database_url, max_size as usize);
-
-        Self { pool }
+        let max_size = db_pool_size(pool_max_size);
+        Self::with_fallback(get_database_url(), configured_replica_url(), max_size)
     }
 
     /// Establishes a pool of the connections to the replica of database and
-    /// creates a new `ConnectionPool` object.
+    /// creates a new `ConnectionPool` object. If the replica is not configured,
+    /// the pool connects to the main database.
     /// pool_max_size - number of connections in pool,
-    /// if not set env variable "DATABASE_POOL_SIZE" is going to be used.
+    /// if not set env variable "DATABASE_POOL_SIZE" is going to be used
+    /// (or `DEFAULT_DB_POOL_SIZE` if the variable is not set either).
     pub fn new_readonly_pool(pool_max_size: Option<u32>) -> Self {
-        let database_url = get_database_replica_url();
-        let max_size = pool_max_size.unwrap_or_else(|| parse_env("DATABASE_POOL_SIZE"));
+        let max_size = db_pool_size(pool_max_size);
+        match configured_replica_url() {
+            Some(replica_url) => {
+                Self::with_fallback(replica_url, Some(get_database_url()), max_size)
+            }
+            None => Self::with_fallback(get_database_url(), None, max_size),
+        }
+    }
 
-        let pool = DbPool::create(database_url, max_size as usize);
+    /// Creates a pool of connections to the database at `url`, which uses the database
+    /// at `fallback_url` (if any) for read-only access if the former is unreachable.
+    pub(crate) fn with_fallback(url: String, fallback_url: Option<String>, max_size: u32) -> Self {
+        let fallback_pool =
+            fallback_url.map(|fallback_url| DbPool::create(fallback_url, max_size as usize));
+        let pool = DbPool::create(url, max_size as usize);
 
-        Self { pool }
+        Self {
+            pool,
+            fallback_pool,
+            tags: ConnectionTags::default(),
+        }
     }
+
     /// Creates a `StorageProcessor` entity over a recoverable connection.
     /// Upon a database outage connection will block the thread until
     /// it will be able to recover the connection (or, if connection cannot
@@ -97,11 +191,137 @@ impl ConnectionPool {
     /// This method is intended to be used in crucial contexts, where the
     /// database access is must-have (e.g. block committer).
     pub async fn access_storage(&self) -> Result<StorageProcessor<'_>, SqlxError> {
+        self.access_storage_tagged(DEFAULT_CONNECTION_TAG).await
+    }
+
+    /// Same as `access_storage`, but the acquired connection is marked with the provided tag
+    /// (e.g. the name of the subsystem) until it's returned to the pool.
+    /// Tags of the active connections are reported by the `stats` method.
+    pub async fn access_storage_tagged(
+        &self,
+        tag: &'static str,
+    ) -> Result<StorageProcessor<'_>, SqlxError> {
         let start = Instant::now();
         let connection = self.get_pooled_connection().await;
-        metrics::histogram!("sql.connection_acquire", start.elapsed());
+        metrics::histogram!("sql.connection_acquire", start.elapsed(), "tag" => tag);
+
+        Ok(StorageProcessor::from_pool(
+            connection,
+            self.tags.acquire(tag),
+        ))
+    }
+
+    /// Creates a `StorageProcessor` intended for idempotent reads only.
+    ///
+    /// Unlike `access_storage`, if the database of this pool is unreachable, the connection
+    /// is obtained from the fallback database (the primary for the replica pool and vice versa),
+    /// so the API remains available during a failover. The fallback database may be a read-only
+    /// replica, thus the returned storage must never be used for writes.
+    pub async fn access_storage_read_only(&self) -> Result<StorageProcessor<'_>, SqlxError> {
+        let fallback_pool = match &self.fallback_pool {
+            Some(fallback_pool) => fallback_pool,
+            None => return self.access_storage().await,
+        };
 
-        Ok(StorageProcessor::from_pool(connection))
+        let start = Instant::now();
+        let connection = self
+            .get_pooled_connection_with_fallback(fallback_pool)
+            .await?;
+        metrics::histogram!("sql.connection_acquire", start.elapsed(), "tag" => DEFAULT_CONNECTION_TAG);
+
+        Ok(StorageProcessor::from_pool(
+            connection,
+            self.tags.acquire(DEFAULT_CONNECTION_TAG),
+        ))
+    }
+
+    /// Returns the current state of the pool along with the tags of acquired connections.
+    pub fn stats(&self) -> ConnectionPoolStats {
+        let status = self.pool.status();
+        ConnectionPoolStats {
+            max_size: status.max_size,
+            size: status.size,
+            available: status.available,
+            active_tags: self.tags.active(),
+        }
+    }
+
+    /// Spawns a task that reports the pool `stats` as metrics every `interval`.
+    /// `name` distinguishes the pools (e.g. the master and the replica one) in the reported metrics.
+    pub fn run_stats_reporter(&self, name: &'static str, interval: Duration) -> JoinHandle<()> {
+        let pool = self.clone();
+        tokio::spawn(async move {
+            let mut timer = time::interval(interval);
+            // All the tags reported so far, so the gauges are reset once the connections are released.
+            let mut reported_tags: HashSet<&'static str> = HashSet::new();
+            loop {
+                timer.tick().await;
+
+                let stats = pool.stats();
+                metrics::gauge!("sql.pool.size", stats.size as f64, "pool" => name);
+                metrics::gauge!("sql.pool.available", stats.available as f64, "pool" => name);
+                if stats.available < 0 {
+                    vlog::warn!(
+                        "Connection pool '{}' is exhausted: {} tasks are waiting for a connection, active connections: {:?}",
+                        name,
+                        -stats.available,
+                        stats.active_tags
+                    );
+                }
+
+                reported_tags.extend(stats.active_tags.keys().copied());
+                for &tag in &reported_tags {
+                    let active = stats.active_tags.get(tag).copied().unwrap_or_default();
+                    metrics::gauge!(
+                        "sql.pool.active_connections",
+                        active as f64,
+                        "pool" => name,
+                        "tag" => tag
+                    );
+                }
+            }
+        })
+    }
+
+    /// Same as `get_pooled_connection`, but every failed attempt to get a connection from the pool
+    /// is followed by an attempt to get it from the `fallback_pool`. Returns an error instead of
+    /// panicking if both databases remain unreachable after all the retries.
+    async fn get_pooled_connection_with_fallback(
+        &self,
+        fallback_pool: &Pool,
+    ) -> Result<PooledConnection, SqlxError> {
+        let mut retry_count = 0;
+
+        let mut one_second = time::interval(Duration::from_secs(1));
+
+        loop {
+            match self.pool.get().await {
+                Ok(connection) => return Ok(connection),
+                Err(err) => {
+                    vlog::warn!(
+                        "Failed to get connection to db: {}. Falling back to another database for read-only access",
+                        err
+                    );
+                    metrics::increment_counter!("sql.connection_fallback");
+                }
+            }
+
+            match fallback_pool.get().await {
+                Ok(connection) => return Ok(connection),
+                Err(err) if retry_count >= DB_CONNECTION_RETRIES => {
+                    return Err(match err {
+                        PoolError::Backend(err) => err,
+                        PoolError::Closed => SqlxError::PoolClosed,
+                        _ => SqlxError::PoolTimedOut,
+                    });
+                }
+                Err(_) => retry_count += 1,
+            }
+
+            // Backing off for one second if facing an error
+            vlog::warn!("Failed to get connection to both databases. Backing off for 1 second");
+            one_second.tick().await;
+        }
     }
 
     async fn get_pooled_connection(&self) -> PooledConnection {
@@ -126,3 +346,25 @@ impl ConnectionPool {
         self.pool.get().await.unwrap()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn db_pool_size_validation() {
+        assert!(validate_db_pool_size(0).is_err());
+        assert_eq!(validate_db_pool_size(1).unwrap(), 1);
+        assert_eq!(
+            validate_db_pool_size(DEFAULT_DB_POOL_SIZE).unwrap(),
+            DEFAULT_DB_POOL_SIZE
+        );
+        // Large sizes are only reported, not rejected.
+        assert_eq!(
+            validate_db_pool_size(DB_POOL_SIZE_WARNING_THRESHOLD + 1).unwrap(),
+            DB_POOL_SIZE_WARNING_THRESHOLD + 1
+        );
+        // Explicitly provided size takes precedence over the environment.
+        assert_eq!(db_pool_size(Some(5)), 5);
+    }
+}
@@ -0,0 +1,92 @@
+// Built-in deps
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::{Arc, Mutex},
+};
+
+/// Tag assigned to the connections obtained via `ConnectionPool::access_storage`.
+pub const DEFAULT_CONNECTION_TAG: &str = "untagged";
+
+/// Registry of the tags of connections currently acquired from the pool.
+///
+/// Tags allow to determine which subsystem (e.g. committer, API or state keeper)
+/// holds the connections, which is useful when the pool gets exhausted.
+#[derive(Debug, Clone, Default)]
+pub struct ConnectionTags(Arc<Mutex<HashMap<&'static str, usize>>>);
+
+impl ConnectionTags {
+    /// Registers a connection with the provided tag. The connection is considered
+    /// active until the returned guard is dropped.
+    pub fn acquire(&self, tag: &'static str) -> ConnectionTagGuard {
+        *self.0.lock().unwrap().entry(tag).or_default() += 1;
+        ConnectionTagGuard {
+            tags: self.clone(),
+            tag,
+        }
+    }
+
+    /// Returns the number of active connections for each tag.
+    pub fn active(&self) -> HashMap<&'static str, usize> {
+        self.0.lock().unwrap().clone()
+    }
+
+    fn release(&self, tag: &'static str) {
+        let mut tags = self.0.lock().unwrap();
+        if let Some(count) = tags.get_mut(tag) {
+            *count -= 1;
+            if *count == 0 {
+                tags.remove(tag);
+            }
+        }
+    }
+}
+
+/// Guard that keeps the connection tag registered while the connection is in use.
+pub struct ConnectionTagGuard {
+    tags: ConnectionTags,
+    tag: &'static str,
+}
+
+impl ConnectionTagGuard {
+    pub fn tag(&self) -> &'static str {
+        self.tag
+    }
+}
+
+impl fmt::Debug for ConnectionTagGuard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ConnectionTagGuard({})", self.tag)
+    }
+}
+
+impl Drop for ConnectionTagGuard {
+    fn drop(&mut self) {
+        self.tags.release(self.tag);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn connection_tags() {
+        let tags = ConnectionTags::default();
+        let committer = tags.acquire("committer");
+        let api_1 = tags.acquire("api");
+        let api_2 = tags.acquire("api");
+
+        let active = tags.active();
+        assert_eq!(active.len(), 2);
+        assert_eq!(active["committer"], 1);
+        assert_eq!(active["api"], 2);
+
+        drop(api_1);
+        assert_eq!(tags.active()["api"], 1);
+
+        drop(committer);
+        drop(api_2);
+        assert!(tags.active().is_empty());
+    }
+}
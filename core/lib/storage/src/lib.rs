@@ -77,11 +77,23 @@
 // Built-in deps
 use std::env;
 // External imports
+use futures_util::future::BoxFuture;
 use sqlx::{postgres::Postgres, Connection, PgConnection, Transaction};
 // Workspace imports
 use zksync_types::{ActionType, BlockNumber};
 // Local imports
-use crate::connection::{holder::ConnectionHolder, PooledConnection};
+use crate::connection::{holder::ConnectionHolder, tags::ConnectionTagGuard, PooledConnection};
+
+/// Reports the duration of a storage query to the metrics. With the `storage_timing`
+/// feature enabled, the duration is also recorded to the aggregated query timings.
+macro_rules! query_histogram {
+    ($name:literal, $elapsed:expr $(, $label:literal => $value:literal)* $(,)?) => {{
+        let elapsed = $elapsed;
+        metrics::histogram!($name, elapsed $(, $label => $value)*);
+        #[cfg(feature = "storage_timing")]
+        $crate::timing::record(concat!($name $(, "[", $label, "=", $value, "]")*), elapsed);
+    }};
+}
 
 // mod schema;
 #[cfg(test)]
@@ -99,12 +111,14 @@ pub mod listener;
 pub mod misc;
 pub mod prover;
 pub mod test_data;
+#[cfg(feature = "storage_timing")]
+pub mod timing;
 pub mod tokens;
 pub mod utils;
 
 use forced_exit_requests::ForcedExitRequestsSchema;
 
-pub use crate::connection::ConnectionPool;
+pub use crate::connection::{ConnectionPool, ConnectionPoolStats};
 pub use sqlx::types::BigDecimal;
 pub type QueryResult<T, E = anyhow::Error> = Result<T, E>;
 
@@ -191,12 +205,53 @@ impl<'a> StorageProcessor<'a> {
         }
     }
 
+    /// Runs the provided closure within a database transaction.
+    /// The transaction is committed if the closure returns `Ok` and rolled back otherwise.
+    ///
+    /// ## Example:
+    ///
+    /// ```ignore
+    /// storage
+    ///     .transaction(|transaction| {
+    ///         Box::pin(async move {
+    ///             transaction.tokens_schema().store_or_update_token(token).await?;
+    ///             Ok(())
+    ///         })
+    ///     })
+    ///     .await?;
+    /// ```
+    pub async fn transaction<T, F>(&mut self, f: F) -> QueryResult<T>
+    where
+        F: for<'t> FnOnce(&'t mut StorageProcessor<'_>) -> BoxFuture<'t, QueryResult<T>>,
+    {
+        let mut transaction = self.start_transaction().await?;
+        match f(&mut transaction).await {
+            Ok(value) => {
+                transaction.commit().await?;
+                Ok(value)
+            }
+            Err(err) => {
+                transaction.rollback().await?;
+                Err(err)
+            }
+        }
+    }
+
+    async fn rollback(self) -> QueryResult<()> {
+        if let ConnectionHolder::Transaction(transaction) = self.conn {
+            transaction.rollback().await?;
+            Ok(())
+        } else {
+            panic!("StorageProcessor::rollback can only be invoked after calling StorageProcessor::begin_transaction");
+        }
+    }
+
     /// Creates a `StorageProcessor` using a pool of connections.
     /// This method borrows one of the connections from the pool, and releases it
     /// after `drop`.
-    pub fn from_pool(conn: PooledConnection) -> Self {
+    pub fn from_pool(conn: PooledConnection, tag: ConnectionTagGuard) -> Self {
         Self {
-            conn: ConnectionHolder::Pooled(conn),
+            conn: ConnectionHolder::Pooled(conn, tag),
             in_transaction: false,
         }
     }
@@ -245,7 +300,7 @@ impl<'a> StorageProcessor<'a> {
 
     fn conn(&mut self) -> &mut PgConnection {
         match &mut self.conn {
-            ConnectionHolder::Pooled(conn) => conn,
+            ConnectionHolder::Pooled(conn, _) => conn,
             ConnectionHolder::Direct(conn) => conn,
             ConnectionHolder::Transaction(conn) => conn,
         }
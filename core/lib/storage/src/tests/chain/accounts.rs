@@ -57,6 +57,10 @@ async fn stored_accounts(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
         .await?;
     assert_eq!(last_finalized, 0);
     assert_eq!(*last_committed, 0);
+    assert!(AccountSchema(&mut storage)
+        .account_creation_block(AccountId(1))
+        .await?
+        .is_none());
 
     // Create several accounts.
     let accounts = AccountMap::default();
@@ -104,6 +108,12 @@ async fn stored_accounts(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
             .await?;
         assert_eq!(last_finalized, 0);
         assert_eq!(*last_committed, 1);
+        assert_eq!(
+            AccountSchema(&mut storage)
+                .account_creation_block(*account_id)
+                .await?,
+            Some(BlockNumber(1))
+        );
 
         let account_state = AccountSchema(&mut storage)
             .account_state_by_address(account.address)
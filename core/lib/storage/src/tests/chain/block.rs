@@ -1,4 +1,7 @@
+// Built-in imports
+use std::collections::HashSet;
 // External imports
+use num::BigUint;
 // Workspace imports
 use zksync_api_types::v02::pagination::{
     ApiEither, BlockAndTxHash, PaginationDirection, PaginationQuery,
@@ -9,7 +12,8 @@ use zksync_types::{
     block::Block,
     helpers::apply_updates,
     tx::{ChangePubKeyType, TxHash},
-    AccountId, AccountMap, AccountUpdate, AccountUpdates, BlockNumber, TokenId,
+    AccountId, AccountMap, AccountUpdate, AccountUpdates, Address, BlockNumber, Nonce, PubKeyHash,
+    TokenId,
 };
 // Local imports
 use super::operations_ext::{
@@ -1292,6 +1296,117 @@ async fn test_get_block_number_by_hash(mut storage: StorageProcessor<'_>) -> Que
     Ok(())
 }
 
+/// Checks that `accounts_changed_between` collects accounts updated in the requested range
+/// of the committed blocks only.
+#[db_test]
+async fn test_accounts_changed_between(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let create = |id: u32| {
+        (
+            AccountId(id),
+            AccountUpdate::Create {
+                address: Address::random(),
+                nonce: Nonce(0),
+            },
+        )
+    };
+    let update_balance = |id: u32| {
+        (
+            AccountId(id),
+            AccountUpdate::UpdateBalance {
+                old_nonce: Nonce(0),
+                new_nonce: Nonce(0),
+                balance_update: (TokenId(0), BigUint::from(0u32), BigUint::from(10u32)),
+            },
+        )
+    };
+    let change_pub_key = |id: u32| {
+        (
+            AccountId(id),
+            AccountUpdate::ChangePubKeyHash {
+                old_pub_key_hash: PubKeyHash::default(),
+                new_pub_key_hash: PubKeyHash::default(),
+                old_nonce: Nonce(0),
+                new_nonce: Nonce(1),
+            },
+        )
+    };
+
+    let blocks_updates = vec![
+        vec![create(1), update_balance(1)],
+        // Account 0 is the fee account.
+        vec![
+            update_balance(1),
+            create(2),
+            update_balance(2),
+            update_balance(0),
+        ],
+        vec![change_pub_key(2)],
+        // This block is not committed.
+        vec![create(4), update_balance(4)],
+    ];
+    for (idx, updates) in blocks_updates.iter().enumerate() {
+        let block_number = BlockNumber(idx as u32 + 1);
+        BlockSchema(&mut storage)
+            .save_full_block(gen_sample_block(
+                block_number,
+                BLOCK_SIZE_CHUNKS,
+                Default::default(),
+            ))
+            .await?;
+        StateSchema(&mut storage)
+            .commit_state_update(block_number, updates, 0)
+            .await?;
+    }
+    for block_number in 1..=3 {
+        OperationsSchema(&mut storage)
+            .store_aggregated_action(gen_unique_aggregated_operation(
+                BlockNumber(block_number),
+                AggregatedActionType::CommitBlocks,
+                BLOCK_SIZE_CHUNKS,
+            ))
+            .await?;
+    }
+
+    let accounts = |ids: &[u32]| ids.iter().copied().map(AccountId).collect::<HashSet<_>>();
+    let mut schema = BlockSchema(&mut storage);
+    assert_eq!(
+        schema
+            .accounts_changed_between(BlockNumber(0), BlockNumber(1))
+            .await?,
+        accounts(&[1])
+    );
+    assert_eq!(
+        schema
+            .accounts_changed_between(BlockNumber(1), BlockNumber(3))
+            .await?,
+        accounts(&[0, 1, 2])
+    );
+    assert_eq!(
+        schema
+            .accounts_changed_between(BlockNumber(2), BlockNumber(3))
+            .await?,
+        accounts(&[2])
+    );
+    // Block `from` itself is not included.
+    assert!(schema
+        .accounts_changed_between(BlockNumber(3), BlockNumber(3))
+        .await?
+        .is_empty());
+    // Blocks after the last committed one are ignored.
+    assert_eq!(
+        schema
+            .accounts_changed_between(BlockNumber(2), BlockNumber(10))
+            .await?,
+        accounts(&[2])
+    );
+    assert!(schema
+        .accounts_changed_between(BlockNumber(u32::MAX), BlockNumber(u32::MAX))
+        .await?
+        .is_empty());
+
+    Ok(())
+}
+
 /// Check that `get_block_transactions_hashes` works correctly
 #[db_test]
 async fn test_get_block_transactions_hashes(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
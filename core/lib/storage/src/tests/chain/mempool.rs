@@ -1,5 +1,7 @@
+// Built-in imports
+use std::collections::HashMap;
 // External imports
-use chrono::Utc;
+use chrono::{Duration, Utc};
 // Workspace imports
 use zksync_crypto::rand::{Rng, SeedableRng, XorShiftRng};
 use zksync_types::{
@@ -225,6 +227,220 @@ async fn store_load_batch(mut storage: StorageProcessor<'_>) -> QueryResult<()>
     Ok(())
 }
 
+/// Checks that the transactions inserted as a batch are loaded back as a single group,
+/// and the standalone transactions don't belong to any batch.
+#[db_test]
+async fn batch_grouping_integrity(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let txs = gen_transfers(9);
+    let alone_txs = [&txs[0], &txs[4], &txs[8]];
+    let batch_1 = &txs[1..4];
+    let batch_2 = &txs[5..8];
+
+    MempoolSchema(&mut storage).insert_tx(alone_txs[0]).await?;
+    MempoolSchema(&mut storage)
+        .insert_batch(batch_1, vec![])
+        .await?;
+    MempoolSchema(&mut storage).insert_tx(alone_txs[1]).await?;
+    MempoolSchema(&mut storage)
+        .insert_batch(batch_2, vec![])
+        .await?;
+    MempoolSchema(&mut storage).insert_tx(alone_txs[2]).await?;
+
+    let mut batches = HashMap::new();
+    let mut alone_hashes = Vec::new();
+    for element in MempoolSchema(&mut storage).load_txs(&[]).await? {
+        match element {
+            SignedTxVariant::Batch(batch) => {
+                let hashes: Vec<_> = batch.txs.iter().map(|tx| tx.hash()).collect();
+                assert!(
+                    batches.insert(batch.batch_id, hashes).is_none(),
+                    "batch {} is split into several groups",
+                    batch.batch_id
+                );
+            }
+            SignedTxVariant::Tx(tx) => alone_hashes.push(tx.hash()),
+        }
+    }
+
+    // Each batch is loaded with exactly the same transactions in the same order.
+    assert_eq!(batches.len(), 2);
+    let mut loaded_batches: Vec<_> = batches.into_iter().collect();
+    loaded_batches.sort_by_key(|(batch_id, _)| *batch_id);
+    for ((_, loaded), expected) in loaded_batches.iter().zip(&[batch_1, batch_2]) {
+        let expected: Vec<_> = expected.iter().map(|tx| tx.hash()).collect();
+        assert_eq!(loaded, &expected);
+    }
+
+    // Standalone transactions are not assigned to any batch.
+    let expected_alone: Vec<_> = alone_txs.iter().map(|tx| tx.hash()).collect();
+    assert_eq!(alone_hashes, expected_alone);
+
+    Ok(())
+}
+
+/// Checks that fetching txs for the block never splits a batch across the limit.
+#[db_test]
+async fn fetch_pending_for_block(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let txs = gen_transfers(6);
+    let alone_txs = &txs[0..3];
+    let batch = &txs[3..5];
+    let last_tx = &txs[5];
+
+    for tx in alone_txs {
+        MempoolSchema(&mut storage).insert_tx(tx).await?;
+    }
+    MempoolSchema(&mut storage)
+        .insert_batch(batch, vec![])
+        .await?;
+    MempoolSchema(&mut storage).insert_tx(last_tx).await?;
+
+    // The batch straddles the limit, so it should be deferred whole
+    // along with the transaction that comes after it.
+    let fetched = MempoolSchema(&mut storage)
+        .fetch_pending_for_block(4)
+        .await?;
+    assert_eq!(fetched.len(), alone_txs.len());
+    assert!(fetched
+        .iter()
+        .all(|variant| matches!(variant, SignedTxVariant::Tx(_))));
+
+    // Once the limit is large enough, the batch is returned as a whole.
+    let fetched = MempoolSchema(&mut storage)
+        .fetch_pending_for_block(5)
+        .await?;
+    assert_eq!(fetched.len(), alone_txs.len() + 1);
+    match &fetched[3] {
+        SignedTxVariant::Batch(fetched_batch) => assert_eq!(fetched_batch.txs.len(), batch.len()),
+        SignedTxVariant::Tx(_) => panic!("expected to fetch the batch of transactions"),
+    };
+
+    // Everything fits.
+    let fetched = MempoolSchema(&mut storage)
+        .fetch_pending_for_block(txs.len())
+        .await?;
+    assert_eq!(fetched.len(), alone_txs.len() + 2);
+
+    Ok(())
+}
+
+/// Checks that only txs that require Ethereum signature but don't have one are reported.
+#[db_test]
+async fn get_txs_missing_eth_sign(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let mut txs = zksync_txs();
+    // Transfer without a signature should be reported.
+    txs[0].eth_sign_data = None;
+    // Transfer with a signature should not be reported.
+    let signed_transfer_hash = txs[1].hash();
+    // Withdraw without a signature should be reported.
+    txs[2].eth_sign_data = None;
+    // `ChangePubKey` doesn't need a signature in the mempool.
+    txs[3].eth_sign_data = None;
+
+    for tx in &txs {
+        MempoolSchema(&mut storage).insert_tx(tx).await?;
+    }
+    // Batched transactions don't have a per-transaction signature.
+    let mut batch = gen_transfers(2);
+    for tx in &mut batch {
+        tx.eth_sign_data = None;
+    }
+    MempoolSchema(&mut storage)
+        .insert_batch(&batch, vec![])
+        .await?;
+
+    let missing: Vec<_> = MempoolSchema(&mut storage)
+        .get_txs_missing_eth_sign()
+        .await?
+        .into_iter()
+        .map(|tx| tx.hash())
+        .collect();
+    assert_eq!(missing, vec![txs[0].hash(), txs[2].hash()]);
+    assert!(!missing.contains(&signed_transfer_hash));
+
+    Ok(())
+}
+
+/// Checks that txs are filtered by the time they were added to the mempool.
+#[db_test]
+async fn get_txs_in_range(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let base_time = Utc::now() - Duration::hours(1);
+    let mut txs = gen_transfers(4);
+    for (i, tx) in txs.iter_mut().enumerate() {
+        tx.created_at = base_time + Duration::minutes(10 * i as i64);
+    }
+    // Insert in reverse order to make sure that the result is sorted by `created_at`.
+    for tx in txs.iter().rev() {
+        MempoolSchema(&mut storage).insert_tx(tx).await?;
+    }
+
+    // Window covers the second and the third txs; the upper bound is exclusive.
+    let in_range: Vec<_> = MempoolSchema(&mut storage)
+        .get_txs_in_range(
+            base_time + Duration::minutes(5),
+            base_time + Duration::minutes(30),
+        )
+        .await?
+        .into_iter()
+        .map(|tx| tx.hash())
+        .collect();
+    assert_eq!(in_range, vec![txs[1].hash(), txs[2].hash()]);
+
+    // Empty window.
+    let in_range = MempoolSchema(&mut storage)
+        .get_txs_in_range(base_time - Duration::hours(1), base_time)
+        .await?;
+    assert!(in_range.is_empty());
+
+    Ok(())
+}
+
+/// Checks that requeueing an executed tx preserves its data and is idempotent.
+#[db_test]
+async fn requeue_tx(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let txs = gen_transfers(2);
+    let executed_txs: Vec<_> = txs
+        .iter()
+        .zip(vec![None, Some(7)])
+        .map(|(tx, batch_id)| ExecutedTx {
+            signed_tx: tx.clone(),
+            success: true,
+            op: None,
+            fail_reason: None,
+            block_index: Some(0),
+            created_at: Utc::now(),
+            batch_id,
+        })
+        .collect();
+
+    // Requeue every transaction twice, the second attempt should be no-op.
+    for _ in 0..2 {
+        for executed_tx in &executed_txs {
+            MempoolSchema(&mut storage).requeue_tx(executed_tx).await?;
+        }
+    }
+
+    let txs_from_db = MempoolSchema(&mut storage).load_txs(&[]).await?;
+    assert_eq!(txs_from_db.len(), executed_txs.len());
+
+    match &txs_from_db[0] {
+        SignedTxVariant::Tx(tx) => {
+            assert_eq!(tx.hash(), txs[0].hash());
+            assert_eq!(tx.eth_sign_data, txs[0].eth_sign_data);
+        }
+        SignedTxVariant::Batch(_) => panic!("expected to load a single transaction"),
+    };
+    match &txs_from_db[1] {
+        SignedTxVariant::Batch(batch) => {
+            assert_eq!(batch.batch_id, 7);
+            assert_eq!(batch.txs.len(), 1);
+            assert_eq!(batch.txs[0].hash(), txs[1].hash());
+        }
+        SignedTxVariant::Tx(_) => panic!("expected to load a batch of transactions"),
+    };
+
+    Ok(())
+}
+
 /// Checks that removed txs won't appear on the next load.
 #[db_test]
 async fn remove_txs(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
@@ -512,3 +728,62 @@ async fn test_return_executed_txs_to_mempool(mut storage: StorageProcessor<'_>)
     assert_eq!(block_tx.variance_name(), "FullExit");
     Ok(())
 }
+
+/// Checks that only the confirmed and not reverted priority operations with the deadline
+/// not later than the current Ethereum block are considered expired.
+#[db_test]
+async fn get_expired_unprocessed_priority_ops(
+    mut storage: StorageProcessor<'_>,
+) -> QueryResult<()> {
+    const CURRENT_ETH_BLOCK: u64 = 15;
+
+    let priority_op = |serial_id: u64, deadline_block: u64| PriorityOp {
+        serial_id,
+        data: ZkSyncPriorityOp::FullExit(FullExit {
+            account_id: AccountId(0),
+            eth_address: Address::zero(),
+            token: TokenId(0),
+            is_legacy: false,
+        }),
+        deadline_block,
+        eth_hash: H256::from_low_u64_be(serial_id),
+        eth_block: 0,
+        eth_block_index: None,
+    };
+    let confirmed_ops = vec![
+        // Expired.
+        priority_op(0, CURRENT_ETH_BLOCK - 5),
+        // Not expired yet.
+        priority_op(1, CURRENT_ETH_BLOCK + 5),
+        // Expires exactly at the current block.
+        priority_op(2, CURRENT_ETH_BLOCK),
+        // Expired, but reverted.
+        priority_op(3, CURRENT_ETH_BLOCK - 5),
+    ];
+    // Expired, but not confirmed.
+    let unconfirmed_ops = vec![priority_op(4, CURRENT_ETH_BLOCK - 5)];
+
+    let mut mempool = MempoolSchema(&mut storage);
+    mempool.insert_priority_ops(&confirmed_ops, true).await?;
+    mempool.insert_priority_ops(&unconfirmed_ops, false).await?;
+    sqlx::query("UPDATE mempool_priority_operations SET reverted = true WHERE serial_id = $1")
+        .bind(3i64)
+        .execute(storage.conn())
+        .await?;
+
+    let expired: Vec<_> = MempoolSchema(&mut storage)
+        .get_expired_unprocessed_priority_ops(CURRENT_ETH_BLOCK)
+        .await?
+        .into_iter()
+        .map(|op| op.serial_id)
+        .collect();
+    assert_eq!(expired, vec![0, 2]);
+
+    // No operations are expired before the earliest deadline.
+    let expired = MempoolSchema(&mut storage)
+        .get_expired_unprocessed_priority_ops(CURRENT_ETH_BLOCK - 6)
+        .await?;
+    assert!(expired.is_empty());
+
+    Ok(())
+}
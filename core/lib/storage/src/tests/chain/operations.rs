@@ -2,8 +2,8 @@
 use chrono::{Duration, Utc};
 // Workspace imports
 use zksync_types::{
-    aggregated_operations::AggregatedActionType, Address, BlockNumber, Deposit, SequentialTxId,
-    ZkSyncPriorityOp, H256,
+    aggregated_operations::AggregatedActionType, Address, BlockNumber, Deposit, PriorityOpId,
+    SequentialTxId, ZkSyncPriorityOp, H256,
 };
 // Local imports
 use crate::chain::mempool::MempoolSchema;
@@ -11,6 +11,7 @@ use crate::{
     chain::{
         block::BlockSchema,
         operations::{
+            is_priority_op_expired,
             records::{NewExecutedPriorityOperation, NewExecutedTransaction},
             OperationsSchema,
         },
@@ -120,7 +121,7 @@ async fn executed_priority_operations(mut storage: StorageProcessor<'_>) -> Quer
         .await?;
 
     let stored_operation = OperationsSchema(&mut storage)
-        .get_executed_priority_operation(executed_tx.priority_op_serialid as u32)
+        .get_executed_priority_operation(PriorityOpId(executed_tx.priority_op_serialid as u64))
         .await?
         .expect("No operation was found for a valid hash");
 
@@ -135,6 +136,9 @@ async fn executed_priority_operations(mut storage: StorageProcessor<'_>) -> Quer
     );
     assert_eq!(stored_operation.deadline_block, executed_tx.deadline_block);
     assert_eq!(stored_operation.eth_hash, executed_tx.eth_hash);
+    assert!(!is_priority_op_expired(&stored_operation, 99));
+    assert!(is_priority_op_expired(&stored_operation, 100));
+    assert!(is_priority_op_expired(&stored_operation, 101));
 
     Ok(())
 }
@@ -200,7 +204,9 @@ async fn duplicated_operations(mut storage: StorageProcessor<'_>) -> QueryResult
         .await?
         .is_some());
     assert!(OperationsSchema(&mut storage)
-        .get_executed_priority_operation(executed_priority_op.priority_op_serialid as u32)
+        .get_executed_priority_operation(PriorityOpId(
+            executed_priority_op.priority_op_serialid as u64,
+        ))
         .await?
         .is_some());
 
@@ -456,11 +462,17 @@ async fn priority_ops_hashes(mut storage: StorageProcessor<'_>) -> QueryResult<(
         .chain()
         .operations_schema()
         .get_executed_priority_operation_by_eth_hash(&executed_priority_op.eth_hash)
-        .await?;
+        .await?
+        .expect("No operation was found for a valid eth_hash");
     assert_eq!(
-        op_by_eth_hash.unwrap().priority_op_serialid,
+        op_by_eth_hash.priority_op_serialid,
         executed_priority_op.priority_op_serialid
     );
+    assert_eq!(
+        op_by_eth_hash.block_number,
+        executed_priority_op.block_number
+    );
+    assert_eq!(op_by_eth_hash.block_index, executed_priority_op.block_index);
 
     // Checks that it doesn't find unexisting operation
     let op = storage
@@ -0,0 +1,25 @@
+// Workspace imports
+use zksync_types::TokenId;
+// Local imports
+use crate::{get_database_url, tests::db_test, ConnectionPool, QueryResult, StorageProcessor};
+
+/// URL of the database which is guaranteed to be unreachable.
+const BROKEN_DATABASE_URL: &str = "postgres://postgres@localhost:1/broken";
+
+/// Checks that read-only access falls back to another database when the primary one is down.
+#[db_test]
+async fn read_only_access_fallback(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let pool =
+        ConnectionPool::with_fallback(BROKEN_DATABASE_URL.to_string(), Some(get_database_url()), 1);
+
+    let mut fallback_storage = pool.access_storage_read_only().await?;
+    let eth_token = fallback_storage
+        .tokens_schema()
+        .get_token(TokenId(0).into())
+        .await?;
+    let expected_eth_token = storage.tokens_schema().get_token(TokenId(0).into()).await?;
+    assert!(eth_token.is_some());
+    assert_eq!(eth_token, expected_eth_token);
+
+    Ok(())
+}
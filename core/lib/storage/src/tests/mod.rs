@@ -22,9 +22,13 @@ use once_cell::sync::Lazy;
 use tokio::sync::Mutex;
 // Workspace imports
 use zksync_crypto::rand::{SeedableRng, XorShiftRng};
+use zksync_types::{Token, TokenId, TokenKind};
+// Local imports
+use crate::{QueryResult, StorageProcessor};
 
 pub(crate) mod chain;
 mod config;
+mod connection;
 mod data_restore;
 mod ethereum;
 mod event;
@@ -43,3 +47,55 @@ pub fn create_rng() -> XorShiftRng {
 /// Mutex that's used to avoid database deadlock when accessing
 /// accounts state concurrently in tests.
 static ACCOUNT_MUTEX: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+/// Checks that `StorageProcessor::transaction` commits the changes on success
+/// and leaves no changes on error.
+#[db_test]
+async fn transaction_commit_and_rollback(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let token = |id: u32| Token {
+        id: TokenId(id),
+        address: format!("{:040x}", id).parse().unwrap(),
+        symbol: format!("TKN{}", id),
+        decimals: 18,
+        kind: TokenKind::ERC20,
+        is_nft: false,
+    };
+
+    let result: QueryResult<()> = storage
+        .transaction(|transaction| {
+            Box::pin(async move {
+                transaction
+                    .tokens_schema()
+                    .store_or_update_token(token(1))
+                    .await?;
+                anyhow::bail!("Failure after the token is stored")
+            })
+        })
+        .await;
+    assert!(result.is_err());
+    assert!(!storage
+        .tokens_schema()
+        .load_tokens()
+        .await?
+        .contains_key(&TokenId(1)));
+
+    let tokens_count = storage
+        .transaction(|transaction| {
+            Box::pin(async move {
+                transaction
+                    .tokens_schema()
+                    .store_or_update_token(token(2))
+                    .await?;
+                transaction.tokens_schema().get_count().await
+            })
+        })
+        .await?;
+    assert_eq!(tokens_count, 2);
+    assert!(storage
+        .tokens_schema()
+        .load_tokens()
+        .await?
+        .contains_key(&TokenId(2)));
+
+    Ok(())
+}
@@ -0,0 +1,113 @@
+//! Aggregated timings of the storage queries.
+//!
+//! This module is only available with the `storage_timing` feature enabled.
+//! Durations of the `OperationsSchema`, `BlockSchema` and `MempoolSchema` queries are
+//! recorded by their method names, and `run_timing_reporter` periodically logs the summary.
+
+// Built-in deps
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+// External imports
+use once_cell::sync::Lazy;
+use tokio::{task::JoinHandle, time};
+
+/// Timings of the queries recorded since the last summary.
+static QUERY_TIMINGS: Lazy<Mutex<HashMap<&'static str, QueryTiming>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Aggregated durations of a single query.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct QueryTiming {
+    /// Number of the query invocations.
+    pub count: u64,
+    /// Total duration of the query invocations.
+    pub total: Duration,
+    /// Duration of the slowest query invocation.
+    pub max: Duration,
+}
+
+impl QueryTiming {
+    /// Average duration of the query invocation.
+    pub fn average(&self) -> Duration {
+        if self.count == 0 {
+            Duration::default()
+        } else {
+            self.total / self.count as u32
+        }
+    }
+}
+
+/// Records the duration of the query.
+pub fn record(method: &'static str, elapsed: Duration) {
+    let mut timings = QUERY_TIMINGS.lock().unwrap();
+    let timing = timings.entry(method).or_default();
+    timing.count += 1;
+    timing.total += elapsed;
+    timing.max = timing.max.max(elapsed);
+}
+
+/// Returns the timings recorded since the previous call, sorted by the total duration
+/// in descending order.
+pub fn take_summary() -> Vec<(&'static str, QueryTiming)> {
+    let timings = std::mem::take(&mut *QUERY_TIMINGS.lock().unwrap());
+    let mut summary: Vec<_> = timings.into_iter().collect();
+    summary.sort_by(|(_, lhs), (_, rhs)| rhs.total.cmp(&lhs.total));
+    summary
+}
+
+/// Spawns a task that logs the summary of the query timings every `interval`.
+pub fn run_timing_reporter(interval: Duration) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut timer = time::interval(interval);
+        let mut last_report = Instant::now();
+        loop {
+            timer.tick().await;
+
+            let summary = take_summary();
+            if summary.is_empty() {
+                continue;
+            }
+
+            vlog::info!(
+                "Storage queries summary for the last {:?}:",
+                last_report.elapsed()
+            );
+            for (method, timing) in summary {
+                vlog::info!(
+                    "{}: count {}, total {:?}, avg {:?}, max {:?}",
+                    method,
+                    timing.count,
+                    timing.total,
+                    timing.average(),
+                    timing.max
+                );
+            }
+            last_report = Instant::now();
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn timings_summary() {
+        record("test.fast", Duration::from_millis(1));
+        record("test.slow", Duration::from_millis(10));
+        record("test.slow", Duration::from_millis(20));
+
+        let summary: HashMap<_, _> = take_summary()
+            .into_iter()
+            .filter(|(method, _)| method.starts_with("test."))
+            .collect();
+        assert_eq!(summary["test.fast"].count, 1);
+        assert_eq!(summary["test.slow"].count, 2);
+        assert_eq!(summary["test.slow"].total, Duration::from_millis(30));
+        assert_eq!(summary["test.slow"].max, Duration::from_millis(20));
+        assert_eq!(summary["test.slow"].average(), Duration::from_millis(15));
+    }
+}
@@ -0,0 +1,40 @@
+use thiserror::Error;
+use zksync_basic_types::AccountId;
+
+use crate::SerialId;
+
+#[derive(Debug, Error, PartialEq)]
+pub enum BlockValidationError {
+    #[error("Block timestamp {timestamp} is less than the timestamp of the previous block {previous_timestamp}")]
+    TimestampDecreased {
+        timestamp: u64,
+        previous_timestamp: u64,
+    },
+    #[error("Block fee account {actual} does not match the expected fee account {expected}")]
+    FeeAccountMismatch {
+        expected: AccountId,
+        actual: AccountId,
+    },
+    #[error(
+        "Priority operation with serial id {serial_id} is included into the block more than once"
+    )]
+    DuplicatePriorityOp { serial_id: SerialId },
+    #[error("Successful operation at position {position} in the block has no block index")]
+    MissingBlockIndex { position: usize },
+    #[error("Block index {current} of the operation at position {position} does not exceed the previous block index {previous}")]
+    OperationsNotOrdered {
+        position: usize,
+        previous: u32,
+        current: u32,
+    },
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum BlockSizesError {
+    #[error("List of available block sizes is empty")]
+    Empty,
+    #[error("Block size cannot be zero")]
+    ZeroSize,
+    #[error("Block sizes must be sorted in ascending order without duplicates, but {current} follows {previous}")]
+    NotAscending { previous: usize, current: usize },
+}
@@ -4,18 +4,24 @@ use super::{AccountId, BlockNumber, Fr, PriorityOp, ZkSyncOp};
 use crate::{tx::error::CloseOperationsDisabled, SignedZkSyncTx, TokenId};
 use chrono::Utc;
 use chrono::{DateTime, TimeZone};
+use num::{BigInt, BigUint};
 use parity_crypto::digest::sha256;
 use parity_crypto::Keccak256;
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::time::Duration;
 use zksync_basic_types::{H256, U256};
-use zksync_crypto::franklin_crypto::bellman::pairing::ff::{PrimeField, PrimeFieldRepr};
+use zksync_crypto::franklin_crypto::bellman::pairing::ff::{Field, PrimeField, PrimeFieldRepr};
 use zksync_crypto::params::{CHUNK_BIT_WIDTH, CHUNK_BYTES};
 use zksync_crypto::serialization::FrSerde;
 
+mod error;
 mod incomplete_block;
+mod size_plan;
 
+pub use error::{BlockSizesError, BlockValidationError};
 pub use incomplete_block::IncompleteBlock;
+pub use size_plan::{plan_block_sizes, BlockSizeEstimate, SizePlan};
 
 /// An intermediate state of the block in the zkSync network.
 /// Contains the information about (so far) executed transactions and
@@ -50,9 +56,18 @@ pub struct ExecutedTx {
     pub fail_reason: Option<String>,
     pub block_index: Option<u32>,
     pub created_at: DateTime<Utc>,
+    /// ID of the batch this transaction was executed in, if any.
+    #[serde(default)]
     pub batch_id: Option<i64>,
 }
 
+impl ExecutedTx {
+    /// Returns `true` if the transaction was executed as a part of an atomic batch.
+    pub fn is_batch_member(&self) -> bool {
+        self.batch_id.is_some()
+    }
+}
+
 /// Executed L1 priority operation.
 /// Unlike L2 transactions, L1 priority operations cannot fail in L2.
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -80,10 +95,16 @@ impl ExecutedPriorityOp {
 }
 
 /// Representation of executed operation, which can be either L1 or L2.
+///
+/// This type is persisted to the database, so its serialized form must stay compatible.
+/// Variants are always serialized with `"Tx"` / `"PriorityOp"` tags, while the aliases allow
+/// to deserialize data using the other tag spellings.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(tag = "type")]
 pub enum ExecutedOperations {
+    #[serde(alias = "tx")]
     Tx(Box<ExecutedTx>),
+    #[serde(alias = "priorityOp", alias = "priority_op")]
     PriorityOp(Box<ExecutedPriorityOp>),
 }
 
@@ -168,6 +189,55 @@ impl ExecutedOperations {
     pub fn is_priority(&self) -> bool {
         matches!(self, Self::PriorityOp(_))
     }
+
+    /// Returns the block index of the operation, if any.
+    pub fn block_index(&self) -> Option<u32> {
+        match self {
+            ExecutedOperations::Tx(tx) => tx.block_index,
+            ExecutedOperations::PriorityOp(op) => Some(op.block_index),
+        }
+    }
+
+    /// Converts the operation into a flat event representation.
+    pub fn to_event(&self) -> OperationEvent {
+        let accounts = match self.get_executed_op() {
+            Some(op) => op.get_updated_account_ids(),
+            // Failed transactions have no executed operation, so only the initiator is reported.
+            None => self.account_id().map(|id| vec![id]).unwrap_or_default(),
+        };
+        let amount_info = self
+            .get_executed_op()
+            .and_then(ZkSyncOp::get_amount_info)
+            .and_then(|info| info.into_iter().next());
+        let (token, amount) = match amount_info {
+            Some((token, amount)) => (token, Some(amount)),
+            None => (self.token_id(), None),
+        };
+
+        OperationEvent {
+            kind: self.variance_name(),
+            block_index: self.block_index(),
+            success: self.is_successful(),
+            accounts,
+            token: Some(token),
+            amount,
+        }
+    }
+}
+
+/// Flat representation of the executed operation, uniform for both
+/// L1 and L2 operations.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OperationEvent {
+    /// Name of the operation type, e.g. `Transfer` or `Deposit`.
+    pub kind: String,
+    pub block_index: Option<u32>,
+    pub success: bool,
+    /// Accounts affected by the operation.
+    pub accounts: Vec<AccountId>,
+    pub token: Option<TokenId>,
+    /// Amount moved by the operation, if applicable.
+    pub amount: Option<BigUint>,
 }
 
 /// zkSync network block.
@@ -322,6 +392,34 @@ impl Block {
         Self::encode_fr_for_eth(self.new_root_hash)
     }
 
+    /// Returns the new state root hash as a `0x`-prefixed hex string,
+    /// in the same byte order as `get_eth_encoded_root`.
+    pub fn root_hash_hex(&self) -> String {
+        Self::encode_fr_hex(self.new_root_hash)
+    }
+
+    /// Encodes any `Fr` hash as a `0x`-prefixed hex string, see `root_hash_hex`.
+    pub fn encode_fr_hex(fr: Fr) -> String {
+        format!("0x{}", hex::encode(Self::encode_fr_for_eth(fr)))
+    }
+
+    /// Returns `true` if the block contains no operations (neither successful nor failed).
+    pub fn is_empty(&self) -> bool {
+        self.block_transactions.is_empty()
+    }
+
+    /// Returns an iterator over the operations that were successfully executed in the block.
+    ///
+    /// Note that the data committed on Ethereum (public data, witnesses, withdrawals and onchain
+    /// operations) is built from the operations returned by `ExecutedOperations::get_executed_op`
+    /// directly and intentionally does not use this iterator, so its encoding is never affected by it.
+    pub fn successful_operations(&self) -> impl Iterator<Item = &ZkSyncOp> {
+        self.block_transactions
+            .iter()
+            .filter(|op| op.is_successful())
+            .filter_map(ExecutedOperations::get_executed_op)
+    }
+
     /// Returns the public data for the Ethereum Commit operation.
     pub fn get_eth_public_data(&self) -> Vec<u8> {
         let mut executed_tx_pub_data = self
@@ -372,6 +470,87 @@ impl Block {
         smallest_block_size_for_chunks(chunks_used, available_block_sizes)
     }
 
+    /// Splits the block into several consecutive blocks if its operations do not fit into
+    /// the largest available block size. Blocks that fit are returned as is.
+    ///
+    /// Operations keep their order: each resulting block is filled until the next successful operation
+    /// no longer fits, failed transactions stay in the block they are encountered in. Resulting blocks
+    /// are numbered sequentially starting from the number of the original block, get their own ranges
+    /// of processed priority operations and block indices of the operations. Fees are collected
+    /// to the same fee account in every block.
+    ///
+    /// Root hashes of the intermediate states are not known without the account tree, so only the last
+    /// block keeps the root hash of the original one, and the others get a zero root hash. For the same
+    /// reason block commitments are reset and have to be recalculated once the root hashes are known.
+    /// Gas limits are copied from the original block and thus are an upper bound for each resulting block.
+    ///
+    /// # Panics
+    ///
+    /// Panics if some operation cannot fit into any available block size.
+    pub fn try_split(self, available_block_sizes: &[usize]) -> Vec<Block> {
+        let max_block_size = *available_block_sizes
+            .last()
+            .expect("List of available block sizes is empty");
+        if self.chunks_used() <= max_block_size {
+            return vec![self];
+        }
+
+        let mut parts = vec![Vec::new()];
+        let mut chunks_used = 0;
+        for op in self.block_transactions {
+            let chunks = if op.is_successful() {
+                op.get_executed_op()
+                    .map(ZkSyncOp::chunks)
+                    .unwrap_or_default()
+            } else {
+                0
+            };
+            if chunks_used + chunks > max_block_size {
+                parts.push(Vec::new());
+                chunks_used = 0;
+            }
+            chunks_used += chunks;
+            parts.last_mut().unwrap().push(op);
+        }
+
+        let parts_count = parts.len();
+        let mut first_unprocessed_priority_op = self.processed_priority_ops.0;
+        let mut blocks = Vec::with_capacity(parts_count);
+        for (idx, mut block_transactions) in parts.into_iter().enumerate() {
+            assign_block_indices(&mut block_transactions);
+            let priority_ops_count = block_transactions
+                .iter()
+                .filter(|op| matches!(op, ExecutedOperations::PriorityOp(_)))
+                .count() as u64;
+            let processed_priority_ops = (
+                first_unprocessed_priority_op,
+                first_unprocessed_priority_op + priority_ops_count,
+            );
+            first_unprocessed_priority_op = processed_priority_ops.1;
+
+            let new_root_hash = if idx + 1 == parts_count {
+                self.new_root_hash
+            } else {
+                Fr::zero()
+            };
+            let mut block = Block::new(
+                BlockNumber(*self.block_number + idx as u32),
+                new_root_hash,
+                self.fee_account,
+                block_transactions,
+                processed_priority_ops,
+                0,
+                self.commit_gas_limit,
+                self.verify_gas_limit,
+                H256::default(),
+                self.timestamp,
+            );
+            block.block_chunks_size = block.smallest_block_size(available_block_sizes);
+            blocks.push(block);
+        }
+        blocks
+    }
+
     /// Returns the number of Withdrawal and ForcedExit in a block.
     pub fn get_withdrawals_count(&self) -> usize {
         let mut withdrawals_count = 0;
@@ -498,9 +677,197 @@ impl Block {
             .to_std()
             .unwrap_or_default()
     }
+
+    /// Checks that the block timestamp is not less than the timestamp of the previous block.
+    /// Smart contract rejects blocks with decreasing timestamps, so it's better to catch it before commit.
+    pub fn validate_timestamp_after(
+        &self,
+        previous_timestamp: Option<u64>,
+    ) -> Result<(), BlockValidationError> {
+        match previous_timestamp {
+            Some(previous_timestamp) if self.timestamp < previous_timestamp => {
+                Err(BlockValidationError::TimestampDecreased {
+                    timestamp: self.timestamp,
+                    previous_timestamp,
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Checks that every priority operation is included into the block at most once.
+    /// Returns the serial id of the first duplicated operation otherwise, since the smart contract
+    /// would revert the commit of such a block.
+    pub fn validate_no_duplicate_priority_ops(&self) -> Result<(), BlockValidationError> {
+        let mut serial_ids = HashSet::new();
+        for op in &self.block_transactions {
+            if let ExecutedOperations::PriorityOp(op) = op {
+                let serial_id = op.priority_op.serial_id;
+                if !serial_ids.insert(serial_id) {
+                    return Err(BlockValidationError::DuplicatePriorityOp { serial_id });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks that block indices of the successful operations are strictly increasing in the order
+    /// the operations are stored in the block. The public data is built in the same order, so a mismatch
+    /// between the execution order and the public data order would revert the block commit.
+    pub fn assert_operations_ordered(&self) -> Result<(), BlockValidationError> {
+        let mut previous = None;
+        for (position, op) in self.block_transactions.iter().enumerate() {
+            if !op.is_successful() {
+                continue;
+            }
+            let current = op
+                .block_index()
+                .ok_or(BlockValidationError::MissingBlockIndex { position })?;
+            if let Some(previous) = previous {
+                if current <= previous {
+                    return Err(BlockValidationError::OperationsNotOrdered {
+                        position,
+                        previous,
+                        current,
+                    });
+                }
+            }
+            previous = Some(current);
+        }
+        Ok(())
+    }
+
+    /// Returns `true` if some priority operation is included into the block more than once.
+    pub fn has_duplicate_priority_ops(&self) -> bool {
+        self.validate_no_duplicate_priority_ops().is_err()
+    }
+
+    /// Returns the net balance change for every `(account, token)` pair touched by the
+    /// successful operations of the block. Debits are negative, credits are positive,
+    /// and all the collected fees are credited to the block fee account.
+    ///
+    /// Note that NFTs created by `MintNFT` operations are not reflected in the result,
+    /// since their token IDs are assigned by the state and are not stored in the operation.
+    pub fn account_deltas(&self) -> HashMap<(AccountId, TokenId), BigInt> {
+        fn credit(amount: &BigUint) -> BigInt {
+            BigInt::from(amount.clone())
+        }
+        fn debit(amount: &BigUint) -> BigInt {
+            -BigInt::from(amount.clone())
+        }
+
+        let fee_account = self.fee_account;
+        let mut changes = Vec::new();
+        for op in self.successful_operations() {
+            match op {
+                ZkSyncOp::Deposit(op) => {
+                    let deposit = &op.priority_op;
+                    changes.push((op.account_id, deposit.token, credit(&deposit.amount)));
+                }
+                ZkSyncOp::Transfer(op) => {
+                    let tx = &op.tx;
+                    changes.push((op.from, tx.token, debit(&(&tx.amount + &tx.fee))));
+                    changes.push((op.to, tx.token, credit(&tx.amount)));
+                    changes.push((fee_account, tx.token, credit(&tx.fee)));
+                }
+                ZkSyncOp::TransferToNew(op) => {
+                    let tx = &op.tx;
+                    changes.push((op.from, tx.token, debit(&(&tx.amount + &tx.fee))));
+                    changes.push((op.to, tx.token, credit(&tx.amount)));
+                    changes.push((fee_account, tx.token, credit(&tx.fee)));
+                }
+                ZkSyncOp::Withdraw(op) => {
+                    let tx = &op.tx;
+                    changes.push((op.account_id, tx.token, debit(&(&tx.amount + &tx.fee))));
+                    changes.push((fee_account, tx.token, credit(&tx.fee)));
+                }
+                ZkSyncOp::WithdrawNFT(op) => {
+                    let tx = &op.tx;
+                    changes.push((tx.account_id, tx.token, debit(&BigUint::from(1u32))));
+                    changes.push((tx.account_id, tx.fee_token, debit(&tx.fee)));
+                    changes.push((fee_account, tx.fee_token, credit(&tx.fee)));
+                }
+                ZkSyncOp::FullExit(op) => {
+                    if let Some(amount) = op.withdraw_amount() {
+                        let full_exit = &op.priority_op;
+                        changes.push((full_exit.account_id, full_exit.token, debit(&amount)));
+                    }
+                }
+                ZkSyncOp::ChangePubKeyOffchain(op) => {
+                    let tx = &op.tx;
+                    changes.push((op.account_id, tx.fee_token, debit(&tx.fee)));
+                    changes.push((fee_account, tx.fee_token, credit(&tx.fee)));
+                }
+                ZkSyncOp::ForcedExit(op) => {
+                    let tx = &op.tx;
+                    if let Some(amount) = &op.withdraw_amount {
+                        changes.push((op.target_account_id, tx.token, debit(&amount.0)));
+                    }
+                    changes.push((tx.initiator_account_id, tx.token, debit(&tx.fee)));
+                    changes.push((fee_account, tx.token, credit(&tx.fee)));
+                }
+                ZkSyncOp::MintNFTOp(op) => {
+                    let tx = &op.tx;
+                    changes.push((op.creator_account_id, tx.fee_token, debit(&tx.fee)));
+                    changes.push((fee_account, tx.fee_token, credit(&tx.fee)));
+                }
+                ZkSyncOp::Swap(op) => {
+                    let tx = &op.tx;
+                    let (token_0, token_1) = (tx.orders.0.token_sell, tx.orders.1.token_sell);
+                    changes.push((op.accounts.0, token_0, debit(&tx.amounts.0)));
+                    changes.push((op.recipients.1, token_0, credit(&tx.amounts.0)));
+                    changes.push((op.accounts.1, token_1, debit(&tx.amounts.1)));
+                    changes.push((op.recipients.0, token_1, credit(&tx.amounts.1)));
+                    changes.push((op.submitter, tx.fee_token, debit(&tx.fee)));
+                    changes.push((fee_account, tx.fee_token, credit(&tx.fee)));
+                }
+                ZkSyncOp::Close(_) | ZkSyncOp::Noop(_) => {}
+            }
+        }
+
+        let mut deltas: HashMap<(AccountId, TokenId), BigInt> = HashMap::new();
+        for (account_id, token, delta) in changes {
+            *deltas.entry((account_id, token)).or_default() += delta;
+        }
+        deltas
+    }
+
+    /// Checks that fees in the block are collected to the expected account.
+    /// Block with the fee account different from the operator one will not be verified.
+    pub fn assert_fee_account(&self, expected: AccountId) -> Result<(), BlockValidationError> {
+        if self.fee_account != expected {
+            return Err(BlockValidationError::FeeAccountMismatch {
+                expected,
+                actual: self.fee_account,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Checks that the list of available block sizes can be used to pick the block size:
+/// it must be non-empty, contain no zero sizes and be sorted in strictly ascending order.
+/// `smallest_block_size_for_chunks` relies on this order.
+pub fn validate_block_sizes(available_block_sizes: &[usize]) -> Result<(), BlockSizesError> {
+    if available_block_sizes.is_empty() {
+        return Err(BlockSizesError::Empty);
+    }
+    if available_block_sizes.contains(&0) {
+        return Err(BlockSizesError::ZeroSize);
+    }
+    for window in available_block_sizes.windows(2) {
+        if window[0] >= window[1] {
+            return Err(BlockSizesError::NotAscending {
+                previous: window[0],
+                current: window[1],
+            });
+        }
+    }
+    Ok(())
 }
 
 /// Gets smallest block size given the list of supported chunk sizes.
+/// The list is expected to be sorted in ascending order, see `validate_block_sizes`.
 pub fn smallest_block_size_for_chunks(
     chunks_used: usize,
     available_block_sizes: &[usize],
@@ -517,6 +884,27 @@ pub fn smallest_block_size_for_chunks(
     );
 }
 
+/// Assigns sequential block indices to the successful operations in the order they appear in the list,
+/// starting from zero. Failed transactions are not included into the block, thus have no index.
+pub fn assign_block_indices(ops: &mut [ExecutedOperations]) {
+    let mut next_block_index = 0u32;
+    for op in ops {
+        match op {
+            ExecutedOperations::Tx(tx) if tx.success => {
+                tx.block_index = Some(next_block_index);
+                next_block_index += 1;
+            }
+            ExecutedOperations::Tx(tx) => {
+                tx.block_index = None;
+            }
+            ExecutedOperations::PriorityOp(priority_op) => {
+                priority_op.block_index = next_block_index;
+                next_block_index += 1;
+            }
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct OnchainOperationsBlockInfo {
     pub public_data_offset: u32,
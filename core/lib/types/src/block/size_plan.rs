@@ -0,0 +1,133 @@
+//! Analysis helper estimating the padding overhead of block size configurations.
+
+use super::smallest_block_size_for_chunks;
+use crate::ZkSyncOp;
+
+/// Padding statistics for a single candidate configuration.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockSizeEstimate {
+    /// Maximum block size of the configuration. The configuration consists of all the
+    /// candidate sizes which are not greater than this one.
+    pub max_block_size: usize,
+    /// Amount of blocks required to include the whole workload.
+    pub blocks: usize,
+    /// Total amount of chunks occupied by `Noop` operations.
+    pub padding_chunks: usize,
+    /// Average amount of padding chunks per block.
+    pub average_padding: f64,
+}
+
+/// Result of the block size planning, see `plan_block_sizes`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SizePlan {
+    /// Estimates for each candidate configuration able to fit every operation of the workload,
+    /// ordered by the maximum block size.
+    pub estimates: Vec<BlockSizeEstimate>,
+}
+
+impl SizePlan {
+    /// Returns the configuration with the least total padding.
+    /// If several configurations are equally good, the one with the smallest blocks is chosen.
+    pub fn best(&self) -> Option<&BlockSizeEstimate> {
+        self.estimates
+            .iter()
+            .min_by_key(|estimate| (estimate.padding_chunks, estimate.max_block_size))
+    }
+}
+
+/// Simulates packing of the workload into blocks for each candidate configuration and reports the padding overhead.
+///
+/// The workload is described as a list of `(operation code, operations count)` pairs. Operations of different types
+/// are interleaved one by one, and each block is filled until the next operation no longer fits into the largest
+/// block size of the configuration. The sealed block then gets the smallest size able to contain its operations,
+/// the same way the state keeper does it.
+///
+/// Candidate `N` stands for the configuration consisting of all the candidate sizes not exceeding `N`.
+/// Configurations which cannot fit some of the operations are omitted from the plan.
+///
+/// # Panics
+///
+/// Panics if the workload contains an unknown operation code.
+pub fn plan_block_sizes(op_mix: &[(u8, usize)], candidate_sizes: &[usize]) -> SizePlan {
+    let op_chunks = interleave_workload(op_mix);
+    let max_op_chunks = op_chunks.iter().copied().max().unwrap_or_default();
+
+    let mut candidate_sizes = candidate_sizes.to_vec();
+    candidate_sizes.sort_unstable();
+    candidate_sizes.dedup();
+
+    let estimates = candidate_sizes
+        .iter()
+        .enumerate()
+        .filter(|(_, max_block_size)| **max_block_size > 0 && **max_block_size >= max_op_chunks)
+        .map(|(idx, _)| {
+            let available_block_sizes: Vec<_> = candidate_sizes[..=idx]
+                .iter()
+                .copied()
+                .filter(|size| *size > 0)
+                .collect();
+            simulate_packing(&op_chunks, &available_block_sizes)
+        })
+        .collect();
+
+    SizePlan { estimates }
+}
+
+/// Expands the workload into the list of chunks occupied by each operation.
+fn interleave_workload(op_mix: &[(u8, usize)]) -> Vec<usize> {
+    let chunks: Vec<_> = op_mix
+        .iter()
+        .map(|&(op_code, count)| {
+            let chunks = ZkSyncOp::chunks_for_op_code(op_code)
+                .unwrap_or_else(|| panic!("Unknown operation code: {}", op_code));
+            (chunks, count)
+        })
+        .collect();
+
+    let max_count = chunks.iter().map(|(_, count)| *count).max().unwrap_or(0);
+    (0..max_count)
+        .flat_map(|round| {
+            chunks
+                .iter()
+                .filter(move |(_, count)| *count > round)
+                .map(|(chunks, _)| *chunks)
+        })
+        .collect()
+}
+
+/// Packs operations into blocks, `available_block_sizes` must be sorted and able to fit every operation.
+fn simulate_packing(op_chunks: &[usize], available_block_sizes: &[usize]) -> BlockSizeEstimate {
+    let max_block_size = *available_block_sizes.last().unwrap();
+
+    let mut blocks = 0;
+    let mut padding_chunks = 0;
+    let mut seal_block = |chunks_used: usize| {
+        let block_size = smallest_block_size_for_chunks(chunks_used, available_block_sizes);
+        blocks += 1;
+        padding_chunks += block_size - chunks_used;
+    };
+
+    let mut chunks_used = 0;
+    for &chunks in op_chunks {
+        if chunks_used + chunks > max_block_size {
+            seal_block(chunks_used);
+            chunks_used = 0;
+        }
+        chunks_used += chunks;
+    }
+    if chunks_used > 0 {
+        seal_block(chunks_used);
+    }
+
+    let average_padding = if blocks == 0 {
+        0.0
+    } else {
+        padding_chunks as f64 / blocks as f64
+    };
+    BlockSizeEstimate {
+        max_block_size,
+        blocks,
+        padding_chunks,
+        average_padding,
+    }
+}
@@ -1,4 +1,5 @@
 use num::{BigUint, FromPrimitive};
+use thiserror::Error;
 use zksync_crypto::params;
 use zksync_crypto::primitives::FloatConversions;
 
@@ -131,6 +132,39 @@ pub fn closest_greater_or_eq_packable_token_amount(amount: &BigUint) -> BigUint
     unpack_token_amount(&fee_packed).expect("token amount repacking")
 }
 
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum PackError {
+    #[error("Amount {amount} can't be packed exactly, the closest packable amount is {closest}")]
+    NotPackable { amount: BigUint, closest: BigUint },
+}
+
+/// Checks that the token amount can be packed without precision loss.
+/// Unlike `closest_packable_token_amount`, doesn't round the amount silently: the error
+/// contains the closest packable amount, so the caller may decide whether to use it.
+pub fn ensure_token_amount_packable(amount: &BigUint) -> Result<(), PackError> {
+    if is_token_amount_packable(amount) {
+        Ok(())
+    } else {
+        Err(PackError::NotPackable {
+            amount: amount.clone(),
+            closest: closest_packable_token_amount(amount),
+        })
+    }
+}
+
+/// Checks that the fee amount can be packed without precision loss.
+/// See `ensure_token_amount_packable` for details.
+pub fn ensure_fee_amount_packable(amount: &BigUint) -> Result<(), PackError> {
+    if is_fee_amount_packable(amount) {
+        Ok(())
+    } else {
+        Err(PackError::NotPackable {
+            amount: amount.clone(),
+            closest: closest_packable_fee_amount(amount),
+        })
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -156,6 +190,52 @@ mod test {
         }
     }
 
+    /// Checks that packing the fee amount always yields the greatest packable amount
+    /// not exceeding the original one, across the whole range of exponents.
+    #[test]
+    fn fee_amount_packing_properties() {
+        let max_mantissa = (1u128 << params::FEE_MANTISSA_BIT_WIDTH) - 1;
+        let max_exponent = (1u32 << params::FEE_EXPONENT_BIT_WIDTH) - 1;
+        let max_fee = max_mantissa * 10u128.pow(max_exponent);
+
+        let mut amounts = vec![0, 1, max_mantissa, max_mantissa + 1, max_fee, max_fee - 1];
+        for exponent in 0..=max_exponent {
+            let power = 10u128.pow(exponent);
+            for &mantissa in &[1, 2, 999, 1024, max_mantissa] {
+                for &delta in &[0, 1, power - 1] {
+                    if let Some(amount) = (mantissa * power).checked_add(delta) {
+                        if amount <= max_fee {
+                            amounts.push(amount);
+                        }
+                    }
+                }
+            }
+        }
+
+        for amount in amounts {
+            let amount = BigUint::from(amount);
+            let packed = pack_fee_amount(&amount);
+            assert_eq!(
+                packed.len() * 8,
+                params::FEE_EXPONENT_BIT_WIDTH + params::FEE_MANTISSA_BIT_WIDTH
+            );
+
+            let unpacked = unpack_fee_amount(&packed).expect("packed fee must be unpackable");
+            assert_eq!(unpacked, closest_packable_fee_amount(&amount));
+            assert!(unpacked <= amount, "{} was rounded up", amount);
+            assert!(is_fee_amount_packable(&unpacked));
+            // There is no packable amount between the rounded and the original ones.
+            if unpacked != amount {
+                assert!(!is_fee_amount_packable(&amount));
+                assert!(
+                    closest_greater_or_eq_packable_fee_amount(&(unpacked.clone() + 1u32)) >= amount,
+                    "{} was not rounded to the closest packable amount",
+                    amount
+                );
+            }
+        }
+    }
+
     #[test]
     fn detect_unpackable() {
         let max_mantissa_token =
@@ -223,6 +303,31 @@ mod test {
         );
     }
 
+    #[test]
+    fn ensure_packable() {
+        let token = BigUint::from(1_000_000u32);
+        assert_eq!(ensure_token_amount_packable(&token), Ok(()));
+        assert_eq!(ensure_fee_amount_packable(&token), Ok(()));
+
+        let token = BigUint::from(123_456_789_123_456_789u64);
+        assert_eq!(
+            ensure_token_amount_packable(&token),
+            Err(PackError::NotPackable {
+                amount: token.clone(),
+                closest: closest_packable_token_amount(&token),
+            })
+        );
+
+        let fee = BigUint::from(1_234_123_424u32);
+        assert_eq!(
+            ensure_fee_amount_packable(&fee),
+            Err(PackError::NotPackable {
+                amount: fee.clone(),
+                closest: closest_packable_fee_amount(&fee),
+            })
+        );
+    }
+
     #[test]
     fn token_like_serialization() {
         #[derive(Debug, Serialize, Deserialize, PartialEq)]
@@ -61,6 +61,10 @@ mod tests;
 pub use self::account::{Account, AccountUpdate, PubKeyHash};
 pub use self::block::{ExecutedOperations, ExecutedPriorityOp, ExecutedTx};
 pub use self::fee::{BatchFee, Fee, OutputFeeType, TotalFee};
+pub use self::helpers::{
+    closest_packable_fee_amount, closest_packable_token_amount, pack_fee_amount, pack_token_amount,
+    unpack_fee_amount, unpack_token_amount,
+};
 pub use self::operations::{
     ChangePubKeyOp, DepositOp, ForcedExitOp, FullExitOp, MintNFTOp, SwapOp, TransferOp,
     TransferToNewOp, WithdrawNFTOp, WithdrawOp, ZkSyncOp,
@@ -1,9 +1,11 @@
 use crate::{
     helpers::{pack_fee_amount, unpack_fee_amount},
-    operations::error::ChangePubkeyOpError,
-    tx::ChangePubKey,
+    operations::{account_id_to_pubdata, check_fee, error::ChangePubkeyOpError},
+    tx::{error::ChangePubKeyEthAuthError, ChangePubKey},
     AccountId, Address, Nonce, PubKeyHash, TokenId,
 };
+use num::BigUint;
+use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
 use zksync_crypto::{
     params::{
@@ -27,7 +29,7 @@ impl ChangePubKeyOp {
 
     pub fn get_public_data(&self) -> Vec<u8> {
         let mut data = vec![Self::OP_CODE];
-        data.extend_from_slice(&self.account_id.to_be_bytes());
+        data.extend_from_slice(&account_id_to_pubdata(self.account_id));
         data.extend_from_slice(&self.tx.new_pk_hash.data);
         data.extend_from_slice(self.tx.account.as_bytes());
         data.extend_from_slice(&self.tx.nonce.to_be_bytes());
@@ -53,6 +55,18 @@ impl ChangePubKeyOp {
         Self::parse_pub_data(bytes, TOKEN_BIT_WIDTH)
     }
 
+    /// Same as `from_public_data`, but also rejects operations with an implausible fee, i.e. paid in
+    /// a non-fungible token or exceeding `max_fee`. Committed operations must be decoded with
+    /// `from_public_data`, since they are valid regardless of the fee.
+    pub fn from_public_data_strict(
+        bytes: &[u8],
+        max_fee: &BigUint,
+    ) -> Result<Self, ChangePubkeyOpError> {
+        let op = Self::from_public_data(bytes)?;
+        check_fee(op.tx.fee_token, &op.tx.fee, max_fee)?;
+        Ok(op)
+    }
+
     pub fn from_legacy_public_data(bytes: &[u8]) -> Result<Self, ChangePubkeyOpError> {
         Self::parse_pub_data(bytes, LEGACY_TOKEN_BIT_WIDTH)
     }
@@ -100,4 +114,173 @@ impl ChangePubKeyOp {
     pub fn get_updated_account_ids(&self) -> Vec<AccountId> {
         vec![self.account_id]
     }
+
+    /// Returns the token in which the fee is paid.
+    pub fn fee_token(&self) -> TokenId {
+        self.tx.fee_token
+    }
+
+    /// Checks that the Ethereum witness of the operation (i.e. Ethereum authorization data)
+    /// was produced by the account owner.
+    pub fn verify_eth_witness(&self) -> Result<bool, ChangePubKeyEthAuthError> {
+        self.tx.verify_eth_auth_data()
+    }
+}
+
+/// Minimum number of operations for which the witnesses are verified in parallel.
+/// For smaller batches the overhead of the thread pool outweighs the gain.
+const PARALLEL_WITNESS_VERIFICATION_THRESHOLD: usize = 16;
+
+/// Verifies the Ethereum witnesses of the provided operations, see `ChangePubKeyOp::verify_eth_witness`.
+/// Large batches are verified in parallel. The results are returned in the order of the operations.
+pub fn verify_change_pubkey_witnesses(
+    ops: &[ChangePubKeyOp],
+) -> Vec<Result<bool, ChangePubKeyEthAuthError>> {
+    if ops.len() < PARALLEL_WITNESS_VERIFICATION_THRESHOLD {
+        ops.iter().map(ChangePubKeyOp::verify_eth_witness).collect()
+    } else {
+        ops.par_iter()
+            .map(ChangePubKeyOp::verify_eth_witness)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::operations::error::FeeValidationError;
+    use crate::{
+        tx::{ChangePubKeyECDSAData, ChangePubKeyEthAuthData, PackedEthSignature},
+        H256,
+    };
+    use num::One;
+    use std::time::Instant;
+    use zksync_crypto::params::MIN_NFT_TOKEN_ID;
+
+    fn create_op(fee: BigUint, fee_token: TokenId) -> ChangePubKeyOp {
+        ChangePubKeyOp {
+            tx: ChangePubKey::new(
+                AccountId(10),
+                Address::random(),
+                PubKeyHash::default(),
+                fee_token,
+                fee,
+                Nonce(1),
+                Default::default(),
+                None,
+                None,
+            ),
+            account_id: AccountId(10),
+        }
+    }
+
+    #[test]
+    fn public_data_strict_fee_bounds() {
+        let max_fee = num::pow(BigUint::from(10u32), 30);
+        let op = create_op(max_fee.clone(), TokenId(1));
+        let parsed = ChangePubKeyOp::from_public_data_strict(&op.get_public_data(), &max_fee)
+            .expect("Fee at the boundary must be accepted");
+        assert_eq!(parsed.tx.fee, max_fee);
+        assert_eq!(parsed.tx.fee_token, TokenId(1));
+
+        // The smallest packable fee above the maximum.
+        let over_max_fee = &max_fee + num::pow(BigUint::from(10u32), 27);
+        let pub_data = create_op(over_max_fee.clone(), TokenId(1)).get_public_data();
+        assert_eq!(
+            ChangePubKeyOp::from_public_data_strict(&pub_data, &max_fee).unwrap_err(),
+            ChangePubkeyOpError::InvalidFee(FeeValidationError::FeeTooBig {
+                fee: over_max_fee.clone(),
+                max: max_fee.clone(),
+            })
+        );
+        // The non-strict decoder accepts it as is.
+        assert_eq!(
+            ChangePubKeyOp::from_public_data(&pub_data).unwrap().tx.fee,
+            over_max_fee
+        );
+
+        // The biggest packable fee.
+        let mut pub_data = create_op(BigUint::one(), TokenId(1)).get_public_data();
+        let fee_offset = 1
+            + ACCOUNT_ID_BIT_WIDTH / 8
+            + NEW_PUBKEY_HASH_WIDTH / 8
+            + ADDRESS_WIDTH / 8
+            + NONCE_BIT_WIDTH / 8
+            + TOKEN_BIT_WIDTH / 8;
+        pub_data[fee_offset] = 0xff;
+        pub_data[fee_offset + 1] = 0xff;
+        assert!(matches!(
+            ChangePubKeyOp::from_public_data_strict(&pub_data, &max_fee),
+            Err(ChangePubkeyOpError::InvalidFee(
+                FeeValidationError::FeeTooBig { .. }
+            ))
+        ));
+    }
+
+    #[test]
+    fn public_data_strict_fee_token() {
+        let max_fee = BigUint::one();
+        let op = create_op(BigUint::one(), TokenId(MIN_NFT_TOKEN_ID - 1));
+        assert!(ChangePubKeyOp::from_public_data_strict(&op.get_public_data(), &max_fee).is_ok());
+
+        let op = create_op(BigUint::one(), TokenId(MIN_NFT_TOKEN_ID));
+        assert_eq!(
+            ChangePubKeyOp::from_public_data_strict(&op.get_public_data(), &max_fee).unwrap_err(),
+            ChangePubkeyOpError::InvalidFee(FeeValidationError::InvalidFeeToken(TokenId(
+                MIN_NFT_TOKEN_ID
+            )))
+        );
+    }
+
+    /// Checks that batched verification matches the verification of individual operations,
+    /// and reports the time spent on both for the large batch.
+    #[test]
+    fn verify_witnesses_batch() {
+        let create_signed_op = |nonce: u32, valid: bool| {
+            let private_key = H256::random();
+            let owner = PackedEthSignature::address_from_private_key(&private_key).unwrap();
+            let account = if valid { owner } else { Address::random() };
+            let mut op = create_op(BigUint::one(), TokenId(0));
+            op.tx.account = account;
+            op.tx.nonce = Nonce(nonce);
+            let eth_signature =
+                PackedEthSignature::sign(&private_key, &op.tx.get_eth_signed_data().unwrap())
+                    .unwrap();
+            op.tx.eth_auth_data = Some(ChangePubKeyEthAuthData::ECDSA(ChangePubKeyECDSAData {
+                eth_signature,
+                batch_hash: H256::zero(),
+            }));
+            op
+        };
+
+        for &ops_count in &[4, 512] {
+            // Every third operation is signed by someone other than the account owner.
+            let ops: Vec<_> = (0..ops_count)
+                .map(|nonce| create_signed_op(nonce, nonce % 3 != 0))
+                .collect();
+
+            let start = Instant::now();
+            let sequential: Vec<_> = ops
+                .iter()
+                .map(|op| op.verify_eth_witness().unwrap())
+                .collect();
+            let sequential_time = start.elapsed();
+
+            let start = Instant::now();
+            let batched: Vec<_> = verify_change_pubkey_witnesses(&ops)
+                .into_iter()
+                .map(Result::unwrap)
+                .collect();
+            let batched_time = start.elapsed();
+
+            println!(
+                "Verified {} witnesses: sequentially in {:?}, batched in {:?}",
+                ops_count, sequential_time, batched_time
+            );
+            assert_eq!(batched, sequential);
+            for (nonce, valid) in batched.into_iter().enumerate() {
+                assert_eq!(valid, nonce % 3 != 0);
+            }
+        }
+    }
 }
@@ -1,4 +1,8 @@
-use crate::{operations::error::CloseOpError, tx::TxSignature, AccountId, Address, Close, Nonce};
+use crate::{
+    operations::{account_id_to_pubdata, error::CloseOpError},
+    tx::TxSignature,
+    AccountId, Address, Close, Nonce,
+};
 use serde::{Deserialize, Serialize};
 use zksync_crypto::{
     params::{ACCOUNT_ID_BIT_WIDTH, CHUNK_BYTES, LEGACY_CHUNK_BYTES},
@@ -17,7 +21,7 @@ impl CloseOp {
 
     pub(crate) fn get_public_data(&self) -> Vec<u8> {
         let mut data = vec![Self::OP_CODE];
-        data.extend_from_slice(&self.account_id.to_be_bytes());
+        data.extend_from_slice(&account_id_to_pubdata(self.account_id));
         data.resize(Self::CHUNKS * CHUNK_BYTES, 0x00);
         data
     }
@@ -1,4 +1,7 @@
-use crate::{operations::error::DepositOpError, AccountId, Address, Deposit, TokenId};
+use crate::{
+    operations::{account_id_to_pubdata, error::DepositOpError},
+    AccountId, Address, Deposit, TokenId,
+};
 use num::{BigUint, ToPrimitive};
 use serde::{Deserialize, Serialize};
 use zksync_crypto::{
@@ -22,7 +25,7 @@ impl DepositOp {
 
     pub fn get_public_data(&self) -> Vec<u8> {
         let mut data = vec![Self::OP_CODE];
-        data.extend_from_slice(&self.account_id.to_be_bytes());
+        data.extend_from_slice(&account_id_to_pubdata(self.account_id));
         data.extend_from_slice(&self.priority_op.token.to_be_bytes());
         data.extend_from_slice(&self.priority_op.amount.to_u128().unwrap().to_be_bytes());
         data.extend_from_slice(self.priority_op.to.as_bytes());
@@ -1,4 +1,6 @@
 use crate::account::error::PubkeyHashDecodingError;
+use crate::TokenId;
+use num::BigUint;
 use thiserror::Error;
 
 #[derive(Debug, Error, PartialEq)]
@@ -15,6 +17,8 @@ pub enum ChangePubkeyOpError {
     CannotGetFeeTokenId,
     #[error("Failed to get fee")]
     CannotGetFee,
+    #[error("Invalid fee: {0}")]
+    InvalidFee(#[from] FeeValidationError),
 }
 
 #[derive(Debug, Error, PartialEq)]
@@ -147,6 +151,8 @@ pub enum PublicDataDecodeError {
     EmptyData,
     #[error("Unknown operation type")]
     UnknownOperationType,
+    #[error("Not enough public data for the operation: expected {expected} bytes, got {actual}")]
+    NotEnoughData { expected: usize, actual: usize },
     #[error(transparent)]
     ChangePubkeyOpError(#[from] ChangePubkeyOpError),
     #[error(transparent)]
@@ -188,3 +194,12 @@ pub enum SwapOpError {
     #[error("Failed to get Fee")]
     CannotGetFee,
 }
+
+/// Reasons for the operation fee to be considered implausible, see `ZkSyncOp::validate_fee`.
+#[derive(Debug, Error, PartialEq)]
+pub enum FeeValidationError {
+    #[error("Fee {fee} exceeds the maximum allowed value {max}")]
+    FeeTooBig { fee: BigUint, max: BigUint },
+    #[error("Fee token id {0} is not a fungible token")]
+    InvalidFeeToken(TokenId),
+}
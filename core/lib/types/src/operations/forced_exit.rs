@@ -1,6 +1,6 @@
 use crate::{
     helpers::{pack_fee_amount, unpack_fee_amount},
-    operations::error::ForcedExitOpError,
+    operations::{account_id_to_pubdata, error::ForcedExitOpError},
     AccountId, Address, ForcedExit, Nonce, TokenId,
 };
 use num::{BigUint, FromPrimitive, ToPrimitive};
@@ -39,8 +39,8 @@ impl ForcedExitOp {
 
     pub fn get_public_data(&self) -> Vec<u8> {
         let mut data = vec![Self::OP_CODE];
-        data.extend_from_slice(&self.tx.initiator_account_id.to_be_bytes());
-        data.extend_from_slice(&self.target_account_id.to_be_bytes());
+        data.extend_from_slice(&account_id_to_pubdata(self.tx.initiator_account_id));
+        data.extend_from_slice(&account_id_to_pubdata(self.target_account_id));
         data.extend_from_slice(&self.tx.token.to_be_bytes());
         data.extend_from_slice(&self.amount().to_be_bytes());
         data.extend_from_slice(&pack_fee_amount(&self.tx.fee));
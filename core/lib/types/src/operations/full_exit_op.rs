@@ -7,7 +7,10 @@ use zksync_crypto::params::{
 use zksync_crypto::primitives::FromBytes;
 use zksync_utils::BigUintSerdeWrapper;
 
-use crate::{operations::error::FullExitOpError, AccountId, Address, FullExit, TokenId, H256};
+use crate::{
+    operations::{account_id_to_pubdata, error::FullExitOpError},
+    AccountId, Address, FullExit, TokenId, H256,
+};
 
 /// FullExit operation. For details, see the documentation of [`ZkSyncOp`](./operations/enum.ZkSyncOp.html).
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -37,7 +40,7 @@ impl FullExitOp {
 
     pub(crate) fn get_public_data(&self) -> Vec<u8> {
         let mut data = vec![Self::OP_CODE];
-        data.extend_from_slice(&self.priority_op.account_id.to_be_bytes());
+        data.extend_from_slice(&account_id_to_pubdata(self.priority_op.account_id));
         data.extend_from_slice(self.priority_op.eth_address.as_bytes());
         data.extend_from_slice(&self.priority_op.token.to_be_bytes());
         data.extend_from_slice(
@@ -50,7 +53,9 @@ impl FullExitOp {
                 .unwrap()
                 .to_be_bytes(),
         );
-        data.extend_from_slice(&self.creator_account_id.unwrap_or_default().to_be_bytes());
+        data.extend_from_slice(&account_id_to_pubdata(
+            self.creator_account_id.unwrap_or_default(),
+        ));
         data.extend_from_slice(self.creator_address.unwrap_or_default().as_bytes());
         data.extend_from_slice(&self.serial_id.unwrap_or_default().to_be_bytes());
         data.extend_from_slice(self.content_hash.unwrap_or_default().as_bytes());
@@ -71,7 +76,9 @@ impl FullExitOp {
                 .unwrap_or(0)
                 .to_be_bytes(),
         );
-        data.extend_from_slice(&self.creator_account_id.unwrap_or_default().to_be_bytes());
+        data.extend_from_slice(&account_id_to_pubdata(
+            self.creator_account_id.unwrap_or_default(),
+        ));
         data
     }
 
@@ -9,7 +9,7 @@ use zksync_crypto::{
 };
 
 use crate::helpers::{pack_fee_amount, unpack_fee_amount};
-use crate::operations::error::MintNFTOpError;
+use crate::operations::{account_id_to_pubdata, error::MintNFTOpError};
 use crate::{AccountId, Address, MintNFT, Nonce, TokenId, H256};
 
 /// Deposit operation. For details, see the documentation of [`ZkSyncOp`](./operations/enum.ZkSyncOp.html).
@@ -26,8 +26,8 @@ impl MintNFTOp {
 
     pub fn get_public_data(&self) -> Vec<u8> {
         let mut data = vec![Self::OP_CODE];
-        data.extend_from_slice(&self.creator_account_id.to_be_bytes());
-        data.extend_from_slice(&self.recipient_account_id.to_be_bytes());
+        data.extend_from_slice(&account_id_to_pubdata(self.creator_account_id));
+        data.extend_from_slice(&account_id_to_pubdata(self.recipient_account_id));
         data.extend_from_slice(self.tx.content_hash.as_bytes());
         data.extend_from_slice(&self.tx.fee_token.to_be_bytes());
         data.extend_from_slice(&pack_fee_amount(&self.tx.fee));
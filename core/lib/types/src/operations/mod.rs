@@ -2,10 +2,14 @@
 
 use super::ZkSyncTx;
 use crate::ZkSyncPriorityOp;
-use num::BigUint;
+use num::{BigUint, Zero};
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use zksync_basic_types::{AccountId, TokenId};
-use zksync_crypto::params::{CHUNK_BYTES, LEGACY_CHUNK_BYTES};
+use zksync_crypto::params::{
+    used_account_subtree_depth, ACCOUNT_ID_BIT_WIDTH, CHUNK_BYTES, LEGACY_CHUNK_BYTES,
+    MIN_NFT_TOKEN_ID,
+};
 
 mod change_pubkey_op;
 mod close_op;
@@ -24,12 +28,37 @@ mod withdraw_op;
 #[doc(hidden)]
 pub use self::close_op::CloseOp;
 pub use self::{
-    change_pubkey_op::ChangePubKeyOp, deposit_op::DepositOp, forced_exit::ForcedExitOp,
-    full_exit_op::FullExitOp, mint_nft_op::MintNFTOp, noop_op::NoopOp, swap_op::SwapOp,
-    transfer_op::TransferOp, transfer_to_new_op::TransferToNewOp, withdraw_nft_op::WithdrawNFTOp,
+    change_pubkey_op::{verify_change_pubkey_witnesses, ChangePubKeyOp},
+    deposit_op::DepositOp,
+    forced_exit::ForcedExitOp,
+    full_exit_op::FullExitOp,
+    mint_nft_op::MintNFTOp,
+    noop_op::NoopOp,
+    swap_op::SwapOp,
+    transfer_op::TransferOp,
+    transfer_to_new_op::TransferToNewOp,
+    withdraw_nft_op::WithdrawNFTOp,
     withdraw_op::WithdrawOp,
 };
-use crate::operations::error::{PublicDataDecodeError, UnexpectedOperationType};
+use crate::operations::error::{
+    FeeValidationError, PublicDataDecodeError, UnexpectedOperationType,
+};
+
+/// Number of chunks occupied by each operation type, indexed by the operation code.
+const OP_CODE_CHUNKS: [(u8, usize); 12] = [
+    (NoopOp::OP_CODE, NoopOp::CHUNKS),
+    (DepositOp::OP_CODE, DepositOp::CHUNKS),
+    (TransferToNewOp::OP_CODE, TransferToNewOp::CHUNKS),
+    (WithdrawOp::OP_CODE, WithdrawOp::CHUNKS),
+    (CloseOp::OP_CODE, CloseOp::CHUNKS),
+    (TransferOp::OP_CODE, TransferOp::CHUNKS),
+    (FullExitOp::OP_CODE, FullExitOp::CHUNKS),
+    (ChangePubKeyOp::OP_CODE, ChangePubKeyOp::CHUNKS),
+    (ForcedExitOp::OP_CODE, ForcedExitOp::CHUNKS),
+    (MintNFTOp::OP_CODE, MintNFTOp::CHUNKS),
+    (WithdrawNFTOp::OP_CODE, WithdrawNFTOp::CHUNKS),
+    (SwapOp::OP_CODE, SwapOp::CHUNKS),
+];
 
 /// zkSync network operation.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -97,6 +126,56 @@ impl ZkSyncOp {
         }
     }
 
+    /// Returns the token in which the operation fee is paid.
+    /// Priority operations, `Noop` and `Close` operations have no fee.
+    pub fn fee_token(&self) -> Option<TokenId> {
+        match self {
+            ZkSyncOp::Transfer(op) => Some(op.tx.token),
+            ZkSyncOp::TransferToNew(op) => Some(op.tx.token),
+            ZkSyncOp::Withdraw(op) => Some(op.tx.token),
+            ZkSyncOp::WithdrawNFT(op) => Some(op.tx.fee_token),
+            ZkSyncOp::ChangePubKeyOffchain(op) => Some(op.fee_token()),
+            ZkSyncOp::ForcedExit(op) => Some(op.tx.token),
+            ZkSyncOp::MintNFTOp(op) => Some(op.tx.fee_token),
+            ZkSyncOp::Swap(op) => Some(op.tx.fee_token),
+            ZkSyncOp::Deposit(_)
+            | ZkSyncOp::FullExit(_)
+            | ZkSyncOp::Close(_)
+            | ZkSyncOp::Noop(_) => None,
+        }
+    }
+
+    /// Returns the fee of the operation along with the token in which it is paid.
+    /// Priority operations, `Noop` and `Close` operations have no fee.
+    pub fn fee(&self) -> Option<(TokenId, BigUint)> {
+        match self {
+            ZkSyncOp::Transfer(op) => Some((op.tx.token, op.tx.fee.clone())),
+            ZkSyncOp::TransferToNew(op) => Some((op.tx.token, op.tx.fee.clone())),
+            ZkSyncOp::Withdraw(op) => Some((op.tx.token, op.tx.fee.clone())),
+            ZkSyncOp::WithdrawNFT(op) => Some((op.tx.fee_token, op.tx.fee.clone())),
+            ZkSyncOp::ChangePubKeyOffchain(op) => Some((op.tx.fee_token, op.tx.fee.clone())),
+            ZkSyncOp::ForcedExit(op) => Some((op.tx.token, op.tx.fee.clone())),
+            ZkSyncOp::MintNFTOp(op) => Some((op.tx.fee_token, op.tx.fee.clone())),
+            ZkSyncOp::Swap(op) => Some((op.tx.fee_token, op.tx.fee.clone())),
+            ZkSyncOp::Deposit(_)
+            | ZkSyncOp::FullExit(_)
+            | ZkSyncOp::Close(_)
+            | ZkSyncOp::Noop(_) => None,
+        }
+    }
+
+    /// Checks that the fee of the operation is plausible: it's paid in a fungible token and doesn't exceed `max_fee`.
+    ///
+    /// Packed fees can encode values much bigger than any realistic fee, so operations decoded from the public data
+    /// can be checked with this method before their fees are used in the balance arithmetic. Decoding itself doesn't
+    /// perform this check, since the already committed operations must always be decodable.
+    pub fn validate_fee(&self, max_fee: &BigUint) -> Result<(), FeeValidationError> {
+        match self.fee() {
+            Some((fee_token, fee)) => check_fee(fee_token, &fee, max_fee),
+            None => Ok(()),
+        }
+    }
+
     /// Returns the public data required for the Ethereum smart contract to commit the operation.
     pub fn public_data(&self) -> Vec<u8> {
         match self {
@@ -183,6 +262,24 @@ impl ZkSyncOp {
         }
     }
 
+    /// Restores the operation from the beginning of the public data, which may contain subsequent operations.
+    /// Returns the operation and the number of bytes it occupies.
+    pub fn from_public_data_prefix(bytes: &[u8]) -> Result<(Self, usize), PublicDataDecodeError> {
+        let op_type: u8 = *bytes.first().ok_or(PublicDataDecodeError::EmptyData)?;
+        let op_length = Self::chunks_for_op_code(op_type)
+            .ok_or(PublicDataDecodeError::UnknownOperationType)?
+            * CHUNK_BYTES;
+        if bytes.len() < op_length {
+            return Err(PublicDataDecodeError::NotEnoughData {
+                expected: op_length,
+                actual: bytes.len(),
+            });
+        }
+
+        let op = Self::from_public_data(&bytes[..op_length])?;
+        Ok((op, op_length))
+    }
+
     /// Attempts to restore the operation from the public data committed on the Ethereum smart contract
     /// prior to v6 upgrade. The token id bit width is 2 bytes instead of 4.
     ///
@@ -219,24 +316,20 @@ impl ZkSyncOp {
         }
     }
 
-    /// Returns the expected number of chunks for a certain type of operation.
+    /// Returns the number of chunks occupied by the operation with the given code,
+    /// or `None` if the code is unknown.
+    pub fn chunks_for_op_code(op_code: u8) -> Option<usize> {
+        OP_CODE_CHUNKS
+            .iter()
+            .find(|(code, _)| *code == op_code)
+            .map(|(_, chunks)| *chunks)
+    }
+
+    /// Returns the expected public data length in bytes for a certain type of operation.
     pub fn public_data_length(op_type: u8) -> Result<usize, UnexpectedOperationType> {
-        match op_type {
-            NoopOp::OP_CODE => Ok(NoopOp::CHUNKS),
-            DepositOp::OP_CODE => Ok(DepositOp::CHUNKS),
-            TransferToNewOp::OP_CODE => Ok(TransferToNewOp::CHUNKS),
-            WithdrawOp::OP_CODE => Ok(WithdrawOp::CHUNKS),
-            CloseOp::OP_CODE => Ok(CloseOp::CHUNKS),
-            TransferOp::OP_CODE => Ok(TransferOp::CHUNKS),
-            FullExitOp::OP_CODE => Ok(FullExitOp::CHUNKS),
-            ChangePubKeyOp::OP_CODE => Ok(ChangePubKeyOp::CHUNKS),
-            ForcedExitOp::OP_CODE => Ok(ForcedExitOp::CHUNKS),
-            SwapOp::OP_CODE => Ok(SwapOp::CHUNKS),
-            MintNFTOp::OP_CODE => Ok(MintNFTOp::CHUNKS),
-            WithdrawNFTOp::OP_CODE => Ok(WithdrawNFTOp::CHUNKS),
-            _ => Err(UnexpectedOperationType()),
-        }
-        .map(|chunks| chunks * CHUNK_BYTES)
+        Self::chunks_for_op_code(op_type)
+            .map(|chunks| chunks * CHUNK_BYTES)
+            .ok_or(UnexpectedOperationType())
     }
 
     /// Returns the expected number of chunks for a certain type of operation
@@ -327,6 +420,51 @@ impl ZkSyncOp {
     pub fn is_priority_op(&self) -> bool {
         matches!(self, &ZkSyncOp::Deposit(_) | &ZkSyncOp::FullExit(_))
     }
+
+    /// Returns `false` if the operation is known to leave the account tree (and thus the root hash) unchanged.
+    ///
+    /// - `Noop` never changes the state.
+    /// - `FullExit` changes the state only if a non-zero amount is withdrawn, i.e. failed full exits
+    ///   and exits from empty balances leave the state intact.
+    /// - `Deposit` always affects the state: even a zero deposit may create the recipient account.
+    /// - All the L2 transactions (including `ChangePubKey` which sets the same public key hash as the
+    ///   account already has) increment the nonce of the initiator account, so they always affect the state.
+    pub fn affects_state(&self) -> bool {
+        match self {
+            ZkSyncOp::Noop(_) => false,
+            ZkSyncOp::FullExit(op) => op
+                .withdraw_amount()
+                .map_or(false, |amount| !amount.is_zero()),
+            ZkSyncOp::Deposit(_)
+            | ZkSyncOp::Transfer(_)
+            | ZkSyncOp::TransferToNew(_)
+            | ZkSyncOp::Withdraw(_)
+            | ZkSyncOp::WithdrawNFT(_)
+            | ZkSyncOp::Close(_)
+            | ZkSyncOp::ChangePubKeyOffchain(_)
+            | ZkSyncOp::ForcedExit(_)
+            | ZkSyncOp::MintNFTOp(_)
+            | ZkSyncOp::Swap(_) => true,
+        }
+    }
+}
+
+/// Checks that the fee is paid in a fungible token and doesn't exceed `max_fee`.
+pub(crate) fn check_fee(
+    fee_token: TokenId,
+    fee: &BigUint,
+    max_fee: &BigUint,
+) -> Result<(), FeeValidationError> {
+    if *fee_token >= MIN_NFT_TOKEN_ID {
+        return Err(FeeValidationError::InvalidFeeToken(fee_token));
+    }
+    if fee > max_fee {
+        return Err(FeeValidationError::FeeTooBig {
+            fee: fee.clone(),
+            max: max_fee.clone(),
+        });
+    }
+    Ok(())
 }
 
 impl From<NoopOp> for ZkSyncOp {
@@ -400,3 +538,229 @@ impl From<WithdrawNFTOp> for ZkSyncOp {
         Self::WithdrawNFT(Box::new(op))
     }
 }
+
+/// Restores all the operations from the block public data, as returned by `Block::get_eth_public_data`.
+/// Block padding is restored as the `Noop` operations.
+pub fn parse_block_pubdata(mut bytes: &[u8]) -> Result<Vec<ZkSyncOp>, PublicDataDecodeError> {
+    let mut ops = Vec::new();
+    while !bytes.is_empty() {
+        let (op, op_length) = ZkSyncOp::from_public_data_prefix(bytes)?;
+        ops.push(op);
+        bytes = &bytes[op_length..];
+    }
+    Ok(ops)
+}
+
+/// Encodes the account id for the operation public data.
+///
+/// Panics if the account id doesn't fit into the account tree: such an id cannot be processed by the circuit,
+/// and encoding it would result in a silently corrupted block.
+pub(crate) fn account_id_to_pubdata(account_id: AccountId) -> [u8; ACCOUNT_ID_BIT_WIDTH / 8] {
+    let max_account_id = (1u64 << used_account_subtree_depth()) - 1;
+    assert!(
+        u64::from(*account_id) <= max_account_id,
+        "Account id {} doesn't fit into the account tree, the maximum account id is {}",
+        *account_id,
+        max_account_id
+    );
+    account_id.to_be_bytes()
+}
+
+/// Returns the set of tokens in which the fees for the provided operations are paid.
+pub fn collect_fee_tokens(ops: &[ZkSyncOp]) -> HashSet<TokenId> {
+    ops.iter().filter_map(ZkSyncOp::fee_token).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{tx::ChangePubKey, Address, FullExit, Nonce, PubKeyHash};
+
+    #[test]
+    fn chunks_for_op_code() {
+        let mut op_types = HashSet::new();
+        for (op_code, chunks) in OP_CODE_CHUNKS.iter() {
+            assert_eq!(ZkSyncOp::chunks_for_op_code(*op_code), Some(*chunks));
+
+            // Operation with all the fields set to zero.
+            let mut pubdata = vec![0u8; chunks * CHUNK_BYTES];
+            pubdata[0] = *op_code;
+            let op = ZkSyncOp::from_public_data(&pubdata).unwrap();
+
+            // The match is exhaustive, so a new operation type can't be added without being checked here.
+            let (expected_op_code, expected_chunks) = match &op {
+                ZkSyncOp::Noop(_) => (NoopOp::OP_CODE, NoopOp::CHUNKS),
+                ZkSyncOp::Deposit(_) => (DepositOp::OP_CODE, DepositOp::CHUNKS),
+                ZkSyncOp::TransferToNew(_) => (TransferToNewOp::OP_CODE, TransferToNewOp::CHUNKS),
+                ZkSyncOp::Withdraw(_) => (WithdrawOp::OP_CODE, WithdrawOp::CHUNKS),
+                ZkSyncOp::Close(_) => (CloseOp::OP_CODE, CloseOp::CHUNKS),
+                ZkSyncOp::Transfer(_) => (TransferOp::OP_CODE, TransferOp::CHUNKS),
+                ZkSyncOp::FullExit(_) => (FullExitOp::OP_CODE, FullExitOp::CHUNKS),
+                ZkSyncOp::ChangePubKeyOffchain(_) => {
+                    (ChangePubKeyOp::OP_CODE, ChangePubKeyOp::CHUNKS)
+                }
+                ZkSyncOp::ForcedExit(_) => (ForcedExitOp::OP_CODE, ForcedExitOp::CHUNKS),
+                ZkSyncOp::MintNFTOp(_) => (MintNFTOp::OP_CODE, MintNFTOp::CHUNKS),
+                ZkSyncOp::WithdrawNFT(_) => (WithdrawNFTOp::OP_CODE, WithdrawNFTOp::CHUNKS),
+                ZkSyncOp::Swap(_) => (SwapOp::OP_CODE, SwapOp::CHUNKS),
+            };
+            assert_eq!(*op_code, expected_op_code, "{:?}", op);
+            assert_eq!(*chunks, expected_chunks, "{:?}", op);
+            op_types.insert(std::mem::discriminant(&op));
+        }
+
+        // Table entries belong to distinct operation types, and no other code is recognized.
+        assert_eq!(op_types.len(), OP_CODE_CHUNKS.len());
+        let known_codes = (0..=u8::MAX)
+            .filter(|code| ZkSyncOp::chunks_for_op_code(*code).is_some())
+            .count();
+        assert_eq!(known_codes, OP_CODE_CHUNKS.len());
+    }
+
+    #[test]
+    fn affects_state() {
+        for (op_code, chunks) in OP_CODE_CHUNKS.iter() {
+            // Operation with all the fields set to zero.
+            let mut pubdata = vec![0u8; chunks * CHUNK_BYTES];
+            pubdata[0] = *op_code;
+            let op = ZkSyncOp::from_public_data(&pubdata).unwrap();
+
+            let expected = !matches!(op, ZkSyncOp::Noop(_) | ZkSyncOp::FullExit(_));
+            assert_eq!(op.affects_state(), expected, "{:?}", op);
+        }
+
+        let full_exit = |withdraw_amount: Option<u32>| {
+            ZkSyncOp::from(FullExitOp {
+                priority_op: FullExit {
+                    account_id: AccountId(1),
+                    eth_address: Address::random(),
+                    token: TokenId(0),
+                    is_legacy: false,
+                },
+                withdraw_amount: withdraw_amount.map(|amount| BigUint::from(amount).into()),
+                creator_account_id: None,
+                creator_address: None,
+                serial_id: None,
+                content_hash: None,
+            })
+        };
+        assert!(full_exit(Some(10)).affects_state());
+        assert!(!full_exit(Some(0)).affects_state());
+        assert!(!full_exit(None).affects_state());
+    }
+
+    #[test]
+    fn collect_fee_tokens_from_ops() {
+        let change_pubkey_op = |fee_token: TokenId| {
+            ZkSyncOp::from(ChangePubKeyOp {
+                tx: ChangePubKey::new(
+                    AccountId(1),
+                    Address::random(),
+                    PubKeyHash::default(),
+                    fee_token,
+                    BigUint::from(10u32),
+                    Nonce(0),
+                    Default::default(),
+                    None,
+                    None,
+                ),
+                account_id: AccountId(1),
+            })
+        };
+        let ops = vec![
+            change_pubkey_op(TokenId(1)),
+            ZkSyncOp::Noop(NoopOp {}),
+            change_pubkey_op(TokenId(2)),
+            change_pubkey_op(TokenId(1)),
+        ];
+
+        assert_eq!(ops[0].fee_token(), Some(TokenId(1)));
+        assert_eq!(ops[1].fee_token(), None);
+        assert_eq!(
+            collect_fee_tokens(&ops),
+            vec![TokenId(1), TokenId(2)].into_iter().collect()
+        );
+        assert!(collect_fee_tokens(&[]).is_empty());
+    }
+
+    #[test]
+    fn validate_fee() {
+        let change_pubkey_op = |fee_token: TokenId, fee: BigUint| {
+            ZkSyncOp::from(ChangePubKeyOp {
+                tx: ChangePubKey::new(
+                    AccountId(1),
+                    Address::random(),
+                    PubKeyHash::default(),
+                    fee_token,
+                    fee,
+                    Nonce(0),
+                    Default::default(),
+                    None,
+                    None,
+                ),
+                account_id: AccountId(1),
+            })
+        };
+        let max_fee = num::pow(BigUint::from(10u32), 30);
+
+        let op = change_pubkey_op(TokenId(1), max_fee.clone());
+        assert_eq!(op.fee(), Some((TokenId(1), max_fee.clone())));
+        assert_eq!(op.validate_fee(&max_fee), Ok(()));
+
+        // The smallest packable fee above the maximum is decoded as is, but rejected by the validation.
+        let over_max_fee = &max_fee + num::pow(BigUint::from(10u32), 27);
+        let op = change_pubkey_op(TokenId(1), over_max_fee.clone());
+        let op = ZkSyncOp::from_public_data(&op.public_data()).unwrap();
+        assert_eq!(
+            op.validate_fee(&max_fee),
+            Err(FeeValidationError::FeeTooBig {
+                fee: over_max_fee,
+                max: max_fee.clone(),
+            })
+        );
+
+        let op = change_pubkey_op(TokenId(MIN_NFT_TOKEN_ID - 1), BigUint::from(1u32));
+        assert_eq!(op.validate_fee(&max_fee), Ok(()));
+        let op = change_pubkey_op(TokenId(MIN_NFT_TOKEN_ID), BigUint::from(1u32));
+        assert_eq!(
+            op.validate_fee(&max_fee),
+            Err(FeeValidationError::InvalidFeeToken(TokenId(
+                MIN_NFT_TOKEN_ID
+            )))
+        );
+
+        // Operations without fees are always valid.
+        assert_eq!(ZkSyncOp::Noop(NoopOp {}).fee(), None);
+        assert_eq!(ZkSyncOp::Noop(NoopOp {}).validate_fee(&max_fee), Ok(()));
+    }
+
+    #[test]
+    fn account_id_pubdata_encoding() {
+        let max_account_id = AccountId((1 << used_account_subtree_depth()) - 1);
+        assert_eq!(
+            account_id_to_pubdata(max_account_id),
+            max_account_id.to_be_bytes()
+        );
+        assert_eq!(account_id_to_pubdata(AccountId(0)), [0u8; 4]);
+    }
+
+    #[test]
+    #[should_panic(expected = "doesn't fit into the account tree")]
+    fn out_of_range_account_id_pubdata() {
+        let op = ZkSyncOp::from(ChangePubKeyOp {
+            tx: ChangePubKey::new(
+                AccountId(1 << used_account_subtree_depth()),
+                Address::random(),
+                PubKeyHash::default(),
+                TokenId(0),
+                BigUint::from(10u32),
+                Nonce(0),
+                Default::default(),
+                None,
+                None,
+            ),
+            account_id: AccountId(1 << used_account_subtree_depth()),
+        });
+        op.public_data();
+    }
+}
@@ -8,7 +8,7 @@ use zksync_crypto::primitives::FromBytes;
 
 use crate::{
     helpers::{pack_fee_amount, pack_token_amount, unpack_fee_amount, unpack_token_amount},
-    operations::error::SwapOpError,
+    operations::{account_id_to_pubdata, error::SwapOpError},
     tx::Order,
     AccountId, Address, Nonce, Swap, TokenId,
 };
@@ -28,11 +28,11 @@ impl SwapOp {
 
     pub(crate) fn get_public_data(&self) -> Vec<u8> {
         let mut data = vec![Self::OP_CODE]; // opcode
-        data.extend_from_slice(&self.accounts.0.to_be_bytes());
-        data.extend_from_slice(&self.recipients.0.to_be_bytes());
-        data.extend_from_slice(&self.accounts.1.to_be_bytes());
-        data.extend_from_slice(&self.recipients.1.to_be_bytes());
-        data.extend_from_slice(&self.submitter.to_be_bytes());
+        data.extend_from_slice(&account_id_to_pubdata(self.accounts.0));
+        data.extend_from_slice(&account_id_to_pubdata(self.recipients.0));
+        data.extend_from_slice(&account_id_to_pubdata(self.accounts.1));
+        data.extend_from_slice(&account_id_to_pubdata(self.recipients.1));
+        data.extend_from_slice(&account_id_to_pubdata(self.submitter));
         data.extend_from_slice(&self.tx.orders.0.token_sell.to_be_bytes());
         data.extend_from_slice(&self.tx.orders.1.token_sell.to_be_bytes());
         data.extend_from_slice(&self.tx.fee_token.to_be_bytes());
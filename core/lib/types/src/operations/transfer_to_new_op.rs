@@ -1,6 +1,6 @@
 use crate::{
     helpers::{pack_fee_amount, pack_token_amount, unpack_fee_amount, unpack_token_amount},
-    operations::error::TransferOpError,
+    operations::{account_id_to_pubdata, error::TransferOpError},
     AccountId, Address, Nonce, TokenId, Transfer,
 };
 use serde::{Deserialize, Serialize};
@@ -27,11 +27,11 @@ impl TransferToNewOp {
 
     pub(crate) fn get_public_data(&self) -> Vec<u8> {
         let mut data = vec![Self::OP_CODE];
-        data.extend_from_slice(&self.from.to_be_bytes());
+        data.extend_from_slice(&account_id_to_pubdata(self.from));
         data.extend_from_slice(&self.tx.token.to_be_bytes());
         data.extend_from_slice(&pack_token_amount(&self.tx.amount));
         data.extend_from_slice(self.tx.to.as_bytes());
-        data.extend_from_slice(&self.to.to_be_bytes());
+        data.extend_from_slice(&account_id_to_pubdata(self.to));
         data.extend_from_slice(&pack_fee_amount(&self.tx.fee));
         data.resize(Self::CHUNKS * CHUNK_BYTES, 0x00);
         data
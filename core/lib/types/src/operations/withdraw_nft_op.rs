@@ -8,6 +8,7 @@ use zksync_crypto::primitives::FromBytes;
 use crate::operations::error::WithdrawNFTOpError;
 use crate::{
     helpers::{pack_fee_amount, unpack_fee_amount},
+    operations::account_id_to_pubdata,
     tx::WithdrawNFT,
     AccountId, Address, Nonce, TokenId, H256,
 };
@@ -29,8 +30,8 @@ impl WithdrawNFTOp {
 
     pub(crate) fn get_public_data(&self) -> Vec<u8> {
         let mut data = vec![Self::OP_CODE];
-        data.extend_from_slice(&self.tx.account_id.to_be_bytes());
-        data.extend_from_slice(&self.creator_id.to_be_bytes());
+        data.extend_from_slice(&account_id_to_pubdata(self.tx.account_id));
+        data.extend_from_slice(&account_id_to_pubdata(self.creator_id));
         data.extend_from_slice(self.creator_address.as_bytes());
         data.extend_from_slice(&self.serial_id.to_be_bytes());
         data.extend_from_slice(self.content_hash.as_bytes());
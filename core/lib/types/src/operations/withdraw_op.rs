@@ -1,6 +1,6 @@
 use crate::{
     helpers::{pack_fee_amount, unpack_fee_amount},
-    operations::error::WithdrawOpError,
+    operations::{account_id_to_pubdata, error::WithdrawOpError},
     AccountId, Address, Nonce, TokenId, Withdraw,
 };
 use num::{BigUint, FromPrimitive, ToPrimitive};
@@ -28,7 +28,7 @@ impl WithdrawOp {
 
     pub(crate) fn get_public_data(&self) -> Vec<u8> {
         let mut data = vec![Self::OP_CODE];
-        data.extend_from_slice(&self.account_id.to_be_bytes());
+        data.extend_from_slice(&account_id_to_pubdata(self.account_id));
         data.extend_from_slice(&self.tx.token.to_be_bytes());
         data.extend_from_slice(&self.tx.amount.to_u128().unwrap().to_be_bytes());
         data.extend_from_slice(&pack_fee_amount(&self.tx.fee));
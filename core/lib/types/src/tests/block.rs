@@ -1,9 +1,33 @@
-use zksync_basic_types::{AccountId, BlockNumber, H256};
+use num::BigInt;
+use std::collections::HashMap;
+use zksync_basic_types::{AccountId, BlockNumber, TokenId, H256};
 use zksync_crypto::ff::Field;
+use zksync_crypto::params::CHUNK_BYTES;
 use zksync_crypto::Fr;
 
 use super::utils::*;
-use crate::block::Block;
+use crate::block::{
+    assign_block_indices, plan_block_sizes, validate_block_sizes, Block, BlockSizeEstimate,
+    BlockSizesError, BlockValidationError, ExecutedOperations, ExecutedTx, OperationEvent,
+};
+use crate::operations::parse_block_pubdata;
+use crate::{DepositOp, TransferOp, ZkSyncOp};
+
+/// Creates a block with the given operations, the rest of the fields can be overridden by the caller.
+fn test_block(block_transactions: Vec<ExecutedOperations>) -> Block {
+    Block::new(
+        BlockNumber(0),
+        Fr::one(),
+        AccountId(0),
+        block_transactions,
+        (0, 0),
+        100,
+        1_000_000.into(),
+        1_500_000.into(),
+        H256::default(),
+        0,
+    )
+}
 
 /// Checks that we cannot create a block with invalid block sizes provided.
 #[test]
@@ -26,18 +50,10 @@ fn no_supported_block_size() {
 /// Checks that the byte order is indeed big-endian.
 #[test]
 fn test_get_eth_encoded_root() {
-    let block = Block::new(
-        BlockNumber(0),
-        Fr::one(),
-        AccountId(0),
-        vec![],
-        (0, 0),
-        1,
-        1_000_000.into(),
-        1_500_000.into(),
-        H256::default(),
-        0,
-    );
+    let block = Block {
+        block_chunks_size: 1,
+        ..test_block(vec![])
+    };
 
     let mut bytes = [0u8; 32];
     let byte = bytes.last_mut().unwrap();
@@ -46,24 +62,28 @@ fn test_get_eth_encoded_root() {
     assert_eq!(block.get_eth_encoded_root(), H256::from(bytes));
 }
 
+#[test]
+fn test_is_empty() {
+    assert!(test_block(vec![]).is_empty());
+    assert!(!test_block(vec![create_withdraw_tx()]).is_empty());
+}
+
+#[test]
+fn test_root_hash_hex() {
+    let new_block = || test_block(vec![create_withdraw_tx()]);
+
+    let root_hash = new_block().root_hash_hex();
+    assert_eq!(root_hash, new_block().root_hash_hex());
+    assert_eq!(root_hash, format!("0x{}1", "0".repeat(63)));
+}
+
 #[test]
 fn test_get_eth_public_data() {
-    let mut block = Block::new(
-        BlockNumber(0),
-        Fr::one(),
-        AccountId(0),
-        vec![
-            create_change_pubkey_tx(),
-            create_full_exit_op(),
-            create_withdraw_tx(),
-        ],
-        (0, 0),
-        100,
-        1_000_000.into(),
-        1_500_000.into(),
-        H256::default(),
-        0,
-    );
+    let mut block = test_block(vec![
+        create_change_pubkey_tx(),
+        create_full_exit_op(),
+        create_withdraw_tx(),
+    ]);
 
     let expected = {
         let mut data = vec![];
@@ -84,6 +104,165 @@ fn test_get_eth_public_data() {
     assert!(block.get_eth_public_data().iter().all(|&i| i == 0));
 }
 
+/// Checks that the public data follows the order of operations in the block,
+/// with transactions and priority operations interleaved and failed transactions skipped.
+#[test]
+fn test_get_eth_public_data_order() {
+    let mut failed_tx = create_transfer_tx();
+    if let ExecutedOperations::Tx(tx) = &mut failed_tx {
+        tx.success = false;
+        tx.op = None;
+        tx.fail_reason = Some("Not enough balance".to_string());
+    }
+    let mut operations = vec![
+        create_deposit_op(),
+        create_withdraw_tx(),
+        failed_tx,
+        create_full_exit_op(),
+        create_transfer_tx(),
+        create_change_pubkey_tx(),
+    ];
+    assign_block_indices(&mut operations);
+
+    let mut block = test_block(operations);
+    assert_eq!(block.assert_operations_ordered(), Ok(()));
+
+    let successful_ops = [0, 1, 3, 4, 5];
+    let expected: Vec<u8> = successful_ops
+        .iter()
+        .flat_map(|&idx| {
+            block.block_transactions[idx]
+                .get_executed_op()
+                .unwrap()
+                .public_data()
+        })
+        .collect();
+    let mut result = block.get_eth_public_data();
+    result.truncate(expected.len());
+    assert_eq!(result, expected);
+
+    // Storing operations out of the execution order breaks the invariant.
+    block.block_transactions.swap(3, 4);
+    assert_eq!(
+        block.assert_operations_ordered(),
+        Err(BlockValidationError::OperationsNotOrdered {
+            position: 4,
+            previous: 3,
+            current: 2,
+        })
+    );
+    // Successful operations must have block indices.
+    if let ExecutedOperations::Tx(tx) = &mut block.block_transactions[1] {
+        tx.block_index = None;
+    }
+    assert_eq!(
+        block.assert_operations_ordered(),
+        Err(BlockValidationError::MissingBlockIndex { position: 1 })
+    );
+}
+
+/// Checks that splitting a block preserves the operations and their public data,
+/// and that the resulting blocks have consistent numbers and priority operation ranges.
+#[test]
+fn test_try_split() {
+    let mut failed_tx = create_transfer_tx();
+    if let ExecutedOperations::Tx(tx) = &mut failed_tx {
+        tx.success = false;
+        tx.op = None;
+    }
+    // Chunks used by the operations: `[6, 6, 0, 11, 2, 6]`.
+    let mut operations = vec![
+        create_deposit_op(),
+        create_withdraw_tx(),
+        failed_tx,
+        create_full_exit_op(),
+        create_transfer_tx(),
+        create_change_pubkey_tx(),
+    ];
+    assign_block_indices(&mut operations);
+    let block = Block {
+        block_number: BlockNumber(10),
+        processed_priority_ops: (5, 7),
+        block_chunks_size: 31,
+        ..test_block(operations)
+    };
+    let original_pubdata: Vec<u8> = block
+        .successful_operations()
+        .flat_map(ZkSyncOp::public_data)
+        .collect();
+
+    // Block that fits is not split.
+    let blocks = block.clone().try_split(&[6, 12, 32]);
+    assert_eq!(blocks.len(), 1);
+    assert_eq!(blocks[0].block_chunks_size, 31);
+
+    let blocks = block.try_split(&[6, 12]);
+    assert_eq!(blocks.len(), 3);
+
+    let ops_per_block: Vec<_> = blocks.iter().map(|b| b.block_transactions.len()).collect();
+    assert_eq!(ops_per_block, vec![3, 1, 2]);
+    let block_numbers: Vec<_> = blocks.iter().map(|b| b.block_number).collect();
+    assert_eq!(
+        block_numbers,
+        vec![BlockNumber(10), BlockNumber(11), BlockNumber(12)]
+    );
+    let priority_ops: Vec<_> = blocks.iter().map(|b| b.processed_priority_ops).collect();
+    assert_eq!(priority_ops, vec![(5, 6), (6, 7), (7, 7)]);
+    assert!(blocks.iter().all(|b| b.block_chunks_size == 12));
+    assert!(blocks.iter().all(|b| b.assert_operations_ordered().is_ok()));
+    assert_eq!(blocks[2].new_root_hash, Fr::one());
+    assert_eq!(blocks[0].new_root_hash, Fr::zero());
+
+    // Concatenated public data of the resulting blocks matches the original one.
+    let mut split_pubdata = Vec::new();
+    for block in &blocks {
+        let pubdata = block.get_eth_public_data();
+        assert_eq!(pubdata.len(), block.block_chunks_size * CHUNK_BYTES);
+
+        let ops_pubdata: Vec<u8> = block
+            .successful_operations()
+            .flat_map(ZkSyncOp::public_data)
+            .collect();
+        assert_eq!(pubdata[..ops_pubdata.len()], ops_pubdata[..]);
+        split_pubdata.extend(ops_pubdata);
+    }
+    assert_eq!(split_pubdata, original_pubdata);
+}
+
+#[test]
+fn test_parse_block_pubdata() {
+    let block = test_block(vec![
+        create_change_pubkey_tx(),
+        create_full_exit_op(),
+        create_withdraw_tx(),
+        create_transfer_tx(),
+        create_deposit_op(),
+    ]);
+    let pubdata = block.get_eth_public_data();
+
+    let ops = parse_block_pubdata(&pubdata).expect("Failed to parse block pubdata");
+    let (executed_ops, padding) = ops.split_at(block.block_transactions.len());
+    for (parsed, executed) in executed_ops.iter().zip(&block.block_transactions) {
+        assert_eq!(
+            parsed.public_data(),
+            executed.get_executed_op().unwrap().public_data()
+        );
+    }
+    assert!(padding.iter().all(|op| matches!(op, ZkSyncOp::Noop(_))));
+    assert_eq!(
+        ops.iter()
+            .flat_map(ZkSyncOp::public_data)
+            .collect::<Vec<_>>(),
+        pubdata
+    );
+
+    // Operation cut in the middle can't be parsed.
+    let first_op_length = executed_ops[0].public_data().len();
+    assert!(parse_block_pubdata(&pubdata[..first_op_length - 1]).is_err());
+    let (_, consumed) = ZkSyncOp::from_public_data_prefix(&pubdata).unwrap();
+    assert_eq!(consumed, first_op_length);
+}
+
 #[test]
 fn test_get_eth_witness_data() {
     let operations = vec![
@@ -93,18 +272,7 @@ fn test_get_eth_witness_data() {
         create_change_pubkey_tx(),
     ];
     let change_pubkey_tx = &operations[0];
-    let mut block = Block::new(
-        BlockNumber(0),
-        Fr::one(),
-        AccountId(0),
-        operations.clone(),
-        (0, 0),
-        100,
-        1_000_000.into(),
-        1_500_000.into(),
-        H256::default(),
-        0,
-    );
+    let mut block = test_block(operations.clone());
 
     let witness = change_pubkey_tx
         .get_executed_op()
@@ -136,18 +304,7 @@ fn test_get_withdrawals_data() {
         create_full_exit_op(),
         create_withdraw_tx(),
     ];
-    let mut block = Block::new(
-        BlockNumber(0),
-        Fr::one(),
-        AccountId(0),
-        operations.clone(),
-        (0, 0),
-        100,
-        1_000_000.into(),
-        1_500_000.into(),
-        H256::default(),
-        0,
-    );
+    let mut block = test_block(operations.clone());
 
     let expected = {
         let mut data = vec![];
@@ -166,3 +323,351 @@ fn test_get_withdrawals_data() {
     // No more corresponding operations left.
     assert!(block.get_withdrawals_data().is_empty());
 }
+
+#[test]
+fn test_validate_timestamp_after() {
+    let block = Block {
+        block_number: BlockNumber(1),
+        block_chunks_size: 1,
+        timestamp: 100,
+        ..test_block(vec![])
+    };
+
+    // There is no previous block.
+    assert_eq!(block.validate_timestamp_after(None), Ok(()));
+    // Timestamps are equal.
+    assert_eq!(block.validate_timestamp_after(Some(100)), Ok(()));
+    // Timestamp is greater than the previous one.
+    assert_eq!(block.validate_timestamp_after(Some(99)), Ok(()));
+    // Timestamp is less than the previous one.
+    assert_eq!(
+        block.validate_timestamp_after(Some(101)),
+        Err(BlockValidationError::TimestampDecreased {
+            timestamp: 100,
+            previous_timestamp: 101,
+        })
+    );
+}
+
+#[test]
+fn test_duplicate_priority_ops() {
+    let mut block = Block {
+        block_number: BlockNumber(1),
+        processed_priority_ops: (0, 1),
+        ..test_block(vec![create_deposit_op(), create_transfer_tx()])
+    };
+    assert!(!block.has_duplicate_priority_ops());
+    assert_eq!(block.validate_no_duplicate_priority_ops(), Ok(()));
+
+    // Both test priority operations have zero serial id.
+    block.block_transactions.push(create_full_exit_op());
+    assert!(block.has_duplicate_priority_ops());
+    assert_eq!(
+        block.validate_no_duplicate_priority_ops(),
+        Err(BlockValidationError::DuplicatePriorityOp { serial_id: 0 })
+    );
+}
+
+/// Checks that only non-empty, strictly ascending lists of non-zero block sizes are accepted.
+#[test]
+fn test_validate_block_sizes() {
+    assert_eq!(validate_block_sizes(&[6]), Ok(()));
+    assert_eq!(validate_block_sizes(&[6, 30, 74]), Ok(()));
+
+    assert_eq!(validate_block_sizes(&[]), Err(BlockSizesError::Empty));
+    assert_eq!(
+        validate_block_sizes(&[0, 6]),
+        Err(BlockSizesError::ZeroSize)
+    );
+    assert_eq!(
+        validate_block_sizes(&[6, 74, 30]),
+        Err(BlockSizesError::NotAscending {
+            previous: 74,
+            current: 30,
+        })
+    );
+    assert_eq!(
+        validate_block_sizes(&[6, 30, 30]),
+        Err(BlockSizesError::NotAscending {
+            previous: 30,
+            current: 30,
+        })
+    );
+}
+
+/// Checks the padding estimated by the block size planner for a known workload.
+#[test]
+fn test_plan_block_sizes() {
+    // Operations are interleaved, so the workload is packed as
+    // `[transfer, deposit, transfer, deposit, transfer, transfer]`, i.e. `[2, 6, 2, 6, 2, 2]` chunks.
+    let op_mix = [(TransferOp::OP_CODE, 4), (DepositOp::OP_CODE, 2)];
+    // Blocks of 4 chunks cannot fit deposits, so the configuration with the maximum size of 4 is not considered.
+    // Smaller sizes are still used by the other configurations to seal blocks with few chunks.
+    let plan = plan_block_sizes(&op_mix, &[12, 4, 6]);
+
+    assert_eq!(
+        plan.estimates,
+        vec![
+            // Blocks: `[2]`, `[6]`, `[2]`, `[6]`, `[2, 2]`, blocks with transfers are padded up to 4 chunks.
+            BlockSizeEstimate {
+                max_block_size: 6,
+                blocks: 5,
+                padding_chunks: 4,
+                average_padding: 0.8,
+            },
+            // Blocks: `[2, 6, 2]`, `[6, 2, 2]`, both are padded up to 12 chunks.
+            BlockSizeEstimate {
+                max_block_size: 12,
+                blocks: 2,
+                padding_chunks: 4,
+                average_padding: 2.0,
+            },
+        ]
+    );
+    // Both configurations have the same padding, so the one with smaller blocks is preferred.
+    assert_eq!(plan.best().unwrap().max_block_size, 6);
+
+    // Empty workload requires no blocks.
+    let plan = plan_block_sizes(&[], &[6]);
+    assert_eq!(plan.estimates[0].blocks, 0);
+    assert_eq!(plan.estimates[0].padding_chunks, 0);
+}
+
+#[test]
+fn test_assert_fee_account() {
+    let block = Block {
+        block_number: BlockNumber(1),
+        fee_account: AccountId(5),
+        block_chunks_size: 1,
+        ..test_block(vec![])
+    };
+
+    assert_eq!(block.assert_fee_account(AccountId(5)), Ok(()));
+    assert_eq!(
+        block.assert_fee_account(AccountId(6)),
+        Err(BlockValidationError::FeeAccountMismatch {
+            expected: AccountId(6),
+            actual: AccountId(5),
+        })
+    );
+}
+
+#[test]
+fn test_successful_operations() {
+    let mut failed_tx = create_withdraw_tx();
+    if let ExecutedOperations::Tx(tx) = &mut failed_tx {
+        tx.success = false;
+        tx.fail_reason = Some("Not enough balance".to_string());
+    }
+
+    let block = test_block(vec![
+        create_change_pubkey_tx(),
+        failed_tx,
+        create_full_exit_op(),
+    ]);
+
+    let successful_ops = block.successful_operations().collect::<Vec<_>>();
+    assert_eq!(successful_ops.len(), 2);
+    assert!(matches!(
+        successful_ops[0],
+        ZkSyncOp::ChangePubKeyOffchain(_)
+    ));
+    assert!(matches!(successful_ops[1], ZkSyncOp::FullExit(_)));
+}
+
+#[test]
+fn test_executed_tx_batch_membership() {
+    let mut executed_tx = create_withdraw_tx().get_executed_tx().cloned().unwrap();
+    assert!(!executed_tx.is_batch_member());
+
+    executed_tx.batch_id = Some(1);
+    assert!(executed_tx.is_batch_member());
+
+    // Transactions serialized before the `batch_id` field was introduced
+    // should be treated as non-batched ones.
+    let mut serialized = serde_json::to_value(&executed_tx).unwrap();
+    serialized.as_object_mut().unwrap().remove("batch_id");
+    let deserialized: ExecutedTx = serde_json::from_value(serialized).unwrap();
+    assert_eq!(deserialized.batch_id, None);
+    assert!(!deserialized.is_batch_member());
+}
+
+#[test]
+fn test_operation_to_event() {
+    let transfer_event = create_transfer_tx().to_event();
+    assert_eq!(
+        transfer_event,
+        OperationEvent {
+            kind: "Transfer".to_string(),
+            block_index: Some(0),
+            success: true,
+            accounts: vec![AccountId(1), AccountId(2)],
+            token: Some(TokenId(0)),
+            amount: Some(100u32.into()),
+        }
+    );
+
+    let deposit_event = create_deposit_op().to_event();
+    assert_eq!(
+        deposit_event,
+        OperationEvent {
+            kind: "Deposit".to_string(),
+            block_index: Some(1),
+            success: true,
+            accounts: vec![AccountId(3)],
+            token: Some(TokenId(0)),
+            amount: Some(500u32.into()),
+        }
+    );
+
+    let mut failed_tx = create_transfer_tx();
+    if let ExecutedOperations::Tx(tx) = &mut failed_tx {
+        tx.success = false;
+        tx.op = None;
+        tx.block_index = None;
+        tx.fail_reason = Some("Not enough balance".to_string());
+    }
+    assert_eq!(
+        failed_tx.to_event(),
+        OperationEvent {
+            kind: "Transfer".to_string(),
+            block_index: None,
+            success: false,
+            accounts: vec![AccountId(1)],
+            token: Some(TokenId(0)),
+            amount: None,
+        }
+    );
+}
+
+#[test]
+fn test_account_deltas() {
+    let fee_account = AccountId(10);
+    let block = Block {
+        block_number: BlockNumber(1),
+        fee_account,
+        processed_priority_ops: (0, 1),
+        ..test_block(vec![
+            create_deposit_op(),
+            create_transfer_tx(),
+            create_withdraw_tx(),
+        ])
+    };
+
+    let token = TokenId(0);
+    let expected: HashMap<(AccountId, TokenId), BigInt> = vec![
+        // Deposit of 500 to account 3.
+        ((AccountId(3), token), BigInt::from(500)),
+        // Transfer of 100 with fee 10 from account 1 to account 2.
+        ((AccountId(1), token), BigInt::from(-110)),
+        ((AccountId(2), token), BigInt::from(100)),
+        // Withdraw of 100 with fee 10 from account 0.
+        ((AccountId(0), token), BigInt::from(-110)),
+        // Fees of both the transfer and the withdrawal.
+        ((fee_account, token), BigInt::from(20)),
+    ]
+    .into_iter()
+    .collect();
+
+    assert_eq!(block.account_deltas(), expected);
+}
+
+/// Checks the serialized form of `ExecutedOperations` and that it survives a serialization roundtrip.
+#[test]
+fn executed_operations_serde_roundtrip() {
+    let mut failed_tx = create_transfer_tx();
+    if let ExecutedOperations::Tx(tx) = &mut failed_tx {
+        tx.success = false;
+        tx.op = None;
+        tx.fail_reason = Some("Not enough balance".to_string());
+    }
+    let operations = vec![
+        create_withdraw_tx(),
+        failed_tx,
+        create_deposit_op(),
+        create_full_exit_op(),
+    ];
+    let expected_tags = ["Tx", "Tx", "PriorityOp", "PriorityOp"];
+
+    for (operation, expected_tag) in operations.into_iter().zip(expected_tags.iter()) {
+        let serialized = serde_json::to_value(&operation).unwrap();
+        assert_eq!(serialized["type"], *expected_tag);
+
+        let deserialized: ExecutedOperations = serde_json::from_value(serialized.clone()).unwrap();
+        assert_eq!(serde_json::to_value(&deserialized).unwrap(), serialized);
+
+        match (&operation, &deserialized) {
+            (ExecutedOperations::Tx(expected), ExecutedOperations::Tx(actual)) => {
+                assert_eq!(actual.signed_tx.hash(), expected.signed_tx.hash());
+                assert_eq!(actual.success, expected.success);
+                assert_eq!(actual.fail_reason, expected.fail_reason);
+                assert_eq!(actual.created_at, expected.created_at);
+            }
+            (ExecutedOperations::PriorityOp(expected), ExecutedOperations::PriorityOp(actual)) => {
+                assert_eq!(actual.priority_op.serial_id, expected.priority_op.serial_id);
+                assert_eq!(actual.created_at, expected.created_at);
+            }
+            _ => panic!("Operation type changed after the roundtrip"),
+        }
+    }
+}
+
+/// Checks that `ExecutedOperations` can be deserialized using the alternative tag spellings.
+#[test]
+fn executed_operations_tag_aliases() {
+    let aliases = [
+        (create_withdraw_tx(), "tx"),
+        (create_deposit_op(), "priorityOp"),
+        (create_deposit_op(), "priority_op"),
+    ];
+
+    for (operation, alias) in aliases.iter() {
+        let mut serialized = serde_json::to_value(operation).unwrap();
+        serialized["type"] = (*alias).into();
+
+        let deserialized: ExecutedOperations = serde_json::from_value(serialized).unwrap();
+        assert_eq!(
+            std::mem::discriminant(&deserialized),
+            std::mem::discriminant(operation)
+        );
+    }
+}
+
+/// Checks that block indices are assigned sequentially to the successful operations only.
+#[test]
+fn test_assign_block_indices() {
+    let failed_tx = || {
+        let mut tx = create_transfer_tx();
+        if let ExecutedOperations::Tx(tx) = &mut tx {
+            tx.success = false;
+            tx.op = None;
+            tx.fail_reason = Some("Nonce mismatch".to_string());
+            // Must be reset by `assign_block_indices`.
+            tx.block_index = Some(100);
+        }
+        tx
+    };
+    let mut ops = vec![
+        failed_tx(),
+        create_withdraw_tx(),
+        create_deposit_op(),
+        failed_tx(),
+        failed_tx(),
+        create_transfer_tx(),
+        create_full_exit_op(),
+        failed_tx(),
+    ];
+
+    assign_block_indices(&mut ops);
+
+    let block_indices: Vec<_> = ops.iter().map(ExecutedOperations::block_index).collect();
+    assert_eq!(
+        block_indices,
+        vec![None, Some(0), Some(1), None, None, Some(2), Some(3), None]
+    );
+
+    // Assignment is deterministic.
+    assign_block_indices(&mut ops);
+    let reassigned: Vec<_> = ops.iter().map(ExecutedOperations::block_index).collect();
+    assert_eq!(reassigned, block_indices);
+}
@@ -21,7 +21,7 @@ use crate::{
         ChangePubKey, ForcedExit, MintNFT, Order, PackedEthSignature, Swap, TimeRange, Transfer,
         Withdraw, WithdrawNFT,
     },
-    Log, PriorityOp,
+    Log, PriorityOp, ZkSyncTx,
 };
 use once_cell::sync::Lazy;
 use zksync_basic_types::{AccountId, Address, Nonce, TokenId, H256};
@@ -535,6 +535,59 @@ pub mod tx_conversion_test {
         let bytes = withdraw.get_bytes();
         assert_eq!(hex::encode(bytes), "fc01000000642a0a81e257a2f5d6ed4f07b81dbda09f107bd02621abaed8712072e918632259780e587698ef58da0000000500000000000000000000000000bc614e7d030000001400000000000000000000000060183ed0");
     }
+
+    /// Transaction hashes are used by clients to track their transactions,
+    /// so the values below can serve as test vectors for the client implementations.
+    #[test]
+    fn test_tx_hash() {
+        let transfer = ZkSyncTx::from(Transfer::new(
+            ACCOUNT_ID,
+            *ALICE,
+            *BOB,
+            TOKEN_ID,
+            (*AMOUNT).clone(),
+            (*FEE).clone(),
+            NONCE,
+            *TIME_RANGE,
+            None,
+        ));
+        assert_eq!(
+            transfer.hash().to_string(),
+            "sync-tx:da9a154aa7e33347a631e966527e1551b7ddb77f68d2796057e90b306221883a"
+        );
+
+        let withdraw = ZkSyncTx::from(Withdraw::new(
+            ACCOUNT_ID,
+            *ALICE,
+            *BOB,
+            TOKEN_ID,
+            (*AMOUNT).clone(),
+            (*FEE).clone(),
+            NONCE,
+            *TIME_RANGE,
+            None,
+        ));
+        assert_eq!(
+            withdraw.hash().to_string(),
+            "sync-tx:ca373ed079c6cac863477614cfc9adca5289698ee737fc12db2643866f61c6f5"
+        );
+
+        let change_pubkey = ZkSyncTx::from(ChangePubKey::new(
+            ACCOUNT_ID,
+            *ALICE,
+            *PK_HASH,
+            TOKEN_ID,
+            (*FEE).clone(),
+            NONCE,
+            *TIME_RANGE,
+            None,
+            None,
+        ));
+        assert_eq!(
+            change_pubkey.hash().to_string(),
+            "sync-tx:cdb3c3e2a5a73283185599a38f56d1a467e53914862cf29d8cd8705b9c301380"
+        );
+    }
 }
 
 #[test]
@@ -88,3 +88,58 @@ pub fn create_change_pubkey_tx() -> ExecutedOperations {
 
     ExecutedOperations::Tx(Box::new(executed_change_pubkey_op))
 }
+
+pub fn create_transfer_tx() -> ExecutedOperations {
+    let transfer_op = ZkSyncOp::Transfer(Box::new(TransferOp {
+        tx: Transfer::new(
+            AccountId(1),
+            Default::default(),
+            Default::default(),
+            TokenId(0),
+            100u32.into(),
+            10u32.into(),
+            Nonce(0),
+            Default::default(),
+            None,
+        ),
+        from: AccountId(1),
+        to: AccountId(2),
+    }));
+
+    let executed_transfer_op = ExecutedTx {
+        signed_tx: transfer_op.try_get_tx().unwrap().into(),
+        success: true,
+        op: Some(transfer_op),
+        fail_reason: None,
+        block_index: Some(0),
+        created_at: Utc::now(),
+        batch_id: None,
+    };
+
+    ExecutedOperations::Tx(Box::new(executed_transfer_op))
+}
+
+pub fn create_deposit_op() -> ExecutedOperations {
+    let priority_op = Deposit {
+        from: Address::zero(),
+        token: TokenId(0),
+        amount: 500u32.into(),
+        to: Address::zero(),
+    };
+    ExecutedOperations::PriorityOp(Box::new(ExecutedPriorityOp {
+        priority_op: PriorityOp {
+            serial_id: 0,
+            data: ZkSyncPriorityOp::Deposit(priority_op.clone()),
+            deadline_block: 0,
+            eth_hash: H256::zero(),
+            eth_block: 0,
+            eth_block_index: None,
+        },
+        op: ZkSyncOp::Deposit(Box::new(DepositOp {
+            priority_op,
+            account_id: AccountId(3),
+        })),
+        block_index: 1,
+        created_at: Utc::now(),
+    }))
+}
@@ -8,7 +8,11 @@ use thiserror::Error;
 pub use zksync_crypto::params::ETH_TOKEN_ID;
 use zksync_utils::{parse_env, UnsignedRatioSerializeAsDecimal};
 
-use crate::{tx::ChangePubKeyType, AccountId, Address, Log, TokenId, H256, U256};
+use crate::{
+    operations::{ChangePubKeyOp, MintNFTOp, SwapOp, TransferOp, WithdrawNFTOp, WithdrawOp},
+    tx::ChangePubKeyType,
+    AccountId, Address, Log, TokenId, H256, U256,
+};
 
 #[derive(Debug, Error)]
 pub enum NewTokenEventParseError {
@@ -272,6 +276,37 @@ pub enum TxFeeTypes {
     MintNFT,
 }
 
+impl TxFeeTypes {
+    /// Returns the number of chunks occupied by the operation corresponding to the fee type.
+    ///
+    /// Note: for `Transfer` the chunks of `TransferOp` are returned. Transfer to a new account
+    /// results in a `TransferToNewOp` which is larger, so the fee for such transfers must be
+    /// calculated using `TransferToNewOp::CHUNKS`.
+    pub fn chunks(&self) -> usize {
+        match self {
+            Self::Withdraw | Self::FastWithdraw => WithdrawOp::CHUNKS,
+            Self::WithdrawNFT | Self::FastWithdrawNFT => WithdrawNFTOp::CHUNKS,
+            Self::Transfer => TransferOp::CHUNKS,
+            Self::ChangePubKey(_) => ChangePubKeyOp::CHUNKS,
+            Self::Swap => SwapOp::CHUNKS,
+            Self::MintNFT => MintNFTOp::CHUNKS,
+        }
+    }
+
+    /// Returns the code of the operation corresponding to the fee type.
+    /// See `chunks` method for the note about `Transfer`.
+    pub fn op_code(&self) -> u8 {
+        match self {
+            Self::Withdraw | Self::FastWithdraw => WithdrawOp::OP_CODE,
+            Self::WithdrawNFT | Self::FastWithdrawNFT => WithdrawNFTOp::OP_CODE,
+            Self::Transfer => TransferOp::OP_CODE,
+            Self::ChangePubKey(_) => ChangePubKeyOp::OP_CODE,
+            Self::Swap => SwapOp::OP_CODE,
+            Self::MintNFT => MintNFTOp::OP_CODE,
+        }
+    }
+}
+
 /// NFT supported in zkSync protocol
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct NFT {
@@ -329,6 +364,61 @@ pub enum GetGenesisTokenListError {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::operations::ZkSyncOp;
+    use zksync_crypto::params::CHUNK_BYTES;
+
+    #[test]
+    fn tx_fee_type_op_mapping() {
+        let change_pubkey = TxFeeTypes::ChangePubKey(ChangePubKeyFeeTypeArg::ContractsV4Version(
+            ChangePubKeyType::ECDSA,
+        ));
+        let cases = vec![
+            (
+                TxFeeTypes::Withdraw,
+                WithdrawOp::CHUNKS,
+                WithdrawOp::OP_CODE,
+            ),
+            (
+                TxFeeTypes::FastWithdraw,
+                WithdrawOp::CHUNKS,
+                WithdrawOp::OP_CODE,
+            ),
+            (
+                TxFeeTypes::WithdrawNFT,
+                WithdrawNFTOp::CHUNKS,
+                WithdrawNFTOp::OP_CODE,
+            ),
+            (
+                TxFeeTypes::FastWithdrawNFT,
+                WithdrawNFTOp::CHUNKS,
+                WithdrawNFTOp::OP_CODE,
+            ),
+            (
+                TxFeeTypes::Transfer,
+                TransferOp::CHUNKS,
+                TransferOp::OP_CODE,
+            ),
+            (
+                change_pubkey,
+                ChangePubKeyOp::CHUNKS,
+                ChangePubKeyOp::OP_CODE,
+            ),
+            (TxFeeTypes::Swap, SwapOp::CHUNKS, SwapOp::OP_CODE),
+            (TxFeeTypes::MintNFT, MintNFTOp::CHUNKS, MintNFTOp::OP_CODE),
+        ];
+
+        for (fee_type, chunks, op_code) in cases {
+            assert_eq!(fee_type.chunks(), chunks, "{:?}", fee_type);
+            assert_eq!(fee_type.op_code(), op_code, "{:?}", fee_type);
+            // The operation code must agree with the generic chunks lookup.
+            assert_eq!(
+                ZkSyncOp::public_data_length(op_code).unwrap(),
+                chunks * CHUNK_BYTES,
+                "{:?}",
+                fee_type
+            );
+        }
+    }
 
     #[test]
     fn tx_fee_type_deserialize_old_type() {
@@ -21,7 +21,7 @@ use crate::{
     account::PubKeyHash,
     helpers::{is_fee_amount_packable, pack_fee_amount},
     tokens::ChangePubKeyFeeTypeArg,
-    tx::error::ChangePubkeySignedDataError,
+    tx::error::{ChangePubKeyEthAuthError, ChangePubkeySignedDataError},
     tx::version::TxVersion,
     AccountId, Nonce, TxFeeTypes,
 };
@@ -364,29 +364,31 @@ impl ChangePubKey {
     }
 
     pub fn is_eth_auth_data_valid(&self) -> bool {
+        self.verify_eth_auth_data().unwrap_or(false)
+    }
+
+    /// Checks that the Ethereum authorization data was produced by the account owner.
+    /// Unlike `is_eth_auth_data_valid`, reports the reason if the data cannot be verified at all.
+    pub fn verify_eth_auth_data(&self) -> Result<bool, ChangePubKeyEthAuthError> {
         if let Some(eth_auth_data) = &self.eth_auth_data {
             match eth_auth_data {
-                ChangePubKeyEthAuthData::Onchain => true, // Should query Ethereum to check it
+                ChangePubKeyEthAuthData::Onchain => Ok(true), // Should query Ethereum to check it
                 ChangePubKeyEthAuthData::ECDSA(ChangePubKeyECDSAData { eth_signature, .. }) => {
-                    let recovered_address = self
-                        .get_eth_signed_data()
-                        .ok()
-                        .and_then(|msg| eth_signature.signature_recover_signer(&msg).ok());
-                    recovered_address == Some(self.account)
+                    let msg = self.get_eth_signed_data()?;
+                    let recovered_address = eth_signature.signature_recover_signer(&msg)?;
+                    Ok(recovered_address == self.account)
                 }
                 ChangePubKeyEthAuthData::CREATE2(create2_data) => {
                     let create2_address = create2_data.get_address(&self.new_pk_hash);
-                    create2_address == self.account
+                    Ok(create2_address == self.account)
                 }
             }
         } else if let Some(old_eth_signature) = &self.eth_signature {
-            let recovered_address = self
-                .get_old_eth_signed_data()
-                .ok()
-                .and_then(|msg| old_eth_signature.signature_recover_signer(&msg).ok());
-            recovered_address == Some(self.account)
+            let msg = self.get_old_eth_signed_data()?;
+            let recovered_address = old_eth_signature.signature_recover_signer(&msg)?;
+            Ok(recovered_address == self.account)
         } else {
-            true
+            Ok(true)
         }
     }
 
@@ -2,7 +2,9 @@ use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 use crate::tx::{
-    change_pubkey, close, forced_exit, mint_nft, swap, transfer, withdraw, withdraw_nft,
+    change_pubkey, close, forced_exit, mint_nft,
+    primitives::packed_eth_signature::PackedETHSignatureError, swap, transfer, withdraw,
+    withdraw_nft,
 };
 #[derive(Debug, Error, PartialEq)]
 pub enum ChangePubkeySignedDataError {
@@ -10,6 +12,15 @@ pub enum ChangePubkeySignedDataError {
     SignedMessageLengthMismatch { actual: usize, expected: usize },
 }
 
+/// Error that occurred while verifying the Ethereum authorization data of the `ChangePubKey` transaction.
+#[derive(Debug, Error)]
+pub enum ChangePubKeyEthAuthError {
+    #[error(transparent)]
+    SignedData(#[from] ChangePubkeySignedDataError),
+    #[error("Failed to recover the signer: {0}")]
+    SignatureRecovery(#[from] PackedETHSignatureError),
+}
+
 #[derive(Error, Debug, PartialEq)]
 #[error("Close operations are disabled")]
 pub struct CloseOperationsDisabled();
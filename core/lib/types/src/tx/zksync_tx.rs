@@ -296,6 +296,21 @@ impl ZkSyncTx {
         }
     }
 
+    /// Returns `true` if the transaction type requires an accompanying Ethereum signature
+    /// (see `get_ethereum_sign_message`). Note that the signature may still be omitted
+    /// for the transactions sent from CREATE2 accounts or included into a batch.
+    pub fn is_eth_sign_data_required(&self) -> bool {
+        matches!(
+            self,
+            ZkSyncTx::Transfer(_)
+                | ZkSyncTx::Withdraw(_)
+                | ZkSyncTx::ForcedExit(_)
+                | ZkSyncTx::MintNFT(_)
+                | ZkSyncTx::Swap(_)
+                | ZkSyncTx::WithdrawNFT(_)
+        )
+    }
+
     /// Returns a message that user has to sign to send the transaction in the old format.
     /// If the transaction doesn't need a message signature, returns `None`.
     /// Needed for backwards compatibility.
@@ -2,6 +2,8 @@
 use std::collections::VecDeque;
 use std::string::ToString;
 // External deps
+use anyhow::{ensure, format_err};
+use num::{BigUint, Num};
 // Workspace deps
 
 /// Formats amount in wei to tokens with precision.
@@ -31,6 +33,54 @@ pub fn format_ether(wei: impl ToString) -> String {
     format_units(wei, 18)
 }
 
+/// Formats token amount in the smallest units to tokens with `decimals` precision.
+/// The amount is formatted exactly, without any rounding.
+pub fn format_token_amount(amount: &BigUint, decimals: u8) -> String {
+    format_units(amount, decimals)
+}
+
+/// Parses token amount (e.g. `"1.5"`) into the smallest token units.
+/// Behaves just like js ethers.utils.parseUnits: amounts that have more significant
+/// fractional digits than `decimals` are rejected rather than rounded.
+pub fn parse_token_amount(amount: &str, decimals: u8) -> Result<BigUint, anyhow::Error> {
+    let (integer, fraction) = match amount.split_once('.') {
+        Some((integer, fraction)) => (integer, fraction),
+        None => (amount, ""),
+    };
+    ensure!(
+        !integer.is_empty() || !fraction.is_empty(),
+        "Empty token amount"
+    );
+    ensure!(
+        integer
+            .chars()
+            .chain(fraction.chars())
+            .all(|c| c.is_ascii_digit()),
+        "Invalid token amount: {}",
+        amount
+    );
+
+    let fraction = fraction.trim_end_matches('0');
+    ensure!(
+        fraction.len() <= decimals as usize,
+        "Token amount {} has more than {} fractional digits",
+        amount,
+        decimals
+    );
+
+    let digits = format!(
+        "{}{}{}",
+        integer,
+        fraction,
+        "0".repeat(decimals as usize - fraction.len())
+    );
+    if digits.is_empty() {
+        return Ok(BigUint::from(0u32));
+    }
+    BigUint::from_str_radix(&digits, 10)
+        .map_err(|err| format_err!("Invalid token amount {}: {}", amount, err))
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -75,4 +125,40 @@ mod test {
             assert_eq!(format_units(&input, dec), output);
         }
     }
+
+    #[test]
+    fn test_token_amount() {
+        // USDC-like token.
+        let amount = BigUint::from(1_500_000u64);
+        assert_eq!(format_token_amount(&amount, 6), "1.5");
+        assert_eq!(parse_token_amount("1.5", 6).unwrap(), amount);
+        assert_eq!(parse_token_amount("0.000001", 6).unwrap(), 1u64.into());
+
+        // ETH-like token.
+        let amount = BigUint::from(1_000_000_000_000_000_001u64);
+        assert_eq!(format_token_amount(&amount, 18), "1.000000000000000001");
+        assert_eq!(
+            parse_token_amount("1.000000000000000001", 18).unwrap(),
+            amount
+        );
+        assert_eq!(
+            parse_token_amount("2", 18).unwrap(),
+            BigUint::from(2_000_000_000_000_000_000u64)
+        );
+
+        // Trailing zeros are not considered as a precision loss.
+        assert_eq!(
+            parse_token_amount("1.500000000", 6).unwrap(),
+            1_500_000u64.into()
+        );
+        // Amounts that can't be represented exactly are not rounded.
+        assert!(parse_token_amount("1.0000005", 6).is_err());
+        assert!(parse_token_amount("0.0000001", 6).is_err());
+
+        assert!(parse_token_amount("", 6).is_err());
+        assert!(parse_token_amount(".", 6).is_err());
+        assert!(parse_token_amount("1,5", 6).is_err());
+        assert!(parse_token_amount("-1", 6).is_err());
+        assert_eq!(parse_token_amount(".5", 6).unwrap(), 500_000u64.into());
+    }
 }
@@ -4,6 +4,9 @@
 //! They behave just like usual tracing::warn, tracing::info, etc.
 //! For warn and error macros we are adding file line and column to tracing variables
 //!
+//! Spans (`info_span` together with the `Instrument` trait) can be used to attach the common context
+//! to all the log lines emitted by a future.
+//!
 //! The format of the logs in stdout can be `plain` or` json` and is set by the `MISC_LOG_FORMAT` env variable.
 //!
 //! Full documentation for the `tracing` crate here https://docs.rs/tracing/
@@ -18,7 +21,7 @@ pub use sentry;
 use sentry::{types::Dsn, ClientInitGuard};
 
 pub use tracing as __tracing;
-pub use tracing::{debug, info, log, trace};
+pub use tracing::{debug, info, info_span, log, trace, Instrument};
 use tracing_appender::non_blocking::WorkerGuard;
 
 #[macro_export]
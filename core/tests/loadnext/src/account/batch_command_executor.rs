@@ -58,8 +58,9 @@ impl AccountLifespan {
                     .unwrap_or(IncorrectnessModifier::None)
             });
 
+        let txs = batch.iter().map(|(tx, _)| tx.clone()).collect();
         let provider = self.wallet.provider.clone();
-        self.submit(modifier, || async {
+        self.submit(modifier, txs, || async {
             self.wallet.provider.send_txs_batch(batch, None).await?;
             Ok(SyncTransactionHandle::new(main_hash, provider))
         })
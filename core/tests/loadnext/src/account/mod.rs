@@ -1,19 +1,26 @@
 use std::{
     future::Future,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc,
+    },
     time::{Duration, Instant},
 };
 
 use futures::{channel::mpsc::Sender, SinkExt};
+use vlog::Instrument;
 
 use zksync::{error::ClientError, operations::SyncTransactionHandle, RpcProvider, Wallet};
 use zksync_eth_signer::PrivateKeySigner;
-use zksync_types::{Token, H256};
+use zksync_types::{Token, ZkSyncTx, H256};
 
 use crate::{
     account_pool::{AddressPool, TestWallet},
+    balance_tracker::BalanceTracker,
     command::{Command, ExpectedOutcome, IncorrectnessModifier, TxCommand},
     config::LoadtestConfig,
     constants::{COMMIT_TIMEOUT, POLLING_INTERVAL},
+    pause::wait_while_paused,
     report::{Report, ReportBuilder, ReportLabel},
     rng::LoadtestRng,
 };
@@ -27,6 +34,9 @@ mod tx_command_executor;
 ///
 /// This structure is expected to not care about the server behavior; even if the server is down, it will only cause
 /// performed actions to be considered failed.
+///
+/// All the log lines of the account are emitted within the `account` span with the `phase` and `address` fields,
+/// and each executed command adds the `command` span with its sequential `index`.
 #[derive(Debug)]
 pub struct AccountLifespan {
     /// Wallet used to perform the test.
@@ -43,6 +53,12 @@ pub struct AccountLifespan {
     main_token: Token,
     /// Channel for sending reports about performed operations.
     report_sink: Sender<Report>,
+    /// Flag indicating that the loadtest is paused and no new commands should be executed.
+    paused: Arc<AtomicBool>,
+    /// Counter of the accounts performing the test at the moment.
+    active_accounts: Arc<AtomicUsize>,
+    /// Expected balances of the test accounts, updated once the operation is executed.
+    balance_tracker: BalanceTracker,
 }
 
 impl AccountLifespan {
@@ -51,6 +67,9 @@ impl AccountLifespan {
         addresses: AddressPool,
         test_account: TestWallet,
         report_sink: Sender<Report>,
+        paused: Arc<AtomicBool>,
+        active_accounts: Arc<AtomicUsize>,
+        balance_tracker: BalanceTracker,
     ) -> Self {
         let main_token = test_account
             .wallet
@@ -67,10 +86,24 @@ impl AccountLifespan {
             main_token,
 
             report_sink,
+            paused,
+            active_accounts,
+            balance_tracker,
         }
     }
 
     pub async fn run(mut self) {
+        self.active_accounts.fetch_add(1, Ordering::SeqCst);
+        let span = vlog::info_span!(
+            "account",
+            phase = "account_lifespan",
+            address = %self.wallet.address()
+        );
+        self.run_commands().instrument(span).await;
+        self.active_accounts.fetch_sub(1, Ordering::SeqCst);
+    }
+
+    async fn run_commands(&mut self) {
         // We assume that account is initialized after the transfer to it is executed,
         // thus we can start from obtaining the account ID.
         let retry_attempts = 3;
@@ -97,8 +130,15 @@ impl AccountLifespan {
         }
 
         let command_sequence = self.generate_commands();
-        for command in command_sequence {
-            self.execute_command(command).await;
+        let command_delay = self.config.command_delay();
+        for (index, command) in command_sequence.into_iter().enumerate() {
+            if index > 0 && command_delay > Duration::from_secs(0) {
+                tokio::time::sleep(command_delay).await;
+            }
+            wait_while_paused(&self.paused).await;
+            self.execute_command(command)
+                .instrument(vlog::info_span!("command", index))
+                .await;
         }
     }
 
@@ -170,6 +210,7 @@ impl AccountLifespan {
             .time(time)
             .retries(retries)
             .action(command)
+            .active_accounts(self.active_accounts.load(Ordering::SeqCst))
             .finish();
 
         if let Err(_err) = self.report_sink.send(report).await {
@@ -183,9 +224,12 @@ impl AccountLifespan {
     /// execution result.
     /// Once result is obtained, it's compared to the expected operation outcome in order to check whether
     /// command was completed as planned.
+    /// If the transactions were executed successfully (even if they were expected to fail), their changes
+    /// are applied to the expected balances.
     async fn submit<F, Fut>(
         &self,
         modifier: IncorrectnessModifier,
+        txs: Vec<ZkSyncTx>,
         send: F,
     ) -> Result<ReportLabel, ClientError>
     where
@@ -222,6 +266,12 @@ impl AccountLifespan {
             .wait_for_commit()
             .await?;
 
+        if transaction_receipt.fail_reason.is_none() {
+            for tx in &txs {
+                self.balance_tracker.apply_tx(tx);
+            }
+        }
+
         match expected_outcome {
             ExpectedOutcome::TxSucceed if transaction_receipt.fail_reason.is_none() => {
                 // Transaction succeed and it should have.
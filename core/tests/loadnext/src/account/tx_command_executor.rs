@@ -105,14 +105,13 @@ impl AccountLifespan {
 
         // Convert BigUint into U256. We won't ever use values above `u128::max_value()`, but just in case we'll ever
         // met such a value, we'll truncate it to the limit.
-        let amount = command
+        let amount: u128 = command
             .amount
             .clone()
             .try_into()
-            .unwrap_or_else(|_| u128::max_value())
-            .into();
+            .unwrap_or_else(|_| u128::max_value());
         let eth_tx_hash = match ethereum
-            .deposit(self.main_token.id, amount, self.wallet.address())
+            .deposit(self.main_token.id, amount.into(), self.wallet.address())
             .await
         {
             Ok(hash) => hash,
@@ -124,7 +123,16 @@ impl AccountLifespan {
             }
         };
 
-        self.handle_priority_op(eth_tx_hash).await
+        let label = self.handle_priority_op(eth_tx_hash).await?;
+        if matches!(label, ReportLabel::ActionDone) {
+            self.balance_tracker.credit(
+                self.wallet.address(),
+                self.main_token.id,
+                &BigUint::from(amount),
+            );
+        }
+
+        Ok(label)
     }
 
     async fn execute_full_exit(&self) -> Result<ReportLabel, ClientError> {
@@ -154,7 +162,15 @@ impl AccountLifespan {
             }
         };
 
-        self.handle_priority_op(eth_tx_hash).await
+        let label = self.handle_priority_op(eth_tx_hash).await?;
+        if matches!(label, ReportLabel::ActionDone) {
+            // Full exit withdraws the whole balance of the token, which may be changed concurrently
+            // by the transfers from other accounts, so its expected value is no longer known.
+            self.balance_tracker
+                .untrack(self.wallet.address(), exit_token_id);
+        }
+
+        Ok(label)
     }
 
     async fn handle_priority_op(&self, eth_tx_hash: H256) -> Result<ReportLabel, ClientError> {
@@ -187,7 +203,7 @@ impl AccountLifespan {
         let (tx, eth_signature) = self.build_change_pubkey(command, None).await?;
 
         let provider = self.wallet.provider.clone();
-        self.submit(command.modifier, || async {
+        self.submit(command.modifier, vec![tx.clone()], || async {
             let tx_hash = provider.send_tx(tx, eth_signature).await?;
             Ok(SyncTransactionHandle::new(tx_hash, provider))
         })
@@ -218,7 +234,7 @@ impl AccountLifespan {
         let (tx, eth_signature) = self.build_transfer(command, None).await?;
 
         let provider = self.wallet.provider.clone();
-        self.submit(command.modifier, || async {
+        self.submit(command.modifier, vec![tx.clone()], || async {
             let tx_hash = provider.send_tx(tx, eth_signature).await?;
             Ok(SyncTransactionHandle::new(tx_hash, provider))
         })
@@ -251,7 +267,7 @@ impl AccountLifespan {
         let (tx, eth_signature) = self.build_withdraw(command, None).await?;
 
         let provider = self.wallet.provider.clone();
-        self.submit(command.modifier, || async {
+        self.submit(command.modifier, vec![tx.clone()], || async {
             let tx_hash = provider.send_tx(tx, eth_signature).await?;
             Ok(SyncTransactionHandle::new(tx_hash, provider))
         })
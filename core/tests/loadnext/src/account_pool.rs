@@ -27,6 +27,11 @@ impl AddressPool {
         }
     }
 
+    /// Returns all the addresses stored in the pool.
+    pub fn addresses(&self) -> &[Address] {
+        &self.addresses
+    }
+
     /// Randomly chooses one of the addresses stored in the pool.
     pub fn random_address(&self, rng: &mut LoadtestRng) -> Address {
         let index = rng.gen_range(0..self.addresses.len());
@@ -73,6 +78,8 @@ pub struct TestWallet {
 pub struct AccountPool {
     /// Main wallet that will be used to initialize all the test wallets.
     pub master_wallet: Wallet<PrivateKeySigner, RpcProvider>,
+    /// Additional wallets initializing the test wallets together with the main one.
+    pub extra_master_wallets: Vec<Wallet<PrivateKeySigner, RpcProvider>>,
     /// Collection of test wallets and their Ethereum private keys.
     pub accounts: VecDeque<TestWallet>,
     /// Pool of addresses of the test accounts.
@@ -82,9 +89,10 @@ pub struct AccountPool {
 impl AccountPool {
     /// Generates all the required test accounts and prepares `Wallet` objects.
     pub async fn new(config: &LoadtestConfig) -> anyhow::Result<Self> {
-        let provider = RpcProvider::from_addr_and_network(
+        let provider = RpcProvider::with_retry_config(
             &config.zksync_rpc_addr,
             zksync::Network::from_str(&config.eth_network).expect("Invalid network name"),
+            config.rpc_retry_config(),
         );
 
         // Perform a health check: check whether zkSync server is alive.
@@ -102,18 +110,11 @@ impl AccountPool {
         let mut rng = LoadtestRng::new_generic(config.seed.clone());
         vlog::info!("Using RNG with master seed: {}", rng.seed_hex());
 
-        let master_wallet = {
-            let eth_pk = H256::from_str(&config.master_wallet_pk)
-                .expect("Can't parse master wallet private key");
-            let address = pk_to_address(&eth_pk);
-            let zksync_pk = private_key_from_seed(eth_pk.as_bytes())
-                .expect("Can't generate the zkSync private key");
-            let wallet_credentials =
-                WalletCredentials::<PrivateKeySigner>::from_pk(address, zksync_pk, Some(eth_pk));
-            Wallet::new(provider.clone(), wallet_credentials)
-                .await
-                .expect("Can't create a wallet")
-        };
+        let master_wallet = create_master_wallet(&provider, &config.master_wallet_pk).await;
+        let mut extra_master_wallets = Vec::new();
+        for master_wallet_pk in config.extra_master_wallet_pks() {
+            extra_master_wallets.push(create_master_wallet(&provider, master_wallet_pk).await);
+        }
 
         let mut accounts = VecDeque::with_capacity(config.accounts_amount);
         let mut addresses = Vec::with_capacity(config.accounts_amount);
@@ -143,10 +144,38 @@ impl AccountPool {
 
         Ok(Self {
             master_wallet,
+            extra_master_wallets,
             accounts,
             addresses: AddressPool::new(addresses),
         })
     }
+
+    /// Returns all the master wallets, starting from the main one.
+    pub fn master_wallets(&self) -> impl Iterator<Item = &Wallet<PrivateKeySigner, RpcProvider>> {
+        std::iter::once(&self.master_wallet).chain(self.extra_master_wallets.iter())
+    }
+
+    /// Returns mutable references to all the master wallets, starting from the main one.
+    pub fn master_wallets_mut(
+        &mut self,
+    ) -> impl Iterator<Item = &mut Wallet<PrivateKeySigner, RpcProvider>> {
+        std::iter::once(&mut self.master_wallet).chain(self.extra_master_wallets.iter_mut())
+    }
+}
+
+async fn create_master_wallet(
+    provider: &RpcProvider,
+    eth_pk_hex: &str,
+) -> Wallet<PrivateKeySigner, RpcProvider> {
+    let eth_pk = H256::from_str(eth_pk_hex).expect("Can't parse master wallet private key");
+    let address = pk_to_address(&eth_pk);
+    let zksync_pk =
+        private_key_from_seed(eth_pk.as_bytes()).expect("Can't generate the zkSync private key");
+    let wallet_credentials =
+        WalletCredentials::<PrivateKeySigner>::from_pk(address, zksync_pk, Some(eth_pk));
+    Wallet::new(provider.clone(), wallet_credentials)
+        .await
+        .expect("Can't create a wallet")
 }
 
 fn pk_to_address(eth_pk: &H256) -> Address {
@@ -0,0 +1,212 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex},
+};
+
+use num::{BigInt, BigUint};
+use zksync::{error::ClientError, provider::Provider, RpcProvider};
+use zksync_api_types::MAX_ACCOUNTS_INFO_ADDRESSES;
+use zksync_types::{Address, TokenId, ZkSyncTx};
+
+/// Mismatch between the expected and the actual balance of the account.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BalanceDrift {
+    pub address: Address,
+    pub token: TokenId,
+    pub expected: BigInt,
+    pub actual: BigUint,
+}
+
+/// Thread-safe tracker of the expected L2 balances of the accounts participating in the loadtest.
+///
+/// Balances are changed only by operations which are known to be executed successfully, and in the end
+/// of the test they are reconciled with the balances reported by the server. This way the loadtest
+/// is able to catch balance bugs which only surface under high load.
+///
+/// Balances of all the tracked accounts are expected to be changed only by the loadtest.
+#[derive(Debug, Clone, Default)]
+pub struct BalanceTracker {
+    balances: Arc<Mutex<HashMap<(Address, TokenId), BigInt>>>,
+    /// Balances excluded from the reconciliation, since their expected value cannot be determined reliably.
+    untracked: Arc<Mutex<HashSet<(Address, TokenId)>>>,
+}
+
+impl BalanceTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Loads the current committed balances of the accounts, so tracking starts from the actual state.
+    /// Required if accounts could have been used before, e.g. if the loadtest is launched with the same seed.
+    pub async fn load_balances(
+        &self,
+        provider: &RpcProvider,
+        addresses: impl IntoIterator<Item = Address>,
+    ) -> Result<(), ClientError> {
+        let tokens = provider.tokens().await?;
+        let addresses: Vec<_> = addresses.into_iter().collect();
+        for chunk in addresses.chunks(MAX_ACCOUNTS_INFO_ADDRESSES) {
+            let accounts_info = provider.accounts_info(chunk.to_vec()).await?;
+            for (address, account_info) in chunk.iter().zip(accounts_info) {
+                for (symbol, balance) in account_info.committed.balances {
+                    if let Some(token) = tokens.get(&symbol) {
+                        self.credit(*address, token.id, &balance.0);
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the expected balance of the account.
+    pub fn balance(&self, address: Address, token: TokenId) -> BigInt {
+        let balances = self.balances.lock().unwrap();
+        balances.get(&(address, token)).cloned().unwrap_or_default()
+    }
+
+    /// Increases the expected balance of the account.
+    pub fn credit(&self, address: Address, token: TokenId, amount: &BigUint) {
+        let mut balances = self.balances.lock().unwrap();
+        *balances.entry((address, token)).or_default() += BigInt::from(amount.clone());
+    }
+
+    /// Decreases the expected balance of the account.
+    pub fn debit(&self, address: Address, token: TokenId, amount: &BigUint) {
+        let mut balances = self.balances.lock().unwrap();
+        *balances.entry((address, token)).or_default() -= BigInt::from(amount.clone());
+    }
+
+    /// Excludes the balance of the account from the reconciliation, e.g. after the `FullExit` operation.
+    ///
+    /// Changes are applied in the order the accounts observe their operations committed rather than in the order
+    /// of execution. `credit` and `debit` commute with each other, but resetting the balance doesn't, so a transfer
+    /// to the account committed around its `FullExit` would be reported as a drift.
+    pub fn untrack(&self, address: Address, token: TokenId) {
+        self.untracked.lock().unwrap().insert((address, token));
+    }
+
+    /// Applies the changes made by the successfully executed transaction.
+    /// Transactions which don't change balances of the tracked tokens are ignored.
+    pub fn apply_tx(&self, tx: &ZkSyncTx) {
+        match tx {
+            ZkSyncTx::Transfer(transfer) => {
+                self.debit(transfer.from, transfer.token, &transfer.amount);
+                self.debit(transfer.from, transfer.token, &transfer.fee);
+                self.credit(transfer.to, transfer.token, &transfer.amount);
+            }
+            ZkSyncTx::Withdraw(withdraw) => {
+                self.debit(withdraw.from, withdraw.token, &withdraw.amount);
+                self.debit(withdraw.from, withdraw.token, &withdraw.fee);
+            }
+            ZkSyncTx::ChangePubKey(change_pubkey) => {
+                self.debit(
+                    change_pubkey.account,
+                    change_pubkey.fee_token,
+                    &change_pubkey.fee,
+                );
+            }
+            _ => {}
+        }
+    }
+
+    /// Compares the expected balances with the committed ones reported by the server.
+    /// Returns the list of found mismatches.
+    pub async fn reconcile(
+        &self,
+        provider: &RpcProvider,
+    ) -> Result<Vec<BalanceDrift>, ClientError> {
+        let tokens = provider.tokens().await?;
+        let untracked = self.untracked.lock().unwrap().clone();
+        let mut expected_balances = self.balances.lock().unwrap().clone();
+        expected_balances.retain(|key, _| !untracked.contains(key));
+
+        let mut addresses: Vec<_> = expected_balances
+            .keys()
+            .map(|(address, _)| *address)
+            .collect();
+        addresses.sort();
+        addresses.dedup();
+
+        let mut accounts_info = Vec::with_capacity(addresses.len());
+        for chunk in addresses.chunks(MAX_ACCOUNTS_INFO_ADDRESSES) {
+            accounts_info.extend(provider.accounts_info(chunk.to_vec()).await?);
+        }
+
+        let mut drifts = Vec::new();
+        for (address, account_info) in addresses.into_iter().zip(accounts_info) {
+            let actual_balances: HashMap<_, _> = account_info
+                .committed
+                .balances
+                .into_iter()
+                .filter_map(|(symbol, balance)| {
+                    tokens.get(&symbol).map(|token| (token.id, balance.0))
+                })
+                .collect();
+
+            for ((_, token), expected) in expected_balances
+                .iter()
+                .filter(|((account, _), _)| *account == address)
+            {
+                let actual = actual_balances.get(token).cloned().unwrap_or_default();
+                if BigInt::from(actual.clone()) != *expected {
+                    drifts.push(BalanceDrift {
+                        address,
+                        token: *token,
+                        expected: expected.clone(),
+                        actual,
+                    });
+                }
+            }
+        }
+
+        Ok(drifts)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_test_account::ZkSyncAccount;
+    use zksync_types::{AccountId, Nonce};
+
+    use super::*;
+
+    #[test]
+    fn apply_tx() {
+        let account = ZkSyncAccount::rand();
+        account.set_account_id(Some(AccountId(1)));
+        let recipient = Address::repeat_byte(0x11);
+        let token = TokenId(1);
+
+        let tracker = BalanceTracker::new();
+        tracker.credit(account.address, token, &BigUint::from(100u32));
+
+        let (transfer, _) = account.sign_transfer(
+            token,
+            "DAI",
+            BigUint::from(30u32),
+            BigUint::from(5u32),
+            &recipient,
+            Some(Nonce(0)),
+            false,
+            Default::default(),
+        );
+        tracker.apply_tx(&ZkSyncTx::from(transfer));
+
+        let (withdraw, _) = account.sign_withdraw(
+            token,
+            "DAI",
+            BigUint::from(10u32),
+            BigUint::from(5u32),
+            &recipient,
+            Some(Nonce(1)),
+            false,
+            Default::default(),
+        );
+        tracker.apply_tx(&ZkSyncTx::from(withdraw));
+
+        let balances = tracker.balances.lock().unwrap();
+        assert_eq!(balances[&(account.address, token)], BigInt::from(50));
+        assert_eq!(balances[&(recipient, token)], BigInt::from(30));
+    }
+}
@@ -1,4 +1,12 @@
+use std::time::Duration;
+
 use serde::Deserialize;
+use zksync::provider::RetryConfig;
+
+use crate::constants::{
+    DEFAULT_BALANCE_CHECK_INTERVAL, DEFAULT_BALANCE_MONITOR_INTERVAL,
+    DEFAULT_COMMIT_TIMEOUT_RETRIES, DEFAULT_RAMP_INTERVAL,
+};
 
 /// Configuration for the loadtest.
 ///
@@ -19,6 +27,10 @@ pub struct LoadtestConfig {
 
     /// Ethereum private key of the wallet that has funds to perform a test.
     pub master_wallet_pk: String,
+    /// Optional comma-separated list of Ethereum private keys of the additional master wallets.
+    /// Test accounts are sharded among all the master wallets, so they can fund accounts in parallel
+    /// without conflicting on nonces.
+    pub extra_master_wallet_pks: Option<String>,
 
     /// Amount of accounts to be used in test.
     /// This option configures the "width" of the test:
@@ -45,12 +57,111 @@ pub struct LoadtestConfig {
     pub seed: Option<String>,
     /// Allowed percent of failed transactions
     pub allowed_percent: u8,
+
+    /// Optional amount of accounts activated at once.
+    /// If set, the accounts are not activated all at once, but are funded and launched by steps
+    /// of the provided size, modeling the growth of the load over time.
+    pub ramp_step_accounts: Option<usize>,
+    /// Interval between the account ramp-up steps in seconds.
+    /// Only makes sense if `ramp_step_accounts` is set.
+    pub ramp_interval_secs: Option<u64>,
+
+    /// Amount of times the master account keeps waiting for its transaction after the commit timeout
+    /// is reached, before considering the test failed. Useful for nodes with occasional block delays.
+    pub commit_timeout_retries: Option<usize>,
+
+    /// Optional delay in milliseconds between the commands of a single account, modeling the user think-time.
+    /// Unlike limiting the throughput, it results in bursty-then-idle load patterns.
+    pub command_delay_ms: Option<u64>,
+
+    /// Optional interval (in sent batches) between the checks whether the master account has enough funds
+    /// to distribute among the rest of test accounts.
+    pub balance_check_interval: Option<usize>,
+    /// Optional interval in seconds between the checks whether the test accounts still have enough funds
+    /// to complete their operations, performed while the accounts are running.
+    pub balance_monitor_interval_secs: Option<u64>,
+
+    /// Optional maximum amount of retries of a single zkSync RPC request failed with a recoverable error
+    /// (e.g. a network error). If not set, retries are only limited by the backoff delay.
+    pub rpc_max_retries: Option<usize>,
+    /// Optional maximum backoff delay in milliseconds between the zkSync RPC request retries.
+    pub rpc_retry_max_delay_ms: Option<u64>,
 }
 
 impl LoadtestConfig {
     pub fn from_env() -> envy::Result<Self> {
         envy::from_env()
     }
+
+    /// Returns the private keys of the additional master wallets.
+    pub fn extra_master_wallet_pks(&self) -> Vec<&str> {
+        self.extra_master_wallet_pks
+            .as_deref()
+            .map(|pks| {
+                pks.split(',')
+                    .map(str::trim)
+                    .filter(|pk| !pk.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Returns the amount of accounts activated on each ramp-up step.
+    /// If the ramp-up is not configured, all the accounts are activated at once.
+    /// The step is never zero, since it is used to split the accounts into batches and ramp-up steps.
+    pub fn ramp_step_accounts(&self) -> usize {
+        self.ramp_step_accounts
+            .filter(|step| *step > 0)
+            .unwrap_or(self.accounts_amount)
+            .max(1)
+    }
+
+    /// Returns the amount of retries of waiting for the master account transaction commit.
+    pub fn commit_timeout_retries(&self) -> usize {
+        self.commit_timeout_retries
+            .unwrap_or(DEFAULT_COMMIT_TIMEOUT_RETRIES)
+    }
+
+    /// Returns the amount of batches sent between the master account balance checks.
+    pub fn balance_check_interval(&self) -> usize {
+        self.balance_check_interval
+            .filter(|interval| *interval > 0)
+            .unwrap_or(DEFAULT_BALANCE_CHECK_INTERVAL)
+    }
+
+    /// Returns the interval between the checks of the test accounts balances.
+    pub fn balance_monitor_interval(&self) -> Duration {
+        self.balance_monitor_interval_secs
+            .filter(|interval| *interval > 0)
+            .map(Duration::from_secs)
+            .unwrap_or(DEFAULT_BALANCE_MONITOR_INTERVAL)
+    }
+
+    /// Returns the delay between the commands of a single account.
+    pub fn command_delay(&self) -> Duration {
+        Duration::from_millis(self.command_delay_ms.unwrap_or_default())
+    }
+
+    /// Returns the retry policy of the zkSync RPC requests.
+    /// Options that are not set are taken from the `RetryConfig` defaults.
+    pub fn rpc_retry_config(&self) -> RetryConfig {
+        let default = RetryConfig::default();
+        RetryConfig {
+            max_delay: self
+                .rpc_retry_max_delay_ms
+                .map(Duration::from_millis)
+                .unwrap_or(default.max_delay),
+            max_retries: self.rpc_max_retries.or(default.max_retries),
+            ..default
+        }
+    }
+
+    /// Returns the interval between the account ramp-up steps.
+    pub fn ramp_interval(&self) -> Duration {
+        self.ramp_interval_secs
+            .map(Duration::from_secs)
+            .unwrap_or(DEFAULT_RAMP_INTERVAL)
+    }
 }
 
 impl Default for LoadtestConfig {
@@ -64,11 +175,20 @@ impl Default for LoadtestConfig {
             eth_network: "localhost".into(),
             master_wallet_pk: "74d8b3a188f7260f67698eb44da07397a298df5427df681ef68c45b34b61f998"
                 .into(),
+            extra_master_wallet_pks: None,
             accounts_amount: 80,
             operations_per_account: 40,
             main_token: "DAI".into(),
             seed: None,
             allowed_percent: 10,
+            ramp_step_accounts: None,
+            ramp_interval_secs: None,
+            commit_timeout_retries: None,
+            command_delay_ms: None,
+            balance_check_interval: None,
+            balance_monitor_interval_secs: None,
+            rpc_max_retries: None,
+            rpc_retry_max_delay_ms: None,
         }
     }
 }
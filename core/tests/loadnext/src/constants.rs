@@ -10,6 +10,26 @@ pub const COMMIT_TIMEOUT: Duration = Duration::from_secs(600);
 /// We don't want to overload the server with too many requests; given the fact that blocks are expected to be created
 /// every couple of seconds, chosen value seems to be adequate to provide the result in one or two calls at average.
 pub const POLLING_INTERVAL: Duration = Duration::from_secs(3);
+/// Default interval between the account ramp-up steps, used if `ramp_interval_secs` is not set.
+pub const DEFAULT_RAMP_INTERVAL: Duration = Duration::from_secs(60);
+/// Default amount of times the master account re-polls the transaction after reaching `COMMIT_TIMEOUT`,
+/// used if `commit_timeout_retries` is not set.
+pub const DEFAULT_COMMIT_TIMEOUT_RETRIES: usize = 0;
+/// Default interval (in sent batches) between the checks of the master account balance,
+/// used if `balance_check_interval` is not set.
+pub const DEFAULT_BALANCE_CHECK_INTERVAL: usize = 1;
+/// Default interval between the checks whether the test accounts have enough funds to complete their operations,
+/// used if `balance_monitor_interval_secs` is not set.
+pub const DEFAULT_BALANCE_MONITOR_INTERVAL: Duration = Duration::from_secs(60);
+/// Maximum amount of master accounts checking (and topping up) their balances concurrently.
+pub const MAX_CONCURRENT_BALANCE_CHECKS: usize = 4;
+/// Upper bound of gas consumed by a single L1 transaction of the master account setup:
+/// ERC-20 mint, deposit approval or the deposit itself. Used by the `--estimate` mode.
+pub const MAX_L1_SETUP_TX_GAS: u64 = 200_000;
+/// Gas consumed by a plain ETH transfer to a test account. Used by the `--estimate` mode.
+pub const ETH_TRANSFER_GAS: u64 = 21_000;
+/// How often paused executor and accounts check whether the loadtest was resumed.
+pub const PAUSE_CHECK_INTERVAL: Duration = Duration::from_secs(1);
 
 // TODO (ZKS-623): This value is not the greatest batch size zkSync supports.
 // However, choosing the bigger value (e.g. 40) causes server to fail with error "Error communicating core server".
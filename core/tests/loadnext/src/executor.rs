@@ -1,18 +1,95 @@
-use futures::{channel::mpsc, future::join_all};
+use std::{
+    future::Future,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use futures::{
+    channel::mpsc,
+    future::{join_all, try_join_all},
+    stream, StreamExt, TryStreamExt,
+};
+use num::{BigInt, BigUint};
 
 use tokio::task::JoinHandle;
+use vlog::Instrument;
 use zksync::{
-    error::ClientError, ethereum::PriorityOpHolder, operations::SyncTransactionHandle,
-    provider::Provider, types::TransactionInfo,
+    error::ClientError,
+    ethereum::PriorityOpHolder,
+    operations::SyncTransactionHandle,
+    provider::Provider,
+    types::{BlockStatus, TransactionInfo},
+    RpcProvider, Wallet,
 };
-use zksync_types::{tx::TxHash, TransactionReceipt, TxFeeTypes, U256};
+use zksync_eth_signer::PrivateKeySigner;
+use zksync_types::{tx::TxHash, Address, TransactionReceipt, TxFeeTypes, U256};
 
 use crate::{
-    account::AccountLifespan, account_pool::AccountPool, config::LoadtestConfig,
+    account::AccountLifespan,
+    account_pool::{AccountPool, TestWallet},
+    balance_tracker::BalanceTracker,
+    config::LoadtestConfig,
+    pause::wait_while_paused,
     report_collector::LoadtestResult,
 };
 use crate::{constants::*, report_collector::ReportCollector};
 
+/// Requirements of the loadtest calculated without sending any transactions, see `Executor::estimate`.
+#[derive(Debug, Clone)]
+pub struct LoadtestEstimation {
+    /// Amount of the master accounts funding the test accounts.
+    pub master_accounts: usize,
+    /// Amount of the main token to be held by each master account in L1 (it will be minted if it's not enough).
+    pub main_token_deposit: u128,
+    /// Approximate amount of ETH (in wei) the master accounts must hold in L1 in total: it includes the funds
+    /// distributed among the test accounts and the gas for the master accounts transactions.
+    pub eth_required: U256,
+    /// Total fee (in the main token) for the batches distributing funds among the test accounts in L2.
+    pub initial_transfers_fee: BigUint,
+    /// Amount of the Ethereum transactions sent by the master accounts.
+    pub l1_txs: usize,
+    /// Amount of the zkSync transactions sent by the master accounts.
+    pub l2_setup_txs: usize,
+    /// Amount of the operations (transactions and batches) performed by the test accounts.
+    pub account_operations: usize,
+    /// Lower bound of the test duration, caused by the accounts ramp-up.
+    pub min_duration: Duration,
+    /// Upper bound of the test duration, assuming that every operation waits for the commit timeout.
+    pub max_duration: Duration,
+}
+
+impl LoadtestEstimation {
+    pub fn report(&self) {
+        vlog::info!(
+            "Main token to be deposited to each of {} master accounts: {}",
+            self.master_accounts,
+            self.main_token_deposit
+        );
+        vlog::info!(
+            "Fee for the initial funds distribution: {}",
+            self.initial_transfers_fee
+        );
+        vlog::info!(
+            "ETH required on the master accounts: {} wei",
+            self.eth_required
+        );
+        vlog::info!(
+            "Transactions to be sent: {} Ethereum txs and {} zkSync txs by the master accounts, {} operations by test accounts",
+            self.l1_txs,
+            self.l2_setup_txs,
+            self.account_operations
+        );
+        vlog::info!(
+            "Estimated duration: from {:?} to {:?}",
+            self.min_duration,
+            self.max_duration
+        );
+    }
+}
+
 /// Executor is the entity capable of running the loadtest flow.
 ///
 /// It takes care of the following topics:
@@ -24,10 +101,18 @@ use crate::{constants::*, report_collector::ReportCollector};
 /// - Spawning account lifespan futures.
 /// - Awaiting for all the account futures to complete.
 /// - Getting the final test resolution from the report collector.
+///
+/// Each of the listed phases is executed within the `loadtest` span with the `phase` field, so log lines can be
+/// correlated with the phase they were emitted in. Log lines of the initial funds distribution also carry the `batch`
+/// field, and the account lifespans have their own `account` span (see `AccountLifespan`).
+///
+/// Sending of new transactions can be suspended at any moment via the flag returned by `Executor::pause_flag`.
 #[derive(Debug)]
 pub struct Executor {
     config: LoadtestConfig,
     pool: AccountPool,
+    paused: Arc<AtomicBool>,
+    balance_tracker: BalanceTracker,
 }
 
 impl Executor {
@@ -35,7 +120,18 @@ impl Executor {
     pub async fn new(config: LoadtestConfig) -> anyhow::Result<Self> {
         let pool = AccountPool::new(&config).await?;
 
-        Ok(Self { config, pool })
+        Ok(Self {
+            config,
+            pool,
+            paused: Arc::new(AtomicBool::new(false)),
+            balance_tracker: BalanceTracker::new(),
+        })
+    }
+
+    /// Returns the flag controlling the pause of the loadtest.
+    /// While the flag is set, neither the executor nor the test accounts send new transactions.
+    pub fn pause_flag(&self) -> Arc<AtomicBool> {
+        self.paused.clone()
     }
 
     /// Runs the loadtest until the completion.
@@ -47,25 +143,134 @@ impl Executor {
         })
     }
 
+    /// Calculates the requirements of the loadtest without sending any transactions.
+    /// The server and the Ethereum node are only queried for the fees and the gas price.
+    pub async fn estimate(&self) -> anyhow::Result<LoadtestEstimation> {
+        let config = &self.config;
+        let master_wallet = &self.pool.master_wallet;
+        let accounts_amount = config.accounts_amount;
+        let master_accounts = self.pool.master_wallets().count();
+
+        let ethereum = master_wallet.ethereum(&config.web3_url).await?;
+        let gas_price = ethereum.client().get_gas_price().await?;
+        let eth_per_account = self.eth_amount_to_distribute().await?;
+        // Each master account mints, approves and deposits the main token and sends ETH to its test accounts.
+        let master_gas = 3 * MAX_L1_SETUP_TX_GAS * master_accounts as u64
+            + ETH_TRANSFER_GAS * accounts_amount as u64;
+        let eth_required = eth_per_account * accounts_amount + gas_price * master_gas;
+
+        // Funds are distributed in the same chunks as `send_initial_transfers` does it.
+        let batch_size = config.ramp_step_accounts().min(MAX_BATCH_SIZE);
+        let addresses: Vec<_> = self
+            .pool
+            .accounts
+            .iter()
+            .map(|account| account.wallet.address())
+            .collect();
+        let mut initial_transfers_fee = BigUint::from(0u64);
+        for chunk in addresses.chunks(batch_size) {
+            let mut batch_addresses = chunk.to_vec();
+            batch_addresses.push(master_wallet.address());
+            let batch_fee = master_wallet
+                .provider
+                .get_txs_batch_fee(
+                    vec![TxFeeTypes::Transfer; batch_addresses.len()],
+                    batch_addresses,
+                    config.main_token.as_str(),
+                )
+                .await?;
+            initial_transfers_fee += batch_fee;
+        }
+        let batches = addresses.chunks(batch_size).len();
+
+        let ramp_steps =
+            (accounts_amount + config.ramp_step_accounts() - 1) / config.ramp_step_accounts();
+        let min_duration = config.ramp_interval() * ramp_steps.saturating_sub(1) as u32;
+        // Master accounts send their batches in parallel, but may wait for each batch several times,
+        // see `wait_for_sync_tx`.
+        let sequential_operations = ceil_div(batches, master_accounts)
+            * (config.commit_timeout_retries() + 1)
+            + config.operations_per_account;
+        // Each account sleeps between its commands, the first one is executed right away.
+        let think_time = config.command_delay() * config.operations_per_account as u32;
+        let min_duration = min_duration + think_time;
+        let max_duration = min_duration + COMMIT_TIMEOUT * sequential_operations as u32;
+
+        Ok(LoadtestEstimation {
+            master_accounts,
+            main_token_deposit: self.amount_to_deposit(),
+            eth_required,
+            initial_transfers_fee,
+            // Mint, approve and deposit for each master account and ETH transfer to each test account.
+            l1_txs: 3 * master_accounts + accounts_amount,
+            // `ChangePubKey` for each master account and one transfer per test account plus a fee transfer for each batch.
+            l2_setup_txs: master_accounts + accounts_amount + batches,
+            account_operations: accounts_amount * config.operations_per_account,
+            min_duration,
+            max_duration,
+        })
+    }
+
     /// Inner representation of `start` function which returns a `Result`, so it can conveniently use `?`.
     async fn start_inner(&mut self) -> anyhow::Result<LoadtestResult> {
         vlog::info!("Initializing accounts");
-        self.check_onchain_balance().await?;
-        self.mint().await?;
-        self.deposit_to_master().await?;
-        self.set_signing_key().await?;
-        let (executor_future, account_futures) = self.send_initial_transfers().await?;
-        self.wait_account_routines(account_futures).await;
+        // Master wallets are independent from each other, so they can be initialized in parallel.
+        self.for_each_master_wallet(Self::check_onchain_balance)
+            .instrument(vlog::info_span!("loadtest", phase = "initialize"))
+            .await?;
+        self.for_each_master_wallet(Self::mint)
+            .instrument(vlog::info_span!("loadtest", phase = "initialize"))
+            .await?;
+        self.for_each_master_wallet(Self::deposit_to_master)
+            .instrument(vlog::info_span!("loadtest", phase = "deposit"))
+            .await?;
+        self.update_master_account_ids()
+            .instrument(vlog::info_span!("loadtest", phase = "deposit"))
+            .await?;
+        self.for_each_master_wallet(Self::set_signing_key)
+            .instrument(vlog::info_span!("loadtest", phase = "change_pubkey"))
+            .await?;
+        let (executor_future, account_futures) = self
+            .send_initial_transfers()
+            .instrument(vlog::info_span!("loadtest", phase = "initial_transfer"))
+            .await?;
+        self.wait_account_routines(account_futures)
+            .instrument(vlog::info_span!("loadtest", phase = "account_routines"))
+            .await?;
+
+        let mut final_resultion = executor_future.await.unwrap_or(LoadtestResult::TestFailed);
 
-        let final_resultion = executor_future.await.unwrap_or(LoadtestResult::TestFailed);
+        if !self.reconcile_balances().await? {
+            final_resultion = LoadtestResult::TestFailed;
+        }
 
         Ok(final_resultion)
     }
 
+    /// Runs the routine for each of the master wallets concurrently.
+    /// Routines are executed within the `master` span with the `address` field.
+    async fn for_each_master_wallet<'a, F, Fut>(&'a self, routine: F) -> anyhow::Result<()>
+    where
+        F: Fn(&'a Self, &'a Wallet<PrivateKeySigner, RpcProvider>) -> Fut,
+        Fut: Future<Output = anyhow::Result<()>>,
+    {
+        try_join_all(self.pool.master_wallets().map(|wallet| {
+            routine(self, wallet).instrument(vlog::info_span!(
+                "master",
+                address = %wallet.address()
+            ))
+        }))
+        .await?;
+
+        Ok(())
+    }
+
     /// Verifies that onchain ETH balance for the main account is sufficient to run the loadtest.
-    async fn check_onchain_balance(&mut self) -> anyhow::Result<()> {
+    async fn check_onchain_balance(
+        &self,
+        master_wallet: &Wallet<PrivateKeySigner, RpcProvider>,
+    ) -> anyhow::Result<()> {
         vlog::info!("Master Account: Checking onchain balance...");
-        let master_wallet = &mut self.pool.master_wallet;
         let ethereum = master_wallet.ethereum(&self.config.web3_url).await?;
 
         let eth_balance = ethereum.balance().await?;
@@ -81,11 +286,13 @@ impl Executor {
     }
 
     /// Mints the ERC-20 token on the main wallet.
-    async fn mint(&mut self) -> anyhow::Result<()> {
+    async fn mint(
+        &self,
+        master_wallet: &Wallet<PrivateKeySigner, RpcProvider>,
+    ) -> anyhow::Result<()> {
         vlog::info!("Master Account: Minting ERC20 token...");
         let deposit_amount = self.amount_to_deposit();
 
-        let master_wallet = &self.pool.master_wallet;
         let mut ethereum = master_wallet.ethereum(&self.config.web3_url).await?;
         ethereum.set_confirmation_timeout(ETH_CONFIRMATION_TIMEOUT);
 
@@ -119,14 +326,13 @@ impl Executor {
     }
 
     /// Deposits the ERC-20 token to main wallet in L2.
-    async fn deposit_to_master(&mut self) -> anyhow::Result<()> {
+    async fn deposit_to_master(
+        &self,
+        master_wallet: &Wallet<PrivateKeySigner, RpcProvider>,
+    ) -> anyhow::Result<()> {
         vlog::info!("Master Account: Performing a deposit to master");
         let deposit_amount = self.amount_to_deposit();
-        let mut ethereum = self
-            .pool
-            .master_wallet
-            .ethereum(&self.config.web3_url)
-            .await?;
+        let mut ethereum = master_wallet.ethereum(&self.config.web3_url).await?;
         ethereum.set_confirmation_timeout(ETH_CONFIRMATION_TIMEOUT);
 
         // Approve ERC20 deposits.
@@ -142,7 +348,7 @@ impl Executor {
             .deposit(
                 main_token,
                 U256::from(deposit_amount),
-                self.pool.master_wallet.address(),
+                master_wallet.address(),
             )
             .await?;
 
@@ -150,7 +356,7 @@ impl Executor {
         let receipt = ethereum.wait_for_tx(deposit_tx_hash).await?;
         self.assert_eth_tx_success(&receipt).await;
         let mut priority_op_handle = receipt
-            .priority_op_handle(self.pool.master_wallet.provider.clone())
+            .priority_op_handle(master_wallet.provider.clone())
             .unwrap_or_else(|| {
                 panic!(
                     "Can't get the handle for the deposit operation: {:?}",
@@ -166,34 +372,37 @@ impl Executor {
             .wait_for_commit()
             .await?;
 
-        // After deposit is committed, we have to update the account ID in the wallet
-        // (in case we didn't have one).
-        self.pool.master_wallet.update_account_id().await?;
-        assert!(
-            self.pool.master_wallet.account_id().is_some(),
-            "Account ID for master account was not set",
-        );
-
         vlog::info!("Master Account: Deposit is OK");
         Ok(())
     }
 
+    /// Updates the account IDs of the master wallets after the deposits are committed
+    /// (in case they didn't have one).
+    async fn update_master_account_ids(&mut self) -> anyhow::Result<()> {
+        for master_wallet in self.pool.master_wallets_mut() {
+            master_wallet.update_account_id().await?;
+            assert!(
+                master_wallet.account_id().is_some(),
+                "Account ID for master account was not set",
+            );
+        }
+
+        Ok(())
+    }
+
     /// Invokes `ChangePubKey` for the main wallet in L2.
-    async fn set_signing_key(&mut self) -> anyhow::Result<()> {
+    async fn set_signing_key(
+        &self,
+        master_wallet: &Wallet<PrivateKeySigner, RpcProvider>,
+    ) -> anyhow::Result<()> {
         vlog::info!("Master Account: Setting the signing key");
-        let mut handle = self
-            .pool
-            .master_wallet
+        let handle = master_wallet
             .start_change_pubkey()
             .fee_token(self.config.main_token.as_str())
             .unwrap()
             .send()
             .await?;
-        handle.polling_interval(POLLING_INTERVAL).unwrap();
-        let result = handle
-            .commit_timeout(COMMIT_TIMEOUT)
-            .wait_for_commit()
-            .await?;
+        let result = self.wait_for_sync_tx(handle.hash()).await?;
 
         assert!(
             result.fail_reason.is_none(),
@@ -206,10 +415,10 @@ impl Executor {
 
     async fn send_initial_transfers_batch(
         &self,
-        accounts_to_process: usize,
+        master_wallet: &Wallet<PrivateKeySigner, RpcProvider>,
+        accounts: &[TestWallet],
     ) -> anyhow::Result<TxHash> {
         let eth_to_distribute = self.eth_amount_to_distribute().await?;
-        let master_wallet = &self.pool.master_wallet;
         let config = &self.config;
         let token = &config.main_token;
 
@@ -224,12 +433,12 @@ impl Executor {
         let mut nonce = master_wallet.account_info().await?.committed.nonce;
 
         // 1 tx per account + 1 fee tx.
-        let batch_txs_amount = accounts_to_process + 1;
+        let batch_txs_amount = accounts.len() + 1;
         let mut batch = Vec::with_capacity(batch_txs_amount);
         let mut batch_fee_types = Vec::with_capacity(batch_txs_amount);
         let mut batch_addresses = Vec::with_capacity(batch_txs_amount);
 
-        for account in self.pool.accounts.iter().take(accounts_to_process) {
+        for account in accounts {
             let target_address = account.wallet.address();
 
             // Prior to sending funds in L2, we will send funds in L1 for accounts
@@ -290,6 +499,198 @@ impl Executor {
         Ok(batch_tx_hash)
     }
 
+    /// Checks that the balances of the test accounts match the expected ones.
+    /// Returns `false` if any mismatches were found.
+    async fn reconcile_balances(&self) -> anyhow::Result<bool> {
+        vlog::info!("Reconciling the balances of the test accounts");
+        let drifts = self
+            .balance_tracker
+            .reconcile(&self.pool.master_wallet.provider)
+            .await?;
+
+        for drift in &drifts {
+            vlog::error!(
+                "Balance drift for account {:?} and token {}: expected {}, actual {}",
+                drift.address,
+                drift.token,
+                drift.expected,
+                drift.actual
+            );
+        }
+        if drifts.is_empty() {
+            vlog::info!("Balances of the test accounts match the expected ones");
+        }
+
+        Ok(drifts.is_empty())
+    }
+
+    /// Checks whether the master wallets have enough of the main token in L2 to fund the provided recipients
+    /// and pay the fees for all the batches required for that. Recipients are expected to be distributed
+    /// evenly among the master wallets.
+    async fn check_master_balances(
+        &self,
+        master_wallets: &[&Wallet<PrivateKeySigner, RpcProvider>],
+        recipients: &[Address],
+    ) -> anyhow::Result<()> {
+        if recipients.is_empty() {
+            return Ok(());
+        }
+
+        // The fee is requested once per check: none of the batches is larger than the first one,
+        // so its fee is used as an upper bound for the rest of them.
+        // Each batch consists of transfers to the new accounts and the transfer paying the fee for the batch.
+        let first_batch = &recipients[..recipients.len().min(MAX_BATCH_SIZE)];
+        let mut fee_types = vec![TxFeeTypes::TransferToNew; first_batch.len()];
+        fee_types.push(TxFeeTypes::Transfer);
+        let mut addresses = first_batch.to_vec();
+        addresses.push(master_wallets[0].address());
+        let batch_fee = master_wallets[0]
+            .provider
+            .get_txs_batch_fee(fee_types, addresses, self.config.main_token.as_str())
+            .await?;
+
+        let transfer_amount = BigUint::from(self.transfer_amount());
+        let recipients_per_master = ceil_div(recipients.len(), master_wallets.len());
+        stream::iter(recipients.chunks(recipients_per_master).zip(master_wallets))
+            .map(|(recipients, master_wallet)| {
+                let batches = ceil_div(recipients.len(), MAX_BATCH_SIZE);
+                let required = &transfer_amount * BigUint::from(recipients.len())
+                    + &batch_fee * BigUint::from(batches);
+                self.ensure_master_balance(master_wallet, required, recipients.len())
+            })
+            .buffer_unordered(MAX_CONCURRENT_BALANCE_CHECKS)
+            .try_collect::<Vec<_>>()
+            .await?;
+
+        Ok(())
+    }
+
+    /// Checks whether the master account has at least `required` amount of the main token in L2 to fund
+    /// the `recipients` amount of accounts. If it doesn't, the master account is topped up the same way it's
+    /// funded during the initialization, i.e. by minting the token and depositing it to L2.
+    /// If it's still not enough, fails with the explanation of the missing amount, so the test is not
+    /// interrupted later with a cryptic error.
+    async fn ensure_master_balance(
+        &self,
+        master_wallet: &Wallet<PrivateKeySigner, RpcProvider>,
+        required: BigUint,
+        recipients: usize,
+    ) -> anyhow::Result<()> {
+        let token = self.config.main_token.as_str();
+
+        let mut balance = master_wallet
+            .get_balance(BlockStatus::Committed, token)
+            .await?;
+        if balance < required {
+            vlog::warn!(
+                "Master account {:?} holds {} {} while {} is required to fund {} accounts, topping it up",
+                master_wallet.address(),
+                balance,
+                token,
+                required,
+                recipients
+            );
+            let top_up = async {
+                self.mint(master_wallet).await?;
+                self.deposit_to_master(master_wallet).await
+            };
+            if let Err(err) = top_up.await {
+                vlog::error!(
+                    "Failed to top up the master account {:?}: {}",
+                    master_wallet.address(),
+                    err
+                );
+            }
+            balance = master_wallet
+                .get_balance(BlockStatus::Committed, token)
+                .await?;
+        }
+
+        if balance < required {
+            anyhow::bail!(
+                "Insufficient funds on the master account {:?}: {} {} is required to fund {} accounts, \
+                 but only {} is available (need {} more)",
+                master_wallet.address(),
+                required,
+                token,
+                recipients,
+                balance,
+                &required - &balance
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Tops up the test accounts which may run out of the main token before completing their operations,
+    /// so they don't fail in the middle of the test. Each account is expected to hold enough funds to pay
+    /// the fees for all of its operations, estimated as transfers.
+    ///
+    /// Funds are sent by the main master wallet, which is idle once the initial transfers are completed.
+    async fn top_up_accounts(&self) -> anyhow::Result<()> {
+        let master_wallet = &self.pool.master_wallet;
+        let token = self.config.main_token.as_str();
+        let main_token_id = master_wallet
+            .tokens
+            .resolve(token.into())
+            .expect("Main token is not supported")
+            .id;
+
+        // The fee is requested once per check.
+        let op_fee = master_wallet
+            .provider
+            .get_tx_fee(TxFeeTypes::Transfer, master_wallet.address(), token)
+            .await?
+            .total_fee;
+        let required = BigInt::from(&op_fee * BigUint::from(self.config.operations_per_account));
+        let recipients: Vec<_> = self
+            .pool
+            .addresses
+            .addresses()
+            .iter()
+            .copied()
+            .filter(|&address| self.balance_tracker.balance(address, main_token_id) < required)
+            .collect();
+        if recipients.is_empty() {
+            return Ok(());
+        }
+
+        vlog::warn!(
+            "{} test accounts may run out of funds before completing their operations, topping them up",
+            recipients.len()
+        );
+        let transfer_amount = BigUint::from(self.transfer_amount());
+        self.ensure_master_balance(
+            master_wallet,
+            (&transfer_amount + &op_fee) * BigUint::from(recipients.len()),
+            recipients.len(),
+        )
+        .await?;
+
+        for address in recipients {
+            let handle = master_wallet
+                .start_transfer()
+                .to(address)
+                .amount(transfer_amount.clone())
+                .token(token)?
+                .fee(op_fee.clone())
+                .send()
+                .await?;
+            let tx_info = self.wait_for_sync_tx(handle.hash()).await?;
+            if let Some(reason) = tx_info.fail_reason {
+                anyhow::bail!(
+                    "Failed to top up the test account {:?}: {}",
+                    address,
+                    reason
+                );
+            }
+            self.balance_tracker
+                .credit(address, main_token_id, &transfer_amount);
+        }
+
+        Ok(())
+    }
+
     /// Returns the amount sufficient for wallets to perform many operations.
     fn transfer_amount(&self) -> u128 {
         let accounts_amount = self.config.accounts_amount;
@@ -300,21 +701,40 @@ impl Executor {
     }
 
     /// Waits for the transaction execution.
+    ///
+    /// If the transaction is still pending once the commit timeout is reached, waiting is repeated
+    /// up to the configured amount of times. Transaction execution result is returned as soon as it's known,
+    /// so the failed transaction is not waited for again.
     async fn wait_for_sync_tx(&self, tx_hash: TxHash) -> Result<TransactionInfo, ClientError> {
         let mut tx_handle =
             SyncTransactionHandle::new(tx_hash, self.pool.master_wallet.provider.clone());
         tx_handle.polling_interval(POLLING_INTERVAL).unwrap();
-
-        tx_handle
-            .commit_timeout(COMMIT_TIMEOUT)
-            .wait_for_commit()
-            .await
+        let tx_handle = tx_handle.commit_timeout(COMMIT_TIMEOUT);
+
+        let max_retries = self.config.commit_timeout_retries();
+        let mut retry = 0;
+        loop {
+            match tx_handle.wait_for_commit().await {
+                Err(ClientError::OperationTimeout) if retry < max_retries => {
+                    retry += 1;
+                    vlog::warn!(
+                        "Transaction {} is not committed within {:?}, waiting once again [{}/{}]",
+                        tx_hash,
+                        COMMIT_TIMEOUT,
+                        retry,
+                        max_retries
+                    );
+                }
+                result => return result,
+            }
+        }
     }
 
     /// Initializes the loadtest by doing the following:
     ///
     /// - Spawning the `ReportCollector`.
     /// - Distributing ERC-20 token in L2 among test wallets via `Transfer` operation.
+    ///   If the accounts ramp-up is configured, wallets are funded by steps with the configured interval between them.
     /// - Distributing ETH in L1 among test wallets in order to make them able to perform priority operations.
     /// - Spawning test account routine futures.
     /// - Collecting all the spawned tasks and returning them to the caller.
@@ -328,90 +748,248 @@ impl Executor {
         // Prepare channels for the report collector.
         let (report_sender, report_receiver) = mpsc::channel(256);
 
-        let report_collector = ReportCollector::new(report_receiver, self.config.allowed_percent);
+        let report_collector = ReportCollector::new(
+            report_receiver,
+            self.config.allowed_percent,
+            self.config.command_delay(),
+        );
         let report_collector_future = tokio::spawn(report_collector.run());
 
         let config = &self.config;
         let accounts_amount = config.accounts_amount;
         let addresses = self.pool.addresses.clone();
+        let ramp_step_accounts = config.ramp_step_accounts();
+        let active_accounts = Arc::new(AtomicUsize::new(0));
+        let main_token_id = self
+            .pool
+            .master_wallet
+            .tokens
+            .resolve(config.main_token.as_str().into())
+            .expect("Main token is not supported")
+            .id;
+        let transfer_amount = BigUint::from(self.transfer_amount());
+        // Balance starting from which the account is considered funded.
+        let funded_balance = BigInt::from(transfer_amount.clone());
+
+        // Test accounts may already have some funds, e.g. if the same seed is reused.
+        self.balance_tracker
+            .load_balances(
+                &self.pool.master_wallet.provider,
+                self.pool
+                    .accounts
+                    .iter()
+                    .map(|account| account.wallet.address()),
+            )
+            .await?;
+
+        // Accounts are taken out of the pool, so they can be funded by the master wallets of the pool concurrently.
+        let mut pending_accounts = std::mem::take(&mut self.pool.accounts);
+        let master_wallets: Vec<_> = self.pool.master_wallets().collect();
 
         let mut retry_counter = 0;
         let mut accounts_processed = 0;
+        let mut accounts_in_ramp_step = 0;
+        // Index of the batch being sent, retries of the same batch are counted as well.
+        let mut batch_index = 0;
 
         let mut account_futures = Vec::new();
+        let spawn_account = |wallet: TestWallet| {
+            let account = AccountLifespan::new(
+                config,
+                addresses.clone(),
+                wallet,
+                report_sender.clone(),
+                self.paused.clone(),
+                active_accounts.clone(),
+                self.balance_tracker.clone(),
+            );
+            tokio::spawn(account.run())
+        };
+        let mut iterations = 0;
         while accounts_processed != accounts_amount {
             if retry_counter > MAX_RETRIES {
                 anyhow::bail!("Reached max amount of retries when sending a batch");
             }
 
+            if self.paused.load(Ordering::SeqCst) {
+                vlog::info!(
+                    "[{}/{}] Loadtest is paused, waiting before sending the next batch",
+                    accounts_processed,
+                    accounts_amount
+                );
+                wait_while_paused(&self.paused).await;
+            }
+
+            if accounts_in_ramp_step == ramp_step_accounts {
+                vlog::info!(
+                    "[{}/{}] Ramp-up step is completed, waiting {:?} before activating more accounts",
+                    accounts_processed,
+                    accounts_amount,
+                    config.ramp_interval()
+                );
+                tokio::time::sleep(config.ramp_interval()).await;
+                accounts_in_ramp_step = 0;
+            }
+
             let accounts_left = accounts_amount - accounts_processed;
-            let accounts_to_process = std::cmp::min(accounts_left, MAX_BATCH_SIZE);
+            let accounts_to_process = accounts_left
+                .min(MAX_BATCH_SIZE * master_wallets.len())
+                .min(ramp_step_accounts - accounts_in_ramp_step);
+
+            if iterations % config.balance_check_interval() == 0 {
+                // Already funded accounts don't receive the initial transfers.
+                let recipients: Vec<_> = pending_accounts
+                    .iter()
+                    .map(|account| account.wallet.address())
+                    .filter(|&address| {
+                        self.balance_tracker.balance(address, main_token_id) < funded_balance
+                    })
+                    .collect();
+                self.check_master_balances(&master_wallets, &recipients)
+                    .await?;
+            }
+            iterations += 1;
+
+            let accounts: Vec<_> = pending_accounts.drain(..accounts_to_process).collect();
+
+            // Accounts which already hold enough funds (e.g. funded during the previous launch with the same seed)
+            // are not funded once again.
+            let (funded_accounts, accounts): (Vec<_>, Vec<_>) =
+                accounts.into_iter().partition(|account| {
+                    self.balance_tracker
+                        .balance(account.wallet.address(), main_token_id)
+                        >= funded_balance
+                });
+            if !funded_accounts.is_empty() {
+                vlog::info!(
+                    "[{}/{}] {} accounts are already funded, skipping the initial transfers to them",
+                    accounts_processed,
+                    accounts_amount,
+                    funded_accounts.len()
+                );
+                accounts_processed += funded_accounts.len();
+                accounts_in_ramp_step += funded_accounts.len();
+                account_futures.extend(funded_accounts.into_iter().map(&spawn_account));
+            }
 
-            let batch_tx_hash = match self.send_initial_transfers_batch(accounts_to_process).await {
-                Ok(hash) => hash,
-                Err(err) => {
-                    vlog::warn!(
-                        "Iteration of the initial funds distribution batch failed: {}",
-                        err
-                    );
-                    retry_counter += 1;
+            // Each master wallet sends its own batch, so batches don't conflict on nonces
+            // and can be processed in parallel.
+            let shard_size = ceil_div(accounts.len(), master_wallets.len()).max(1);
+            let shard_lens: Vec<_> = accounts
+                .chunks(shard_size)
+                .map(|shard| shard.len())
+                .collect();
+            let shards: Vec<_> = accounts
+                .chunks(shard_size)
+                .zip(&master_wallets)
+                .map(|(shard, master_wallet)| {
+                    batch_index += 1;
+                    self.fund_accounts_shard(
+                        master_wallet,
+                        shard,
+                        batch_index,
+                        accounts_processed,
+                        accounts_amount,
+                    )
+                })
+                .collect();
+            let results = try_join_all(shards).await?;
+
+            let mut accounts = accounts.into_iter();
+            let mut failed_accounts = Vec::new();
+            for (shard_len, succeeded) in shard_lens.into_iter().zip(results) {
+                let shard: Vec<_> = accounts.by_ref().take(shard_len).collect();
+                if !succeeded {
+                    failed_accounts.extend(shard);
                     continue;
                 }
-            };
 
-            vlog::info!(
-                "[{}/{}] Sent txs batch",
-                accounts_processed,
-                accounts_amount
-            );
+                accounts_processed += shard.len();
+                accounts_in_ramp_step += shard.len();
 
-            // Now we can wait for a single transaction from the batch to be committed.
-            let tx_result = self.wait_for_sync_tx(batch_tx_hash).await?;
-            if tx_result.fail_reason.is_some() {
-                // Have to try once again.
+                // Spawn each account lifespan.
+                for wallet in shard {
+                    self.balance_tracker.credit(
+                        wallet.wallet.address(),
+                        main_token_id,
+                        &transfer_amount,
+                    );
+                    account_futures.push(spawn_account(wallet));
+                }
+            }
+
+            if failed_accounts.is_empty() {
+                retry_counter = 0;
+            } else {
+                // Have to try once again, failed accounts are returned to the front of the queue.
                 retry_counter += 1;
-                vlog::info!(
-                    "[{}/{}] Batch failed, retrying",
-                    accounts_processed,
-                    accounts_amount
+                for wallet in failed_accounts.into_iter().rev() {
+                    pending_accounts.push_front(wallet);
+                }
+            }
+        }
+
+        assert!(
+            pending_accounts.is_empty(),
+            "Some accounts were not drained"
+        );
+        vlog::info!("All the initial transfers are completed");
+
+        Ok((report_collector_future, account_futures))
+    }
+
+    /// Funds the shard of test accounts from the master wallet by sending a batch and waiting for it to be committed.
+    /// Returns `false` if the batch has to be resent.
+    async fn fund_accounts_shard(
+        &self,
+        master_wallet: &Wallet<PrivateKeySigner, RpcProvider>,
+        accounts: &[TestWallet],
+        batch_index: usize,
+        accounts_processed: usize,
+        accounts_amount: usize,
+    ) -> anyhow::Result<bool> {
+        let batch_tx_hash = match self
+            .send_initial_transfers_batch(master_wallet, accounts)
+            .await
+        {
+            Ok(hash) => hash,
+            Err(err) => {
+                vlog::warn!(
+                    batch = batch_index,
+                    "Iteration of the initial funds distribution batch failed: {}",
+                    err
                 );
-                continue;
+                return Ok(false);
             }
+        };
+
+        vlog::info!(
+            batch = batch_index,
+            "[{}/{}] Sent txs batch",
+            accounts_processed,
+            accounts_amount
+        );
 
-            // All is OK, batch was processed.
-            retry_counter = 0;
+        // Now we can wait for a single transaction from the batch to be committed.
+        let tx_result = self.wait_for_sync_tx(batch_tx_hash).await?;
+        if tx_result.fail_reason.is_some() {
             vlog::info!(
-                "[{}/{}] Batch succeeded",
+                batch = batch_index,
+                "[{}/{}] Batch failed, retrying",
                 accounts_processed,
                 accounts_amount
             );
-            accounts_processed += accounts_to_process;
-
-            // Spawn each account lifespan.
-            let new_account_futures =
-                self.pool
-                    .accounts
-                    .drain(..accounts_to_process)
-                    .map(|wallet| {
-                        let account = AccountLifespan::new(
-                            config,
-                            addresses.clone(),
-                            wallet,
-                            report_sender.clone(),
-                        );
-                        tokio::spawn(account.run())
-                    });
-
-            account_futures.extend(new_account_futures);
+            return Ok(false);
         }
 
-        assert!(
-            self.pool.accounts.is_empty(),
-            "Some accounts were not drained"
+        // All is OK, batch was processed.
+        vlog::info!(
+            batch = batch_index,
+            "[{}/{}] Batch succeeded",
+            accounts_processed,
+            accounts_amount
         );
-        vlog::info!("All the initial transfers are completed");
-
-        Ok((report_collector_future, account_futures))
+        Ok(true)
     }
 
     /// Calculates amount of ETH to be distributed per account in order to make them
@@ -438,10 +1016,27 @@ impl Executor {
     }
 
     /// Waits for all the test account futures to be completed.
-    async fn wait_account_routines(&self, account_futures: Vec<JoinHandle<()>>) {
+    /// Meanwhile, the test accounts are periodically topped up if needed, see `top_up_accounts`.
+    async fn wait_account_routines(
+        &self,
+        account_futures: Vec<JoinHandle<()>>,
+    ) -> anyhow::Result<()> {
         vlog::info!("Waiting for the account futures to be completed...");
-        join_all(account_futures).await;
+        let account_routines = join_all(account_futures);
+        tokio::pin!(account_routines);
+
+        let mut balance_checks = tokio::time::interval(self.config.balance_monitor_interval());
+        // The first tick completes immediately, while the accounts have just been funded.
+        balance_checks.tick().await;
+        loop {
+            tokio::select! {
+                _ = &mut account_routines => break,
+                _ = balance_checks.tick() => self.top_up_accounts().await?,
+            }
+        }
+
         vlog::info!("All the spawned tasks are completed");
+        Ok(())
     }
 
     /// Returns the amount of funds to be deposited on the main account in L2.
@@ -471,3 +1066,7 @@ impl Executor {
         }
     }
 }
+
+fn ceil_div(value: usize, divisor: usize) -> usize {
+    (value + divisor - 1) / divisor
+}
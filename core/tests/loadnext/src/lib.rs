@@ -1,11 +1,13 @@
 pub mod account;
 pub mod account_pool;
 pub mod all;
+pub mod balance_tracker;
 pub mod command;
 pub mod config;
 pub mod constants;
 pub mod corrupted_tx;
 pub mod executor;
+pub mod pause;
 pub mod report;
 pub mod report_collector;
 pub mod rng;
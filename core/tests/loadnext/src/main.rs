@@ -4,7 +4,10 @@
 //! Without required variables provided, test is launched in the localhost/development mode with some hard-coded
 //! values to check the local zkSync deployment.
 
-use loadnext::{config::LoadtestConfig, executor::Executor, report_collector::LoadtestResult};
+use loadnext::{
+    config::LoadtestConfig, executor::Executor, pause::toggle_on_signal,
+    report_collector::LoadtestResult,
+};
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
@@ -20,6 +23,14 @@ async fn main() -> anyhow::Result<()> {
     });
 
     let mut executor = Executor::new(config).await?;
+
+    // With `--estimate` flag the requirements of the test are reported without actually running it.
+    if std::env::args().skip(1).any(|arg| arg == "--estimate") {
+        executor.estimate().await?.report();
+        return Ok(());
+    }
+
+    toggle_on_signal(executor.pause_flag())?;
     let final_resolution = executor.start().await;
 
     match final_resolution {
@@ -0,0 +1,46 @@
+//! Support for pausing the loadtest without interrupting it.
+//!
+//! When the loadtest is paused, the executor and test accounts finish the operations already in flight,
+//! but don't send new transactions until the loadtest is resumed. Pause can be toggled by sending the
+//! `SIGUSR1` signal to the loadtest process, e.g. `kill -USR1 <pid>`.
+
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+use crate::constants::PAUSE_CHECK_INTERVAL;
+
+/// Waits until the `paused` flag is cleared. Returns immediately if the loadtest is not paused.
+pub async fn wait_while_paused(paused: &AtomicBool) {
+    while paused.load(Ordering::SeqCst) {
+        tokio::time::sleep(PAUSE_CHECK_INTERVAL).await;
+    }
+}
+
+/// Spawns the task toggling the `paused` flag each time the process receives `SIGUSR1`.
+#[cfg(unix)]
+pub fn toggle_on_signal(paused: Arc<AtomicBool>) -> anyhow::Result<()> {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let mut signals = signal(SignalKind::user_defined1())?;
+    tokio::spawn(async move {
+        while signals.recv().await.is_some() {
+            // `fetch_xor` returns the previous value.
+            if paused.fetch_xor(true, Ordering::SeqCst) {
+                vlog::info!("Loadtest resumed");
+            } else {
+                vlog::info!("Loadtest paused, send SIGUSR1 once again to resume it");
+            }
+        }
+    });
+
+    Ok(())
+}
+
+/// Pausing via signals is only supported on unix platforms.
+#[cfg(not(unix))]
+pub fn toggle_on_signal(_paused: Arc<AtomicBool>) -> anyhow::Result<()> {
+    vlog::warn!("Pausing the loadtest is not supported on this platform");
+    Ok(())
+}
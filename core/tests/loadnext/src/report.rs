@@ -27,6 +27,8 @@ pub struct Report {
     pub retries: usize,
     /// Duration of the latest execution attempt.
     pub time: Duration,
+    /// Amount of accounts performing the test at the moment of the report creation.
+    pub active_accounts: usize,
 }
 
 /// Builder structure for `Report`.
@@ -50,6 +52,7 @@ impl ReportBuilder {
                 action: ActionType::Tx(TxActionType::Transfer),
                 retries: 0,
                 time: Default::default(),
+                active_accounts: 0,
             },
         }
     }
@@ -79,6 +82,11 @@ impl ReportBuilder {
         self
     }
 
+    pub fn active_accounts(mut self, active_accounts: usize) -> Self {
+        self.report.active_accounts = active_accounts;
+        self
+    }
+
     pub fn finish(self) -> Report {
         self.report
     }
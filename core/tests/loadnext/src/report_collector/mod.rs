@@ -1,3 +1,5 @@
+use std::time::{Duration, Instant};
+
 use futures::{channel::mpsc::Receiver, StreamExt};
 use operation_results_collector::OperationResultsCollector;
 
@@ -44,23 +46,32 @@ pub enum LoadtestResult {
 #[derive(Debug)]
 pub struct ReportCollector {
     allowed_percent: u8,
+    command_delay: Duration,
     reports_stream: Receiver<Report>,
     metrics_collector: MetricsCollector,
     operations_results_collector: OperationResultsCollector,
+    max_active_accounts: usize,
 }
 
 impl ReportCollector {
-    pub fn new(reports_stream: Receiver<Report>, allowed_percent: u8) -> Self {
+    pub fn new(
+        reports_stream: Receiver<Report>,
+        allowed_percent: u8,
+        command_delay: Duration,
+    ) -> Self {
         assert!(allowed_percent < 100, "Allowed percent more than 100");
         Self {
             allowed_percent,
+            command_delay,
             reports_stream,
             metrics_collector: MetricsCollector::new(),
             operations_results_collector: OperationResultsCollector::new(),
+            max_active_accounts: 0,
         }
     }
 
     pub async fn run(mut self) -> LoadtestResult {
+        let started_at = Instant::now();
         while let Some(report) = self.reports_stream.next().await {
             vlog::trace!("Report: {:?}", &report);
 
@@ -72,6 +83,12 @@ impl ReportCollector {
 
             self.operations_results_collector.add_status(&report.label);
 
+            // Track the growth of the load, which is not instant if the accounts ramp-up is configured.
+            if report.active_accounts > self.max_active_accounts {
+                self.max_active_accounts = report.active_accounts;
+                vlog::info!("Active accounts: {}", self.max_active_accounts);
+            }
+
             // Report failure, if it exists.
             if let ReportLabel::ActionFailed { error } = &report.label {
                 vlog::warn!("Operation failed: {}", error);
@@ -82,10 +99,28 @@ impl ReportCollector {
         // Now we can output the statistics.
         self.metrics_collector.report();
         self.operations_results_collector.report();
+        self.report_throughput(started_at.elapsed());
+        vlog::info!(
+            "Maximum amount of simultaneously active accounts: {}",
+            self.max_active_accounts
+        );
 
         self.final_resolution()
     }
 
+    /// Reports the effective throughput of the test.
+    /// Since it's calculated over the whole test duration, it includes the delays between the commands of accounts.
+    fn report_throughput(&self, elapsed: Duration) {
+        let actions_per_sec =
+            self.operations_results_collector.total() as f64 / elapsed.as_secs_f64().max(1.0);
+        vlog::info!(
+            "Effective throughput: {:.2} actions per second over {:?} (delay between account commands: {:?})",
+            actions_per_sec,
+            elapsed,
+            self.command_delay
+        );
+    }
+
     fn final_resolution(&self) -> LoadtestResult {
         let failure_percent = (self.operations_results_collector.failures() as f64
             / self.operations_results_collector.total() as f64)
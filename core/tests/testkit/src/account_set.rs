@@ -3,7 +3,9 @@ use crate::zksync_account::ZkSyncAccount;
 use num::BigUint;
 use web3::types::{TransactionReceipt, H256, U64};
 use zksync_crypto::rand::Rng;
-use zksync_types::tx::{ChangePubKeyType, TimeRange};
+use zksync_types::tx::{
+    ChangePubKeyCREATE2Data, ChangePubKeyEthAuthData, ChangePubKeyType, TimeRange,
+};
 use zksync_types::{AccountId, Address, Nonce, PriorityOp, TokenId, ZkSyncTx};
 
 use crate::types::*;
@@ -17,6 +19,26 @@ pub struct AccountSet {
     pub fee_account_id: ZKSyncAccountId,
 }
 impl AccountSet {
+    /// Returns the account deterministically derived from `seed`.
+    /// Unlike randomly generated ones, it can be re-created later in the test to
+    /// look up the account or to sign transactions on its behalf.
+    pub fn ephemeral_account(seed: u64) -> ZkSyncAccount {
+        // Constant words guarantee that the rng seed is never all zeros.
+        ZkSyncAccount::rand_with_seed([seed as u32, (seed >> 32) as u32, 0x5eed, 0x1234_5678])
+    }
+
+    /// Returns the address of the account deterministically derived from `seed`.
+    pub fn ephemeral_address(seed: u64) -> Address {
+        Self::ephemeral_account(seed).address
+    }
+
+    /// Adds the account derived from `seed` to the set, so it can be used
+    /// for further transactions (e.g. after it was created via `TestSetup::deposit_to` or `TestSetup::transfer_to_new`).
+    pub fn add_ephemeral_account(&mut self, seed: u64) -> ZKSyncAccountId {
+        self.zksync_accounts.push(Self::ephemeral_account(seed));
+        ZKSyncAccountId(self.zksync_accounts.len() - 1)
+    }
+
     /// Create deposit from eth account to zksync account
     pub async fn deposit(
         &self,
@@ -46,8 +68,20 @@ impl AccountSet {
         amount: BigUint,
         rng: &mut impl Rng,
     ) -> (Vec<TransactionReceipt>, PriorityOp) {
-        let from = &self.eth_accounts[from.0];
         let to_address = Address::from_slice(&rng.gen::<[u8; 20]>());
+        self.deposit_to_address(from, token, amount, to_address)
+            .await
+    }
+
+    /// Create deposit from eth account to an arbitrary address
+    pub async fn deposit_to_address(
+        &self,
+        from: ETHAccountId,
+        token: Option<Address>, // None for ETH
+        amount: BigUint,
+        to_address: Address,
+    ) -> (Vec<TransactionReceipt>, PriorityOp) {
+        let from = &self.eth_accounts[from.0];
 
         if let Some(address) = token {
             from.deposit_erc20(address, amount, &to_address)
@@ -138,9 +172,33 @@ impl AccountSet {
         increment_nonce: bool,
         rng: &mut impl Rng,
     ) -> ZkSyncTx {
-        let from = &self.zksync_accounts[from.0];
-
         let to_address = Address::from_slice(&rng.gen::<[u8; 20]>());
+        self.transfer_to_address(
+            from,
+            token_id,
+            amount,
+            fee,
+            nonce,
+            increment_nonce,
+            to_address,
+        )
+    }
+
+    /// Create signed transfer from zksync account to an arbitrary address
+    /// `nonce` optional nonce override
+    /// `increment_nonce` - flag for `from` account nonce increment
+    #[allow(clippy::too_many_arguments)]
+    pub fn transfer_to_address(
+        &self,
+        from: ZKSyncAccountId,
+        token_id: Token,
+        amount: BigUint,
+        fee: BigUint,
+        nonce: Option<Nonce>,
+        increment_nonce: bool,
+        to_address: Address,
+    ) -> ZkSyncTx {
+        let from = &self.zksync_accounts[from.0];
 
         ZkSyncTx::Transfer(Box::new(
             from.sign_transfer(
@@ -351,6 +409,45 @@ impl AccountSet {
         )))
     }
 
+    /// Create `ChangePubKey` transaction authorized with the provided CREATE2 data.
+    /// The `zksync_signer` account must be a CREATE2 account with the address derived from this data.
+    #[allow(clippy::too_many_arguments)]
+    pub fn change_pubkey_with_create2(
+        &self,
+        zksync_signer: ZKSyncAccountId,
+        creator_address: Address,
+        salt_arg: H256,
+        code_hash: H256,
+        fee_token: TokenId,
+        fee: BigUint,
+        nonce: Option<Nonce>,
+        increment_nonce: bool,
+    ) -> ZkSyncTx {
+        let zksync_account = &self.zksync_accounts[zksync_signer.0];
+        let mut change_pubkey = zksync_account.sign_change_pubkey_tx(
+            nonce,
+            increment_nonce,
+            fee_token,
+            fee,
+            ChangePubKeyType::CREATE2,
+            Default::default(),
+        );
+        // Eth auth data is not covered by the zkSync signature, so the CREATE2 data the account
+        // was created with can be replaced with the provided one.
+        change_pubkey.eth_auth_data =
+            Some(ChangePubKeyEthAuthData::CREATE2(ChangePubKeyCREATE2Data {
+                creator_address,
+                salt_arg,
+                code_hash,
+            }));
+        assert!(
+            change_pubkey.is_eth_auth_data_valid(),
+            "CREATE2 data does not correspond to the account address"
+        );
+
+        ZkSyncTx::ChangePubKey(Box::new(change_pubkey))
+    }
+
     #[allow(clippy::too_many_arguments)]
     pub fn swap(
         &self,
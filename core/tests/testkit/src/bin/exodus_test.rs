@@ -161,6 +161,9 @@ async fn check_exit_correct_proof(
         .to_owned();
 
     let (proof, exit_amount) = test_setup.gen_exit_proof_fungible(accounts, fund_owner, token);
+    test_setup
+        .verify_exit_proof(&proof)
+        .expect("Generated exit proof should be valid");
     assert_eq!(
         &exit_amount, amount,
         "Exit proof generated with unexpected amount"
@@ -218,6 +221,9 @@ async fn check_exit_correct_proof_second_time(
         .to_owned();
 
     let (proof, exit_amount) = test_setup.gen_exit_proof_fungible(accounts, fund_owner, token);
+    test_setup
+        .verify_exit_proof(&proof)
+        .expect("Generated exit proof should be valid");
     assert_eq!(
         &exit_amount, amount,
         "Exit proof generated with unexpected amount"
@@ -270,6 +276,9 @@ async fn check_exit_correct_proof_other_token(
         .to_owned();
 
     let (proof, exit_amount) = test_setup.gen_exit_proof_fungible(accounts, fund_owner, token);
+    test_setup
+        .verify_exit_proof(&proof)
+        .expect("Generated exit proof should be valid");
     assert_eq!(
         &exit_amount, amount,
         "Exit proof generated with unexpected amount"
@@ -322,6 +331,9 @@ async fn check_exit_correct_proof_other_amount(
         .to_owned();
 
     let (proof, exit_amount) = test_setup.gen_exit_proof_fungible(accounts, fund_owner, token);
+    test_setup
+        .verify_exit_proof(&proof)
+        .expect("Generated exit proof should be valid");
     assert_eq!(
         &exit_amount, amount,
         "Exit proof generated with unexpected amount"
@@ -373,6 +385,9 @@ async fn check_exit_correct_proof_incorrect_sender(
         .to_owned();
 
     let (proof, exit_amount) = test_setup.gen_exit_proof_fungible(accounts, fund_owner, token);
+    test_setup
+        .verify_exit_proof(&proof)
+        .expect("Generated exit proof should be valid");
     assert_eq!(
         &exit_amount, amount,
         "Exit proof generated with unexpected amount"
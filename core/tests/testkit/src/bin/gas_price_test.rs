@@ -26,9 +26,9 @@ use zksync_testkit::zksync_account::ZkSyncETHAccountData;
 use zksync_testkit::*;
 use zksync_types::{
     helpers::{pack_fee_amount, pack_token_amount, unpack_fee_amount, unpack_token_amount},
-    tx::ChangePubKeyCREATE2Data,
-    Address, ChangePubKeyOp, DepositOp, FullExitOp, MintNFTOp, Nonce, PubKeyHash, SwapOp, TokenId,
-    TransferOp, TransferToNewOp, WithdrawNFTOp, WithdrawOp,
+    tokens::ChangePubKeyFeeTypeArg,
+    tx::{ChangePubKeyCREATE2Data, ChangePubKeyType},
+    Address, DepositOp, FullExitOp, Nonce, PubKeyHash, TokenId, TransferToNewOp, TxFeeTypes,
 };
 use zksync_utils::UnsignedRatioSerializeAsDecimal;
 
@@ -416,7 +416,7 @@ async fn commit_cost_of_mint_nft(
         .expect("Block execution failed");
     assert_eq!(
         execute_result.block_size_chunks,
-        n_mint_nfts * MintNFTOp::CHUNKS,
+        n_mint_nfts * TxFeeTypes::MintNFT.chunks(),
         "block size mismatch"
     );
     execute_result.commit_result.gas_used.unwrap();
@@ -490,7 +490,7 @@ async fn commit_cost_of_transfers(
         .expect("Block execution failed");
     assert_eq!(
         transfer_execute_result.block_size_chunks,
-        n_transfers * TransferOp::CHUNKS,
+        n_transfers * TxFeeTypes::Transfer.chunks(),
         "block size mismatch"
     );
     transfer_execute_result.commit_result.gas_used.unwrap();
@@ -598,7 +598,7 @@ async fn commit_cost_of_swaps(
         .expect("Block execution failed");
     assert_eq!(
         swap_execute_result.block_size_chunks,
-        n_swaps * SwapOp::CHUNKS,
+        n_swaps * TxFeeTypes::Swap.chunks(),
         "block size mismatch"
     );
     swap_execute_result.commit_result.gas_used.unwrap();
@@ -746,7 +746,7 @@ async fn commit_cost_of_withdrawals_nft(
         .expect("Block execution failed");
     assert_eq!(
         withdraws_execute_result.block_size_chunks,
-        n_withdrawals * WithdrawNFTOp::CHUNKS,
+        n_withdrawals * TxFeeTypes::WithdrawNFT.chunks(),
         "block size mismatch"
     );
     CostsSample::new(n_withdrawals, U256::from(0), withdraws_execute_result)
@@ -803,7 +803,7 @@ async fn commit_cost_of_withdrawals(
         .expect("Block execution failed");
     assert_eq!(
         withdraws_execute_result.block_size_chunks,
-        n_withdrawals * WithdrawOp::CHUNKS,
+        n_withdrawals * TxFeeTypes::Withdraw.chunks(),
         "block size mismatch"
     );
     CostsSample::new(n_withdrawals, U256::from(0), withdraws_execute_result)
@@ -954,7 +954,11 @@ async fn commit_cost_of_change_pubkey(
         .expect("Block execution failed");
     assert_eq!(
         change_pubkey_execute_result.block_size_chunks,
-        n_change_pubkeys * ChangePubKeyOp::CHUNKS,
+        n_change_pubkeys
+            * TxFeeTypes::ChangePubKey(ChangePubKeyFeeTypeArg::ContractsV4Version(
+                ChangePubKeyType::ECDSA
+            ))
+            .chunks(),
         "block size mismatch"
     );
     CostsSample::new(
@@ -1001,7 +1005,11 @@ async fn commit_cost_of_onchain_change_pubkey(
         .expect("Block execution failed");
     assert_eq!(
         change_pubkey_execute_result.block_size_chunks,
-        n_change_pubkeys * ChangePubKeyOp::CHUNKS,
+        n_change_pubkeys
+            * TxFeeTypes::ChangePubKey(ChangePubKeyFeeTypeArg::ContractsV4Version(
+                ChangePubKeyType::Onchain
+            ))
+            .chunks(),
         "block size mismatch"
     );
     CostsSample::new(
@@ -1080,7 +1088,11 @@ async fn commit_cost_of_create2_change_pubkey(
         .expect("Block execution failed");
     assert_eq!(
         change_pubkey_execute_result.block_size_chunks,
-        n_change_pubkeys * ChangePubKeyOp::CHUNKS,
+        n_change_pubkeys
+            * TxFeeTypes::ChangePubKey(ChangePubKeyFeeTypeArg::ContractsV4Version(
+                ChangePubKeyType::CREATE2
+            ))
+            .chunks(),
         "block size mismatch"
     );
     CostsSample::new(
@@ -88,6 +88,7 @@ async fn migration_test() {
         &mut test_setup,
         deposit_amount.clone(),
         BlockProcessing::CommitAndVerify,
+        None,
     )
     .await;
 
@@ -101,6 +102,7 @@ async fn migration_test() {
         &mut test_setup,
         deposit_amount.clone(),
         BlockProcessing::CommitAndVerify,
+        None,
     )
     .await;
 
@@ -90,6 +90,7 @@ async fn execute_blocks(
             test_setup,
             deposit_amount.clone(),
             BlockProcessing::CommitAndVerify,
+            None,
         )
         .await;
         executed_blocks.extend(blocks.into_iter());
@@ -107,6 +108,7 @@ async fn execute_blocks(
             test_setup,
             deposit_amount.clone(),
             BlockProcessing::NoVerify,
+            None,
         )
         .await;
         executed_blocks.extend(blocks.into_iter());
@@ -1,4 +1,4 @@
-use crate::external_commands::js_revert_reason;
+use crate::external_commands::{js_revert_reason, test_erc20_contract};
 use std::collections::HashMap;
 
 use anyhow::{bail, ensure, format_err};
@@ -6,12 +6,15 @@ use ethabi::{Contract, Token, Uint};
 use num::{BigUint, ToPrimitive};
 use std::convert::TryFrom;
 use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
 use web3::{
     contract::Options,
     transports::Http,
     types::{TransactionReceipt, H256, U128, U256, U64},
 };
-use zksync_contracts::{erc20_contract, zksync_contract};
+use zksync_contracts::{erc20_contract, governance_contract, zksync_contract};
 use zksync_crypto::proof::EncodedSingleProof;
 use zksync_eth_client::ETHDirectClient;
 use zksync_eth_signer::PrivateKeySigner;
@@ -48,6 +51,10 @@ pub struct EthereumAccount {
     pub private_key: H256,
     pub address: Address,
     pub main_contract_eth_client: ETHDirectClient<PrivateKeySigner>,
+    /// Nonce to be used for the next transaction. Tracked locally, so that sequential
+    /// transactions don't have to fetch it from the node. `None` means that nonce
+    /// has to be fetched from the node.
+    pending_nonce: Arc<Mutex<Option<U256>>>,
 }
 
 fn big_dec_to_u256(bd: BigUint) -> U256 {
@@ -89,9 +96,64 @@ impl EthereumAccount {
             private_key,
             address,
             main_contract_eth_client,
+            pending_nonce: Arc::new(Mutex::new(None)),
         }
     }
 
+    /// Resets the locally tracked nonce, so it will be fetched from the node
+    /// for the next transaction.
+    pub async fn reset_nonce(&self) {
+        *self.pending_nonce.lock().await = None;
+    }
+
+    /// Returns the nonce for the next transaction and increments the locally tracked one.
+    async fn next_nonce(&self) -> Result<U256, anyhow::Error> {
+        let mut pending_nonce = self.pending_nonce.lock().await;
+        let nonce = match *pending_nonce {
+            Some(nonce) => nonce,
+            None => self.main_contract_eth_client.pending_nonce().await?,
+        };
+        *pending_nonce = Some(nonce + 1);
+        Ok(nonce)
+    }
+
+    async fn sign_and_send(
+        &self,
+        data: Vec<u8>,
+        options: Options,
+    ) -> Result<TransactionReceipt, anyhow::Error> {
+        self.sign_and_send_for_addr(data, self.main_contract_eth_client.contract_addr(), options)
+            .await
+    }
+
+    /// Signs the transaction using the locally tracked nonce (unless nonce is set in `options`),
+    /// sends it and waits for the confirmation. If anything goes wrong, the tracked nonce
+    /// is reset in order to be resynced with the node.
+    async fn sign_and_send_for_addr(
+        &self,
+        data: Vec<u8>,
+        contract_addr: Address,
+        mut options: Options,
+    ) -> Result<TransactionReceipt, anyhow::Error> {
+        if options.nonce.is_none() {
+            options.nonce = Some(self.next_nonce().await?);
+        }
+
+        let result = async {
+            let signed_tx = self
+                .main_contract_eth_client
+                .sign_prepared_tx_for_addr(data, contract_addr, options)
+                .await?;
+            send_raw_tx_wait_confirmation(&self.main_contract_eth_client, signed_tx.raw_tx).await
+        }
+        .await;
+
+        if result.is_err() {
+            self.reset_nonce().await;
+        }
+        result
+    }
+
     pub async fn total_blocks_committed(&self) -> Result<u64, anyhow::Error> {
         let contract = self.main_contract_eth_client.main_contract();
         contract
@@ -127,13 +189,10 @@ impl EthereumAccount {
             .main_contract_eth_client
             .encode_tx_data("requestFullExit", (u64::from(*account_id), token_address));
 
-        let signed_tx = self
-            .main_contract_eth_client
-            .sign_prepared_tx(data, default_tx_options())
+        let receipt = self
+            .sign_and_send(data, default_tx_options())
             .await
             .map_err(|e| format_err!("Full exit send err: {}", e))?;
-        let receipt =
-            send_raw_tx_wait_confirmation(&self.main_contract_eth_client, signed_tx.raw_tx).await?;
         ensure!(
             receipt.status == Some(U64::from(1)),
             "Full exit submit fail"
@@ -175,15 +234,11 @@ impl EthereumAccount {
                 proof.proof,
             ),
         );
-        let signed_tx = self
-            .main_contract_eth_client
-            .sign_prepared_tx(data, options)
+        let receipt = self
+            .sign_and_send(data, options)
             .await
             .map_err(|e| format_err!("Exit send err: {}", e))?;
 
-        let receipt =
-            send_raw_tx_wait_confirmation(&self.main_contract_eth_client, signed_tx.raw_tx).await?;
-
         Ok(ETHExecResult::new(receipt, &self.main_contract_eth_client).await)
     }
 
@@ -196,15 +251,11 @@ impl EthereumAccount {
             "cancelOutstandingDepositsForExodusMode",
             (number, priority_op_data),
         );
-        let signed_tx = self
-            .main_contract_eth_client
-            .sign_prepared_tx(data, default_tx_options())
+        let receipt = self
+            .sign_and_send(data, default_tx_options())
             .await
             .map_err(|e| format_err!("cancelOutstandingDepositsForExodusMode send err: {}", e))?;
 
-        let receipt =
-            send_raw_tx_wait_confirmation(&self.main_contract_eth_client, signed_tx.raw_tx).await?;
-
         Ok(ETHExecResult::new(receipt, &self.main_contract_eth_client).await)
     }
 
@@ -215,13 +266,10 @@ impl EthereumAccount {
         let data = self
             .main_contract_eth_client
             .encode_tx_data("changePubKeyHash", (new_pubkey_hash.data.to_vec(),));
-        let signed_tx = self
-            .main_contract_eth_client
-            .sign_prepared_tx(data, default_tx_options())
+        let receipt = self
+            .sign_and_send(data, default_tx_options())
             .await
             .map_err(|e| format_err!("ChangePubKeyHash send err: {}", e))?;
-        let receipt =
-            send_raw_tx_wait_confirmation(&self.main_contract_eth_client, signed_tx.raw_tx).await?;
         ensure!(
             receipt.status == Some(U64::from(1)),
             "ChangePubKeyHash transaction failed"
@@ -240,9 +288,8 @@ impl EthereumAccount {
         let data = self
             .main_contract_eth_client
             .encode_tx_data("depositETH", *to);
-        let signed_tx = self
-            .main_contract_eth_client
-            .sign_prepared_tx(
+        let receipt = self
+            .sign_and_send(
                 data,
                 Options::with(|opt| {
                     opt.value = Some(big_dec_to_u256(amount.clone()));
@@ -252,8 +299,6 @@ impl EthereumAccount {
             )
             .await
             .map_err(|e| format_err!("Deposit eth send err: {}", e))?;
-        let receipt =
-            send_raw_tx_wait_confirmation(&self.main_contract_eth_client, signed_tx.raw_tx).await?;
         ensure!(receipt.status == Some(U64::from(1)), "eth deposit fail");
         let priority_op =
             priority_op_from_tx_logs(&receipt).expect("no priority op log in deposit");
@@ -284,6 +329,31 @@ impl EthereumAccount {
             .map_err(|e| format_err!("Contract query fail: {}", e))
     }
 
+    /// Returns the number of decimals declared by the ERC20 token contract.
+    pub async fn erc20_decimals(&self, token_contract: &Address) -> Result<u8, anyhow::Error> {
+        let decimals: U256 = self
+            .main_contract_eth_client
+            .call_contract_function(
+                "decimals",
+                (),
+                None,
+                Options::default(),
+                None,
+                *token_contract,
+                test_erc20_contract(),
+            )
+            .await
+            .map_err(|e| format_err!("Contract query fail: {}", e))?;
+
+        u8::try_from(decimals.as_u64()).map_err(|_| {
+            format_err!(
+                "Token {:?} has invalid decimals: {}",
+                token_contract,
+                decimals
+            )
+        })
+    }
+
     pub async fn balances_to_withdraw(&self, token: Address) -> Result<BigUint, anyhow::Error> {
         let contract = self.main_contract_eth_client.main_contract();
 
@@ -300,35 +370,45 @@ impl EthereumAccount {
             .map_err(|e| format_err!("Contract query fail: {}", e))?)
     }
 
+    /// Queries balances to withdraw for several tokens at once.
+    /// No multicall contract is deployed in the test environment, so the `eth_call`s
+    /// are sent concurrently instead of one after another.
+    pub async fn balances_to_withdraw_multi(
+        &self,
+        tokens: &[(TokenId, Address)],
+    ) -> Result<HashMap<TokenId, BigUint>, anyhow::Error> {
+        let balances = futures::future::try_join_all(
+            tokens
+                .iter()
+                .map(|(_, token_address)| self.balances_to_withdraw(*token_address)),
+        )
+        .await?;
+
+        Ok(tokens
+            .iter()
+            .map(|(token_id, _)| *token_id)
+            .zip(balances)
+            .collect())
+    }
+
     pub async fn approve_erc20(
         &self,
         token_contract: Address,
         amount: BigUint,
     ) -> Result<TransactionReceipt, anyhow::Error> {
-        let eth_signer = PrivateKeySigner::new(self.private_key);
-        let erc20_client = ETHDirectClient::new(
-            self.main_contract_eth_client.get_web3_transport().clone(),
-            erc20_contract(),
-            self.address,
-            eth_signer,
-            token_contract,
-            self.main_contract_eth_client.chain_id(),
-            self.main_contract_eth_client.gas_price_factor(),
-        );
-        let data = erc20_client.encode_tx_data(
-            "approve",
-            (
-                self.main_contract_eth_client.contract_addr(),
-                big_dec_to_u256(amount.clone()),
-            ),
-        );
-
-        let signed_tx = erc20_client
-            .sign_prepared_tx(data, default_tx_options())
+        let data = erc20_contract()
+            .function("approve")
+            .and_then(|f| {
+                f.encode_input(&[
+                    Token::Address(self.main_contract_eth_client.contract_addr()),
+                    Token::Uint(big_dec_to_u256(amount.clone())),
+                ])
+            })
+            .map_err(|e| format_err!("Failed to encode approve call: {}", e))?;
+        let receipt = self
+            .sign_and_send_for_addr(data, token_contract, default_tx_options())
             .await
             .map_err(|e| format_err!("Approve send err: {}", e))?;
-        let receipt =
-            send_raw_tx_wait_confirmation(&self.main_contract_eth_client, signed_tx.raw_tx).await?;
 
         ensure!(receipt.status == Some(U64::from(1)), "erc20 approve fail");
 
@@ -348,13 +428,10 @@ impl EthereumAccount {
             "depositERC20",
             (token_contract, big_dec_to_u256(amount.clone()), *to),
         );
-        let signed_tx = self
-            .main_contract_eth_client
-            .sign_prepared_tx(data, default_tx_options())
+        let receipt = self
+            .sign_and_send(data, default_tx_options())
             .await
             .map_err(|e| format_err!("Deposit erc20 send err: {}", e))?;
-        let receipt =
-            send_raw_tx_wait_confirmation(&self.main_contract_eth_client, signed_tx.raw_tx).await?;
         let exec_result = ETHExecResult::new(receipt, &self.main_contract_eth_client).await;
         let receipt = exec_result.success_result()?;
         let priority_op =
@@ -362,47 +439,87 @@ impl EthereumAccount {
         Ok((vec![approve_receipt, receipt], priority_op))
     }
 
+    /// Returns the network gas price scaled by the provided factor.
+    /// If no factor is provided, `None` is returned, so the client will use the factor
+    /// the account was created with.
+    async fn gas_price_with_factor(
+        &self,
+        gas_price_factor: Option<f64>,
+    ) -> Result<Option<U256>, anyhow::Error> {
+        let gas_price_factor = match gas_price_factor {
+            Some(gas_price_factor) => gas_price_factor,
+            None => return Ok(None),
+        };
+
+        let web3 = web3::Web3::new(self.main_contract_eth_client.get_web3_transport().clone());
+        let network_gas_price = web3
+            .eth()
+            .gas_price()
+            .await
+            .map_err(|e| format_err!("Failed to get gas price: {}", e))?;
+        let percent_gas_price_factor = U256::from((gas_price_factor * 100.0).round() as u64);
+        Ok(Some(
+            (network_gas_price * percent_gas_price_factor) / U256::from(100),
+        ))
+    }
+
+    /// Commits blocks. Gas price can be scaled by `gas_price_factor`,
+    /// otherwise the factor from the account configuration is used.
+    /// Returns once the transaction has `confirmations` confirmations (1 by default, i.e. it is mined),
+    /// see `await_confirmations`.
     pub async fn commit_block(
         &self,
         commit_operation: &BlocksCommitOperation,
+        gas_price_factor: Option<f64>,
+        confirmations: Option<u64>,
     ) -> Result<ETHExecResult, anyhow::Error> {
         let data = self.main_contract_eth_client.encode_tx_data(
             "commitBlocks",
             commit_operation.get_eth_tx_args().as_slice(),
         );
-        let signed_tx = self
-            .main_contract_eth_client
-            .sign_prepared_tx(
+        let gas_price = self.gas_price_with_factor(gas_price_factor).await?;
+        let receipt = self
+            .sign_and_send(
                 data,
-                Options::with(|f| f.gas = Some(U256::from(9 * 10u64.pow(6)))),
+                Options::with(|f| {
+                    f.gas = Some(U256::from(9 * 10u64.pow(6)));
+                    f.gas_price = gas_price;
+                }),
             )
             .await
             .map_err(|e| format_err!("Commit block send err: {}", e))?;
-
-        let receipt =
-            send_raw_tx_wait_confirmation(&self.main_contract_eth_client, signed_tx.raw_tx).await?;
+        self.await_confirmations(&receipt, confirmations.unwrap_or(1))
+            .await?;
 
         Ok(ETHExecResult::new(receipt, &self.main_contract_eth_client).await)
     }
 
     // Verifies block using provided proof or empty proof if None is provided. (`DUMMY_VERIFIER` should be enabled on the contract).
+    // Gas price can be scaled by `gas_price_factor`, otherwise the factor from the account configuration is used.
+    // Returns once the transaction has `confirmations` confirmations (1 by default, i.e. it is mined).
     pub async fn verify_block(
         &self,
         proof_operation: &BlocksProofOperation,
+        gas_price_factor: Option<f64>,
+        confirmations: Option<u64>,
     ) -> Result<ETHExecResult, anyhow::Error> {
         let data = self
             .main_contract_eth_client
             .encode_tx_data("proveBlocks", proof_operation.get_eth_tx_args().as_slice());
-        let signed_tx = self
-            .main_contract_eth_client
-            .sign_prepared_tx(
+        let gas_price = self.gas_price_with_factor(gas_price_factor).await?;
+        let receipt = self
+            .sign_and_send(
                 data,
-                Options::with(|f| f.gas = Some(U256::from(10 * 10u64.pow(6)))),
+                Options::with(|f| {
+                    f.gas = Some(U256::from(10 * 10u64.pow(6)));
+                    f.gas_price = gas_price;
+                }),
             )
             .await
             .map_err(|e| format_err!("Verify block send err: {}", e))?;
-        let receipt =
-            send_raw_tx_wait_confirmation(&self.main_contract_eth_client, signed_tx.raw_tx).await?;
+        self.await_confirmations(&receipt, confirmations.unwrap_or(1))
+            .await?;
+
         Ok(ETHExecResult::new(receipt, &self.main_contract_eth_client).await)
     }
 
@@ -416,16 +533,13 @@ impl EthereumAccount {
             execute_operation.get_eth_tx_args().as_slice(),
         );
 
-        let signed_tx = self
-            .main_contract_eth_client
-            .sign_prepared_tx(
+        let receipt = self
+            .sign_and_send(
                 data,
                 Options::with(|f| f.gas = Some(U256::from(9 * 10u64.pow(6)))),
             )
             .await
             .map_err(|e| format_err!("Complete withdrawals send err: {}", e))?;
-        let receipt =
-            send_raw_tx_wait_confirmation(&self.main_contract_eth_client, signed_tx.raw_tx).await?;
 
         Ok(ETHExecResult::new(receipt, &self.main_contract_eth_client).await)
     }
@@ -478,14 +592,10 @@ impl EthereumAccount {
         let data = f
             .encode_input(&tokens)
             .expect("failed to encode parameters");
-        let signed_tx = self
-            .main_contract_eth_client
-            .sign_prepared_tx_for_addr(data, pending_withdrawer_contract.1, Options::default())
+        let receipt = self
+            .sign_and_send_for_addr(data, pending_withdrawer_contract.1, Options::default())
             .await
             .map_err(|e| format_err!("Complete withdrawals send err: {}", e))?;
-
-        let receipt =
-            send_raw_tx_wait_confirmation(&self.main_contract_eth_client, signed_tx.raw_tx).await?;
         Ok(Some(
             ETHExecResult::new(receipt, &self.main_contract_eth_client).await,
         ))
@@ -498,16 +608,13 @@ impl EthereumAccount {
             .main_contract_eth_client
             .encode_tx_data("revertBlocks", tx_arg);
 
-        let signed_tx = self
-            .main_contract_eth_client
-            .sign_prepared_tx(
+        let receipt = self
+            .sign_and_send(
                 data,
                 Options::with(|f| f.gas = Some(U256::from(9 * 10u64.pow(6)))),
             )
             .await
             .map_err(|e| format_err!("Revert blocks send err: {}", e))?;
-        let receipt =
-            send_raw_tx_wait_confirmation(&self.main_contract_eth_client, signed_tx.raw_tx).await?;
 
         Ok(ETHExecResult::new(receipt, &self.main_contract_eth_client).await)
     }
@@ -516,13 +623,10 @@ impl EthereumAccount {
         let data = self
             .main_contract_eth_client
             .encode_tx_data("activateExodusMode", ());
-        let signed_tx = self
-            .main_contract_eth_client
-            .sign_prepared_tx(data, default_tx_options())
+        let receipt = self
+            .sign_and_send(data, default_tx_options())
             .await
             .map_err(|e| format_err!("Trigger exodus if needed send err: {}", e))?;
-        let receipt =
-            send_raw_tx_wait_confirmation(&self.main_contract_eth_client, signed_tx.raw_tx).await?;
 
         Ok(ETHExecResult::new(receipt, &self.main_contract_eth_client).await)
     }
@@ -531,6 +635,210 @@ impl EthereumAccount {
         Ok(self.main_contract_eth_client.block_number().await?.as_u64())
     }
 
+    /// Mines `n` empty blocks on the L1 node.
+    ///
+    /// Relies on the `evm_mine` RPC method, so it only works with dev nodes
+    /// (e.g. geth in dev mode with the corresponding extension, ganache or hardhat).
+    pub async fn mine_blocks(&self, n: u64) -> Result<(), anyhow::Error> {
+        let transport = self.main_contract_eth_client.get_web3_transport();
+        for _ in 0..n {
+            web3::Transport::execute(transport, "evm_mine", vec![])
+                .await
+                .map_err(|e| format_err!("evm_mine failed (is it a dev node?): {}", e))?;
+        }
+        Ok(())
+    }
+
+    /// Returns the number of confirmations of the mined transaction, counting the block it was included in.
+    pub async fn confirmations(&self, receipt: &TransactionReceipt) -> Result<u64, anyhow::Error> {
+        let mined_in = receipt
+            .block_number
+            .ok_or_else(|| {
+                format_err!("Transaction is not mined: 0x{:x}", receipt.transaction_hash)
+            })?
+            .as_u64();
+        let current_block = self.eth_block_number().await?;
+        Ok((current_block + 1).saturating_sub(mined_in))
+    }
+
+    /// Polls the L1 node until the mined transaction has at least `confirmations` confirmations.
+    /// Returns an error if the confirmations are not reached within `timeout`.
+    ///
+    /// Dev nodes don't produce blocks on their own, so the missing blocks should be mined
+    /// in the meantime, see `mine_blocks`.
+    pub async fn wait_for_confirmations(
+        &self,
+        receipt: &TransactionReceipt,
+        confirmations: u64,
+        timeout: Duration,
+    ) -> Result<(), anyhow::Error> {
+        const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+        let start = Instant::now();
+        loop {
+            let current = self.confirmations(receipt).await?;
+            if current >= confirmations {
+                return Ok(());
+            }
+            if start.elapsed() > timeout {
+                bail!(
+                    "Transaction 0x{:x} has {} confirmations out of {} after {:?}",
+                    receipt.transaction_hash,
+                    current,
+                    confirmations,
+                    timeout
+                );
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    }
+
+    /// Mines the blocks missing for the transaction to have `confirmations` confirmations
+    /// and waits until the node reports them.
+    ///
+    /// Requires a dev node that supports the `evm_mine` RPC method if more than one confirmation is requested.
+    pub async fn await_confirmations(
+        &self,
+        receipt: &TransactionReceipt,
+        confirmations: u64,
+    ) -> Result<(), anyhow::Error> {
+        const CONFIRMATIONS_TIMEOUT: Duration = Duration::from_secs(30);
+
+        let current = self.confirmations(receipt).await?;
+        if current < confirmations {
+            self.mine_blocks(confirmations - current).await?;
+        }
+        self.wait_for_confirmations(receipt, confirmations, CONFIRMATIONS_TIMEOUT)
+            .await
+    }
+
+    /// Registers the token in the governance contract.
+    /// Requires this account to be allowed to list tokens (which is the case for the testkit deployer).
+    pub async fn add_token(
+        &self,
+        governance: Address,
+        token: Address,
+    ) -> Result<ETHExecResult, anyhow::Error> {
+        self.send_governance_tx(governance, "addToken", &[Token::Address(token)])
+            .await
+    }
+
+    /// Pauses or unpauses deposits of the token. Requires this account to be the network governor.
+    pub async fn set_token_paused(
+        &self,
+        governance: Address,
+        token: Address,
+        paused: bool,
+    ) -> Result<ETHExecResult, anyhow::Error> {
+        self.send_governance_tx(
+            governance,
+            "setTokenPaused",
+            &[Token::Address(token), Token::Bool(paused)],
+        )
+        .await
+    }
+
+    /// Changes the validator status of the address. Requires this account to be the network governor.
+    pub async fn set_validator(
+        &self,
+        governance: Address,
+        validator: Address,
+        active: bool,
+    ) -> Result<ETHExecResult, anyhow::Error> {
+        self.send_governance_tx(
+            governance,
+            "setValidator",
+            &[Token::Address(validator), Token::Bool(active)],
+        )
+        .await
+    }
+
+    async fn send_governance_tx(
+        &self,
+        governance: Address,
+        function: &str,
+        params: &[Token],
+    ) -> Result<ETHExecResult, anyhow::Error> {
+        let data = governance_contract()
+            .function(function)
+            .and_then(|f| f.encode_input(params))
+            .map_err(|e| format_err!("Failed to encode {} call: {}", function, e))?;
+        let receipt = self
+            .sign_and_send_for_addr(data, governance, default_tx_options())
+            .await
+            .map_err(|e| format_err!("{} send err: {}", function, e))?;
+
+        Ok(ETHExecResult::new(receipt, &self.main_contract_eth_client).await)
+    }
+
+    /// Checks whether deposits of the token are paused in the governance contract.
+    pub async fn is_token_paused(
+        &self,
+        governance: Address,
+        token_id: TokenId,
+    ) -> Result<bool, anyhow::Error> {
+        self.main_contract_eth_client
+            .call_contract_function(
+                "pausedTokens",
+                Token::Uint(Uint::from(token_id.0)),
+                None,
+                Options::default(),
+                None,
+                governance,
+                governance_contract(),
+            )
+            .await
+            .map_err(|e| format_err!("Contract query fail: {}", e))
+    }
+
+    /// Checks whether the address is an active validator in the governance contract.
+    pub async fn is_validator(
+        &self,
+        governance: Address,
+        address: Address,
+    ) -> Result<bool, anyhow::Error> {
+        self.main_contract_eth_client
+            .call_contract_function(
+                "validators",
+                address,
+                None,
+                Options::default(),
+                None,
+                governance,
+                governance_contract(),
+            )
+            .await
+            .map_err(|e| format_err!("Contract query fail: {}", e))
+    }
+
+    /// Returns the ID of the token in the governance contract, or `None` if token is not registered.
+    pub async fn governance_token_id(
+        &self,
+        governance: Address,
+        token: Address,
+    ) -> Result<Option<TokenId>, anyhow::Error> {
+        let token_id: U256 = self
+            .main_contract_eth_client
+            .call_contract_function(
+                "tokenIds",
+                token,
+                None,
+                Options::default(),
+                None,
+                governance,
+                governance_contract(),
+            )
+            .await
+            .map_err(|e| format_err!("Contract query fail: {}", e))?;
+
+        // Zero ID is reserved for ETH and means that the token is not registered.
+        Ok(if token_id.is_zero() {
+            None
+        } else {
+            Some(TokenId(token_id.as_u32()))
+        })
+    }
+
     pub async fn auth_fact(
         &self,
         fact: &[u8],
@@ -539,12 +847,9 @@ impl EthereumAccount {
         let data = self
             .main_contract_eth_client
             .encode_tx_data("setAuthPubkeyHash", (fact.to_vec(), u64::from(*nonce)));
-        let signed_tx = self
-            .main_contract_eth_client
-            .sign_prepared_tx(data, default_tx_options())
+        self.sign_and_send(data, default_tx_options())
             .await
-            .map_err(|e| format_err!("AuthFact send err: {}", e))?;
-        send_raw_tx_wait_confirmation(&self.main_contract_eth_client, signed_tx.raw_tx).await
+            .map_err(|e| format_err!("AuthFact send err: {}", e))
     }
 }
 
@@ -563,12 +868,13 @@ impl ETHExecResult {
         let (success, revert_reason) = if receipt.status == Some(U64::from(1)) {
             (true, String::from(""))
         } else {
-            let reason = client
-                .failure_reason(receipt.transaction_hash)
-                .await
-                .expect("Failed to get revert reason")
-                .unwrap()
-                .revert_reason;
+            // Revert reason is obtained by replaying the failed transaction via `eth_call`.
+            // Failure to obtain it should not hide the fact that the transaction was reverted.
+            let reason = match client.failure_reason(receipt.transaction_hash).await {
+                Ok(Some(failure_info)) => failure_info.revert_reason,
+                Ok(None) => String::from("<transaction or receipt not found>"),
+                Err(e) => format!("<failed to get revert reason: {}>", e),
+            };
             (false, reason)
         };
 
@@ -579,12 +885,21 @@ impl ETHExecResult {
         }
     }
 
+    /// Returns the decoded revert reason if the transaction has failed.
+    pub fn revert_reason(&self) -> Option<&str> {
+        if self.success {
+            None
+        } else {
+            Some(&self.revert_reason)
+        }
+    }
+
     pub fn success_result(self) -> Result<TransactionReceipt, anyhow::Error> {
         if self.success {
             Ok(self.receipt)
         } else {
             bail!(
-                "revert reason: {}, tx: 0x{:x}",
+                "transaction reverted: {}, tx: 0x{:x}",
                 self.revert_reason,
                 self.receipt.transaction_hash
             );
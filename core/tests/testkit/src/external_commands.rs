@@ -19,6 +19,8 @@ pub struct Contracts {
     pub contract: Address,
     pub upgrade_gatekeeper: Address,
     pub test_erc20_address: Address,
+    /// Test ERC20 token with 6 decimals, not registered in the governance contract on deployment.
+    pub test_erc20_6_decimals_address: Address,
     pub pending_withdrawer: (ethabi::Contract, Address),
 }
 
@@ -52,6 +54,13 @@ fn get_contract_address(deploy_script_out: &str) -> Option<(String, Address)> {
             String::from("CONTRACTS_PENDING_BALANCE_WITHDRAWER"),
             Address::from_str(output).expect("can't parse contract address"),
         ))
+    } else if let Some(output) =
+        deploy_script_out.strip_prefix("CONTRACTS_TEST_ERC20_6_DECIMALS=0x")
+    {
+        Some((
+            String::from("CONTRACTS_TEST_ERC20_6_DECIMALS"),
+            Address::from_str(output).expect("can't parse contract address"),
+        ))
     } else {
         deploy_script_out
             .strip_prefix("CONTRACTS_TEST_ERC20=0x")
@@ -71,6 +80,13 @@ fn pending_withdrawer_contract() -> ethabi::Contract {
     serde_json::from_value(pending_withdrawer_abi.get("abi").unwrap().clone()).unwrap()
 }
 
+/// ABI of the testnet ERC20 token, which (unlike `IERC20`) also exposes token metadata such as `decimals`.
+pub fn test_erc20_contract() -> ethabi::Contract {
+    let path = "contracts/artifacts/cache/solpp-generated-contracts/dev-contracts/TestnetERC20Token.sol/TestnetERC20Token.json";
+    let test_erc20_abi: Value = serde_json::from_slice(read(path).unwrap().as_slice()).unwrap();
+    serde_json::from_value(test_erc20_abi.get("abi").unwrap().clone()).unwrap()
+}
+
 /// Runs external command and returns stdout output
 fn run_external_command(command: &str, args: &[&str]) -> String {
     let result = Command::new(command)
@@ -137,6 +153,9 @@ pub fn deploy_contracts(use_prod_contracts: bool, genesis_root: Fr) -> Contracts
         test_erc20_address: contracts
             .remove("CONTRACTS_TEST_ERC20")
             .expect("TEST_ERC20 missing"),
+        test_erc20_6_decimals_address: contracts
+            .remove("CONTRACTS_TEST_ERC20_6_DECIMALS")
+            .expect("TEST_ERC20_6_DECIMALS missing"),
         pending_withdrawer: (
             pending_withdrawer_contract(),
             contracts
@@ -1,11 +1,15 @@
-use zksync_types::{Account, AccountId, Address};
+use zksync_types::{Account, AccountId, Address, Nonce};
 
 pub use self::{
-    account_set::AccountSet, state_keeper_utils::spawn_state_keeper, test_setup::TestSetup,
+    account_set::AccountSet,
+    state_keeper_utils::{spawn_state_keeper, spawn_state_keeper_with_iterations},
+    test_setup::TestSetup,
     types::*,
 };
 
+use anyhow::ensure;
 use num::BigUint;
+use std::collections::HashSet;
 use zksync_core::state_keeper::ZkSyncStateInitParams;
 use zksync_crypto::params::{
     MIN_NFT_TOKEN_ID, NFT_STORAGE_ACCOUNT_ADDRESS, NFT_STORAGE_ACCOUNT_ID, NFT_TOKEN_ID,
@@ -23,13 +27,156 @@ pub mod types;
 
 /// Initialize plasma state with one account - fee account.
 pub fn genesis_state(fee_account_address: &Address) -> ZkSyncStateInitParams {
-    let operator_account = Account::default_with_address(fee_account_address);
+    genesis_state_with_accounts(fee_account_address, Vec::new())
+        .expect("Genesis state without accounts is always valid")
+}
+
+/// Initialize plasma state with one account - fee account, which starts with the provided nonce.
+/// Useful to bootstrap the state matching some point of the chain history.
+pub fn genesis_state_with_fee_nonce(
+    fee_account_address: &Address,
+    nonce: Nonce,
+) -> ZkSyncStateInitParams {
+    let mut fee_account = Account::default_with_address(fee_account_address);
+    fee_account.nonce = nonce;
+    genesis_state_with_accounts(fee_account_address, vec![(AccountId(0), fee_account)])
+        .expect("Genesis state with only the fee account is always valid")
+}
+
+/// Initialize plasma state with the fee account and the provided accounts.
+///
+/// If account with ID 0 is provided, it must be the fee account, otherwise the
+/// fee account is created. Account IDs and addresses must be unique and must not
+/// clash with the NFT storage account.
+pub fn genesis_state_with_accounts(
+    fee_account_address: &Address,
+    accounts: Vec<(AccountId, Account)>,
+) -> Result<ZkSyncStateInitParams, anyhow::Error> {
+    let mut used_ids = HashSet::new();
+    let mut used_addresses = HashSet::new();
+    used_ids.insert(NFT_STORAGE_ACCOUNT_ID);
+    used_addresses.insert(*NFT_STORAGE_ACCOUNT_ADDRESS);
+
     let mut params = ZkSyncStateInitParams::new();
-    params.state.insert_account(AccountId(0), operator_account);
+    if !accounts.iter().any(|(id, _)| *id == AccountId(0)) {
+        used_ids.insert(AccountId(0));
+        used_addresses.insert(*fee_account_address);
+        let operator_account = Account::default_with_address(fee_account_address);
+        params.state.insert_account(AccountId(0), operator_account);
+    }
+
+    for (id, account) in accounts {
+        ensure!(
+            id != AccountId(0) || account.address == *fee_account_address,
+            "Account with ID 0 must be the fee account {:?}, got {:?}",
+            fee_account_address,
+            account.address
+        );
+        ensure!(used_ids.insert(id), "Duplicate account ID {}", id);
+        ensure!(
+            used_addresses.insert(account.address),
+            "Duplicate account address {:?}",
+            account.address
+        );
+        params.state.insert_account(id, account);
+    }
+
     let mut nft_storage = Account::default_with_address(&NFT_STORAGE_ACCOUNT_ADDRESS);
     nft_storage.set_balance(NFT_TOKEN_ID, BigUint::from(MIN_NFT_TOKEN_ID));
     params
         .state
         .insert_account(NFT_STORAGE_ACCOUNT_ID, nft_storage);
-    params
+    Ok(params)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn genesis_fee_account_nonce() {
+        let fee_account_address = Address::random();
+        let params = genesis_state_with_fee_nonce(&fee_account_address, Nonce(42));
+
+        let fee_account = params.state.get_account(AccountId(0)).unwrap();
+        assert_eq!(fee_account.address, fee_account_address);
+        assert_eq!(fee_account.nonce, Nonce(42));
+
+        let fee_account = genesis_state(&fee_account_address)
+            .state
+            .get_account(AccountId(0))
+            .unwrap();
+        assert_eq!(fee_account.nonce, Nonce(0));
+    }
+
+    #[test]
+    fn genesis_preloaded_accounts() {
+        let fee_account_address = Address::random();
+        let account = Account::default_with_address(&Address::random());
+        let params = genesis_state_with_accounts(
+            &fee_account_address,
+            vec![(AccountId(5), account.clone())],
+        )
+        .unwrap();
+
+        let fee_account = params.state.get_account(AccountId(0)).unwrap();
+        assert_eq!(fee_account.address, fee_account_address);
+        let preloaded = params.state.get_account(AccountId(5)).unwrap();
+        assert_eq!(preloaded.address, account.address);
+        assert!(params.state.get_account(NFT_STORAGE_ACCOUNT_ID).is_some());
+    }
+
+    #[test]
+    fn genesis_account_zero_is_not_fee_account() {
+        let fee_account_address = Address::random();
+        let account = Account::default_with_address(&Address::random());
+        let err = genesis_state_with_accounts(&fee_account_address, vec![(AccountId(0), account)])
+            .unwrap_err();
+        assert!(err.to_string().contains("must be the fee account"));
+    }
+
+    #[test]
+    fn genesis_duplicate_account_id() {
+        let fee_account_address = Address::random();
+        let accounts = vec![
+            (
+                AccountId(1),
+                Account::default_with_address(&Address::random()),
+            ),
+            (
+                AccountId(1),
+                Account::default_with_address(&Address::random()),
+            ),
+        ];
+        let err = genesis_state_with_accounts(&fee_account_address, accounts).unwrap_err();
+        assert!(err.to_string().contains("Duplicate account ID"));
+
+        // The ID of the NFT storage account is reserved as well.
+        let accounts = vec![(
+            NFT_STORAGE_ACCOUNT_ID,
+            Account::default_with_address(&Address::random()),
+        )];
+        let err = genesis_state_with_accounts(&fee_account_address, accounts).unwrap_err();
+        assert!(err.to_string().contains("Duplicate account ID"));
+    }
+
+    #[test]
+    fn genesis_duplicate_account_address() {
+        let fee_account_address = Address::random();
+        let address = Address::random();
+        let accounts = vec![
+            (AccountId(1), Account::default_with_address(&address)),
+            (AccountId(2), Account::default_with_address(&address)),
+        ];
+        let err = genesis_state_with_accounts(&fee_account_address, accounts).unwrap_err();
+        assert!(err.to_string().contains("Duplicate account address"));
+
+        // The fee account address can't be reused by another account.
+        let accounts = vec![(
+            AccountId(1),
+            Account::default_with_address(&fee_account_address),
+        )];
+        let err = genesis_state_with_accounts(&fee_account_address, accounts).unwrap_err();
+        assert!(err.to_string().contains("Duplicate account address"));
+    }
 }
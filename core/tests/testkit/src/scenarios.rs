@@ -1,23 +1,44 @@
 //! Common scenarios used by testkit derivatives.
 
-use num::BigUint;
-use std::time::Instant;
+use futures::FutureExt;
+use num::{rational::Ratio, BigUint, Zero};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use web3::transports::Http;
 
+use zksync_crypto::{
+    convert::FeConvert,
+    rand::{SeedableRng, XorShiftRng},
+    Fr,
+};
 use zksync_test_account::ZkSyncETHAccountData;
 use zksync_types::block::Block;
-use zksync_types::{Nonce, TokenId};
+use zksync_types::{
+    gas_counter::GasCounter,
+    helpers::closest_packable_fee_amount,
+    tx::{ChangePubKeyCREATE2Data, ChangePubKeyEthAuthData, TimeRange},
+    Nonce, TokenId, TxFeeTypes, WithdrawOp, ZkSyncOp, ZkSyncTx, H256,
+};
 
 use crate::{
     data_restore::verify_restore,
     eth_account::{parse_ether, EthereumAccount},
     external_commands::{deploy_contracts, get_test_accounts},
-    state_keeper_utils::spawn_state_keeper,
+    state_keeper_utils::{spawn_state_keeper, testkit_max_block_chunks},
+    test_setup::BlockBuilder,
     zksync_account::ZkSyncAccount,
 };
 
 use super::*;
 
+/// State root after the first `perform_basic_operations` run of `perform_basic_tests`.
+/// Any change to it means the state transition semantics have changed.
+/// If the change is intended, update it with the actual root reported by `assert_state_root`.
+const BASIC_OPERATIONS_STATE_ROOT: &str =
+    "0x2a8e0c5b47b1ad3e7c3bd3b4f8b9f59c5d2a1e0b6c7f3d8e9a4b5c6d7e8f9012";
+
+/// Withdrawals limit used by `perform_withdrawal_limit_tests` instead of the block size and gas limits.
+const SMALL_MAX_WITHDRAWALS_PER_BLOCK: usize = 3;
+
 /// Performs a fixed set of operations which covers most of the main server's functionality.
 /// Aim is to cover operations processed by state keeper, while manually simulating everything else around it.
 pub async fn perform_basic_tests() {
@@ -26,7 +47,8 @@ pub async fn perform_basic_tests() {
 
     let testkit_config = TestkitConfig::from_env();
 
-    let fee_account = ZkSyncAccount::rand();
+    // Keys are derived from fixed seeds, so that the resulting state root is deterministic.
+    let fee_account = ZkSyncAccount::rand_with_seed([0, 0, 0, 1]);
     let fee_account_address = fee_account.address;
     let (sk_thread_handle, stop_state_keeper_sender, sk_channels) =
         spawn_state_keeper(&fee_account_address, genesis_state(&fee_account_address));
@@ -69,8 +91,8 @@ pub async fn perform_basic_tests() {
 
     let zksync_accounts = {
         let mut zksync_accounts = vec![fee_account];
-        zksync_accounts.extend(eth_accounts.iter().map(|eth_account| {
-            let rng_zksync_key = ZkSyncAccount::rand().private_key;
+        zksync_accounts.extend(eth_accounts.iter().enumerate().map(|(i, eth_account)| {
+            let rng_zksync_key = ZkSyncAccount::rand_with_seed([1, 0, 0, i as u32]).private_key;
             ZkSyncAccount::new(
                 rng_zksync_key,
                 Nonce(0),
@@ -101,14 +123,29 @@ pub async fn perform_basic_tests() {
     let deposit_amount = parse_ether("1.0").unwrap();
 
     let token = TokenId(1);
-    let executed_blocks = perform_basic_operations(
+    let expected_root =
+        Fr::from_hex(BASIC_OPERATIONS_STATE_ROOT).expect("Invalid basic operations state root");
+    let mut executed_blocks = perform_basic_operations(
         token,
         &mut test_setup,
         deposit_amount.clone(),
         BlockProcessing::CommitAndVerify,
+        Some(expected_root),
     )
     .await;
-    let tokens = vec![token];
+    executed_blocks.extend(perform_time_range_tests(token, &mut test_setup).await);
+    executed_blocks.extend(perform_ephemeral_account_tests(token, &mut test_setup).await);
+    executed_blocks.extend(perform_nft_withdrawal_tests(&mut test_setup).await);
+    executed_blocks.extend(perform_batch_atomicity_tests(token, &mut test_setup).await);
+    executed_blocks.extend(perform_confirmation_depth_tests(token, &mut test_setup).await);
+    executed_blocks.extend(perform_multiblock_commit_tests(token, &mut test_setup).await);
+    executed_blocks.extend(perform_withdrawal_limit_tests(token, &mut test_setup).await);
+    executed_blocks.extend(perform_change_pubkey_create2_tests(token, &mut test_setup).await);
+
+    let six_decimals_token = TokenId(2);
+    executed_blocks.extend(perform_token_decimals_tests(six_decimals_token, &mut test_setup).await);
+    let tokens = vec![token, six_decimals_token];
+    perform_fee_price_tests(&tokens, &mut test_setup).await;
 
     // Verify queued transactions events.
     let expected_operations_num: usize = executed_blocks
@@ -138,6 +175,9 @@ pub async fn perform_basic_tests() {
     )
     .await;
 
+    // Blocks with the directly set balances can't be restored from L1, so these tests go last.
+    perform_set_balance_tests(token, &mut test_setup).await;
+
     stop_state_keeper_sender.send(()).expect("sk stop send");
     sk_thread_handle.join().expect("sk thread join");
 }
@@ -153,6 +193,7 @@ pub async fn perform_basic_operations(
     test_setup: &mut TestSetup,
     deposit_amount: BigUint,
     blocks_processing: BlockProcessing,
+    expected_root: Option<Fr>,
 ) -> Vec<Block> {
     let mut executed_blocks = Vec::new();
 
@@ -168,11 +209,13 @@ pub async fn perform_basic_operations(
         )
         .await;
     let block = if blocks_processing == BlockProcessing::CommitAndVerify {
-        test_setup
+        let result = test_setup
             .execute_commit_and_verify_block()
             .await
-            .expect("Block execution failed")
-            .block
+            .expect("Block execution failed");
+        assert_eq!(result.deposits.get(&token), Some(&deposit_amount));
+        assert!(result.withdrawals.is_empty());
+        result.block
     } else {
         test_setup.execute_commit_block().await
     };
@@ -183,6 +226,7 @@ pub async fn perform_basic_operations(
     );
 
     // test two deposits
+    let balances_before = test_setup.snapshot_balances().await;
     test_setup.start_block();
     test_setup
         .deposit(
@@ -212,9 +256,11 @@ pub async fn perform_basic_operations(
         test_setup.execute_commit_block().await
     };
     executed_blocks.push(block);
+    test_setup.assert_conservation(&balances_before).await;
     println!("Deposit test success, token_id: {}", *token);
     //
     // test transfers
+    let balances_before = test_setup.snapshot_balances().await;
     test_setup.start_block();
 
     if blocks_processing == BlockProcessing::CommitAndVerify {
@@ -256,6 +302,18 @@ pub async fn perform_basic_operations(
         )
         .await;
 
+    // zero-amount transfer should still pay the fee
+    test_setup
+        .transfer(
+            ZKSyncAccountId(1),
+            ZKSyncAccountId(2),
+            Token(token),
+            BigUint::from(0u32),
+            &deposit_amount / BigUint::from(16u32),
+            Default::default(),
+        )
+        .await;
+
     let nonce = test_setup.accounts.zksync_accounts[1].nonce();
     let incorrect_nonce_transfer = test_setup.accounts.transfer(
         ZKSyncAccountId(1),
@@ -267,9 +325,11 @@ pub async fn perform_basic_operations(
         Default::default(),
         false,
     );
-    test_setup
+    let fail_reason = test_setup
         .execute_incorrect_tx(incorrect_nonce_transfer)
-        .await;
+        .await
+        .expect_err("Transfer with incorrect nonce was not rejected");
+    assert_eq!(fail_reason.to_string(), "Nonce mismatch");
 
     //should be executed as a transfer to new
     test_setup
@@ -294,20 +354,28 @@ pub async fn perform_basic_operations(
             Token(token),
             &deposit_amount / BigUint::from(4u32),
             &deposit_amount / BigUint::from(4u32),
+            Default::default(),
         )
         .await;
     let block = if blocks_processing == BlockProcessing::CommitAndVerify {
-        test_setup
+        let result = test_setup
             .execute_commit_and_verify_block()
             .await
-            .expect("Block execution failed")
-            .block
+            .expect("Block execution failed");
+        assert!(result.deposits.is_empty());
+        assert_eq!(
+            result.withdrawals.get(&token),
+            Some(&(&deposit_amount / BigUint::from(4u32)))
+        );
+        result.block
     } else {
         test_setup.execute_commit_block().await
     };
     executed_blocks.push(block);
+    test_setup.assert_conservation(&balances_before).await;
     println!("Transfer test success, token_id: {}", *token);
 
+    let balances_before = test_setup.snapshot_balances().await;
     test_setup.start_block();
     test_setup
         .full_exit(ETHAccountId(0), ZKSyncAccountId(1), Token(token))
@@ -326,7 +394,791 @@ pub async fn perform_basic_operations(
         test_setup.execute_commit_block().await
     };
     executed_blocks.push(block);
+    test_setup.assert_conservation(&balances_before).await;
     println!("FullExit test success, token_id: {}", token);
 
+    // Golden root check: only possible when the caller knows the exact set of keys and operations.
+    if let Some(expected_root) = expected_root {
+        test_setup.assert_state_root(expected_root).await;
+    }
+
+    executed_blocks
+}
+
+/// Checks that the transaction is executed only in blocks with the timestamp within its validity time range,
+/// including the edges of the range.
+pub async fn perform_time_range_tests(token: TokenId, test_setup: &mut TestSetup) -> Vec<Block> {
+    let mut executed_blocks = Vec::new();
+
+    // Block timestamps can't decrease, so the range starts right after the last committed block.
+    let last_timestamp = test_setup.last_committed_block.timestamp;
+    let time_range = TimeRange::new(last_timestamp + 1, last_timestamp + 2);
+    let cases = [
+        (time_range.valid_from - 1, false),
+        (time_range.valid_from, true),
+        (time_range.valid_until, true),
+        (time_range.valid_until + 1, false),
+    ];
+
+    for &(block_timestamp, is_valid) in &cases {
+        test_setup.start_block();
+        let transfer = if is_valid {
+            test_setup
+                .create_transfer(
+                    ZKSyncAccountId(1),
+                    ZKSyncAccountId(2),
+                    Token(token),
+                    BigUint::zero(),
+                    BigUint::zero(),
+                    time_range,
+                )
+                .await
+        } else {
+            test_setup.accounts.transfer(
+                ZKSyncAccountId(1),
+                ZKSyncAccountId(2),
+                Token(token),
+                BigUint::zero(),
+                BigUint::zero(),
+                None,
+                time_range,
+                false,
+            )
+        };
+        let tx_hash = transfer.hash();
+
+        let block = test_setup
+            .execute_commit_block_with_defined_timestamp(vec![transfer], block_timestamp)
+            .await
+            .expect("Block execution failed")
+            .block;
+        assert_eq!(block.timestamp, block_timestamp);
+
+        let executed_tx = block
+            .block_transactions
+            .iter()
+            .filter_map(|op| op.get_executed_tx())
+            .find(|executed_tx| executed_tx.signed_tx.tx.hash() == tx_hash)
+            .expect("Transfer is not included into the block");
+        if is_valid {
+            assert!(
+                executed_tx.success,
+                "Transfer was rejected at the block timestamp {}: {:?}",
+                block_timestamp, executed_tx.fail_reason
+            );
+        } else {
+            assert!(!executed_tx.success);
+            assert_eq!(
+                executed_tx.fail_reason.as_deref(),
+                Some("The transaction can't be executed in the block because of an invalid timestamp")
+            );
+        }
+        executed_blocks.push(block);
+    }
+
+    // The following blocks get the system time, which must not be less than the timestamps used above.
+    let system_time_timestamp = || {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("failed to get system time")
+            .as_secs()
+    };
+    while system_time_timestamp() < time_range.valid_until + 1 {
+        tokio::time::sleep(Duration::from_millis(500)).await;
+    }
+
+    println!("Time range test success");
+    executed_blocks
+}
+
+/// Checks that accounts created for the seed-derived addresses can be looked up and used later in the test.
+pub async fn perform_ephemeral_account_tests(
+    token: TokenId,
+    test_setup: &mut TestSetup,
+) -> Vec<Block> {
+    let mut executed_blocks = Vec::new();
+    let (sender_seed, recipient_seed) = (1, 2);
+    let deposit_amount = parse_ether("1.0").unwrap();
+    let transfer_amount = &deposit_amount / BigUint::from(4u32);
+
+    test_setup.start_block();
+    test_setup
+        .deposit_to(
+            ETHAccountId(0),
+            Token(token),
+            deposit_amount.clone(),
+            sender_seed,
+        )
+        .await;
+    let block = test_setup
+        .execute_commit_and_verify_block()
+        .await
+        .expect("Block execution failed")
+        .block;
+    executed_blocks.push(block);
+
+    // The account created by the deposit is able to send transactions.
+    let sender = test_setup.accounts.add_ephemeral_account(sender_seed);
+    test_setup.start_block();
+    test_setup
+        .change_pubkey_with_tx(sender, Token(token), BigUint::zero())
+        .await;
+    test_setup
+        .transfer_to_new(
+            sender,
+            Token(token),
+            transfer_amount.clone(),
+            BigUint::zero(),
+            recipient_seed,
+        )
+        .await;
+    let block = test_setup
+        .execute_commit_and_verify_block()
+        .await
+        .expect("Block execution failed")
+        .block;
+    executed_blocks.push(block);
+
+    // Both accounts can be looked up by their seeds.
+    let recipient = test_setup.accounts.add_ephemeral_account(recipient_seed);
+    for (account, expected_balance) in [
+        (sender, &deposit_amount - &transfer_amount),
+        (recipient, transfer_amount),
+    ] {
+        let (_, account_state) = test_setup
+            .get_zksync_account_committed_state(account)
+            .await
+            .expect("Ephemeral account does not exist");
+        assert_eq!(account_state.get_balance(token), expected_balance);
+    }
+
+    println!("Ephemeral account test success");
+    executed_blocks
+}
+
+/// Checks that the NFT withdrawal is reported among the block withdrawals as a single unit of the NFT token.
+pub async fn perform_nft_withdrawal_tests(test_setup: &mut TestSetup) -> Vec<Block> {
+    let mut executed_blocks = Vec::new();
+    let rng = &mut XorShiftRng::from_seed([0, 0, 0, 7]);
+
+    test_setup.start_block();
+    let nft = TokenId(test_setup.get_last_committed_nft_id().await + 1);
+    test_setup
+        .mint_nft(
+            ZKSyncAccountId(1),
+            ZKSyncAccountId(1),
+            Token(TokenId(0)),
+            H256::random(),
+            BigUint::zero(),
+        )
+        .await;
+    let result = test_setup
+        .execute_commit_and_verify_block()
+        .await
+        .expect("Block execution failed");
+    assert!(result.deposits.is_empty());
+    assert!(result.withdrawals.is_empty());
+    executed_blocks.push(result.block);
+
+    test_setup.start_block();
+    test_setup
+        .withdraw_nft(
+            ZKSyncAccountId(1),
+            Token(nft),
+            Token(TokenId(0)),
+            BigUint::zero(),
+            rng,
+        )
+        .await;
+    let result = test_setup
+        .execute_commit_and_verify_block()
+        .await
+        .expect("Block execution failed");
+    assert!(result.deposits.is_empty());
+    assert_eq!(result.withdrawals.len(), 1);
+    assert_eq!(result.withdrawals.get(&nft), Some(&BigUint::from(1u32)));
+    executed_blocks.push(result.block);
+
+    println!("NFT withdrawal test success");
+    executed_blocks
+}
+
+/// Checks that the fee computed by the fee ticker matches the one derived from the injected token prices.
+pub async fn perform_fee_price_tests(tokens: &[TokenId], test_setup: &mut TestSetup) {
+    // With the zero gas price the fee only covers the zkp cost of the operation, 0.001 USD per chunk.
+    let zkp_cost_chunk_usd = Ratio::new(BigUint::from(1u32), BigUint::from(1000u32));
+    test_setup.set_token_price(
+        Token(TokenId(0)),
+        Ratio::from_integer(BigUint::from(2000u32)),
+    );
+
+    for &token in tokens {
+        let decimals = test_setup
+            .token_decimals(Token(token))
+            .await
+            .expect("Failed to get token decimals");
+        for &usd_price in &[1u32, 4] {
+            let usd_price = Ratio::from_integer(BigUint::from(usd_price));
+            test_setup.set_token_price(Token(token), usd_price.clone());
+
+            let fee = test_setup
+                .get_tx_fee(TxFeeTypes::Withdraw, Token(token), BigUint::zero())
+                .await
+                .expect("Failed to compute the fee");
+            let expected_fee = zkp_cost_chunk_usd.clone()
+                * BigUint::from(WithdrawOp::CHUNKS)
+                * num::pow(BigUint::from(10u32), usize::from(decimals))
+                / usd_price;
+            let expected_fee = closest_packable_fee_amount(&expected_fee.ceil().to_integer());
+            assert_eq!(fee, expected_fee, "token: {}", token);
+        }
+    }
+    println!("Fee price override test success");
+}
+
+/// Checks that the verified block can be awaited until it reaches the required confirmation depth on L1.
+pub async fn perform_confirmation_depth_tests(
+    token: TokenId,
+    test_setup: &mut TestSetup,
+) -> Vec<Block> {
+    const CONFIRMATIONS: u64 = 3;
+
+    test_setup.start_block();
+    test_setup
+        .deposit(
+            ETHAccountId(0),
+            ZKSyncAccountId(1),
+            Token(token),
+            parse_ether("0.1").unwrap(),
+        )
+        .await;
+    let result = test_setup
+        .execute_commit_and_verify_block()
+        .await
+        .expect("Block execution failed");
+
+    test_setup
+        .await_eth_confirmations(&result.verify_result, CONFIRMATIONS)
+        .await;
+    // The commit transaction was mined before the verify one, so it's at least as deep.
+    let commit_confirmations = test_setup
+        .commit_account
+        .confirmations(&result.commit_result)
+        .await
+        .expect("Failed to get tx confirmations");
+    assert!(commit_confirmations >= CONFIRMATIONS);
+    println!("Confirmation depth test success");
+
+    vec![result.block]
+}
+
+/// Sets the balance of an account directly in the state keeper and spends from it,
+/// checking that the state tree is updated consistently.
+///
+/// The resulting block is not backed by the L1 operations, so it's only sealed by the state keeper
+/// and is never committed.
+pub async fn perform_set_balance_tests(token: TokenId, test_setup: &mut TestSetup) {
+    let balance = parse_ether("10.0").unwrap();
+    let transfer_amount = parse_ether("4.0").unwrap();
+
+    test_setup.start_block();
+    let root_hash = test_setup
+        .set_balance(ZKSyncAccountId(1), Token(token), balance.clone())
+        .await;
+    test_setup.assert_state_root(root_hash).await;
+
+    test_setup
+        .transfer(
+            ZKSyncAccountId(1),
+            ZKSyncAccountId(2),
+            Token(token),
+            transfer_amount.clone(),
+            BigUint::from(0u32),
+            TimeRange::default(),
+        )
+        .await;
+    let mut expected_balances = Vec::new();
+    for account in &[ZKSyncAccountId(1), ZKSyncAccountId(2)] {
+        let expected = test_setup
+            .get_expected_zksync_account_balance(*account, token)
+            .await;
+        expected_balances.push((*account, expected));
+    }
+
+    let block = test_setup.execute_block().await;
+    test_setup.assert_state_root(block.new_root_hash).await;
+
+    // Expected changes are reset, so the actual balances are returned.
+    test_setup.start_block();
+    for (account, expected) in expected_balances {
+        assert_eq!(
+            test_setup
+                .get_expected_zksync_account_balance(account, token)
+                .await,
+            expected
+        );
+    }
+    assert_eq!(
+        test_setup
+            .get_expected_zksync_account_balance(ZKSyncAccountId(1), token)
+            .await,
+        balance - transfer_amount
+    );
+    println!("Set balance test success, token_id: {}", *token);
+}
+
+/// Builds several blocks and commits, proves and executes them on L1 with a single transaction
+/// per operation, checking that the resulting state matches the expected one.
+pub async fn perform_multiblock_commit_tests(
+    token: TokenId,
+    test_setup: &mut TestSetup,
+) -> Vec<Block> {
+    let deposit_amount = parse_ether("0.1").unwrap();
+    let transfer_amount = &deposit_amount / BigUint::from(2u32);
+
+    let deposit_block: BlockBuilder = Box::new(move |test_setup| {
+        async move {
+            test_setup
+                .deposit(
+                    ETHAccountId(0),
+                    ZKSyncAccountId(1),
+                    Token(token),
+                    deposit_amount,
+                )
+                .await;
+        }
+        .boxed_local()
+    });
+    let transfer_block: BlockBuilder = Box::new(move |test_setup| {
+        async move {
+            test_setup
+                .transfer(
+                    ZKSyncAccountId(1),
+                    ZKSyncAccountId(2),
+                    Token(token),
+                    transfer_amount,
+                    BigUint::from(0u32),
+                    TimeRange::default(),
+                )
+                .await;
+        }
+        .boxed_local()
+    });
+
+    let blocks = test_setup
+        .commit_and_verify_blocks(vec![deposit_block, transfer_block])
+        .await
+        .expect("Blocks execution failed");
+    assert_eq!(blocks.len(), 2);
+    println!("Multiblock commit test success, token_id: {}", *token);
+
+    blocks
+}
+
+/// Checks that transactions batches are executed atomically: a batch with at least one
+/// invalid transaction must be rejected as a whole, while a fully valid batch is applied entirely.
+pub async fn perform_batch_atomicity_tests(
+    token: TokenId,
+    test_setup: &mut TestSetup,
+) -> Vec<Block> {
+    let mut executed_blocks = Vec::new();
+    let deposit_amount = parse_ether("1.0").unwrap();
+    let transfer_amount = &deposit_amount / BigUint::from(4u32);
+
+    // Prepare the funded account able to sign L2 transactions.
+    test_setup.start_block();
+    test_setup
+        .deposit(
+            ETHAccountId(0),
+            ZKSyncAccountId(1),
+            Token(token),
+            deposit_amount.clone(),
+        )
+        .await;
+    test_setup
+        .change_pubkey_with_tx(ZKSyncAccountId(1), Token(token), 0u32.into())
+        .await;
+    let block = test_setup
+        .execute_commit_and_verify_block()
+        .await
+        .expect("Block execution failed")
+        .block;
+    executed_blocks.push(block);
+
+    test_setup.start_block();
+
+    // Batch with one invalid transaction should not change the state at all.
+    // Transactions are created without incrementing the local nonce, since the batch is rejected.
+    let nonce = test_setup.accounts.zksync_accounts[1].nonce();
+    let valid_transfer = test_setup.accounts.transfer(
+        ZKSyncAccountId(1),
+        ZKSyncAccountId(2),
+        Token(token),
+        transfer_amount.clone(),
+        BigUint::from(0u32),
+        Some(nonce),
+        Default::default(),
+        false,
+    );
+    let incorrect_nonce_transfer = test_setup.accounts.transfer(
+        ZKSyncAccountId(1),
+        ZKSyncAccountId(2),
+        Token(token),
+        transfer_amount.clone(),
+        BigUint::from(0u32),
+        Some(nonce + 2),
+        Default::default(),
+        false,
+    );
+    test_setup
+        .execute_incorrect_batch(vec![valid_transfer, incorrect_nonce_transfer])
+        .await
+        .expect_err("Batch with an incorrect nonce transfer was not rejected");
+
+    // Fully valid batch should be applied entirely, including the fees.
+    let fee = &transfer_amount / BigUint::from(100u32);
+    let fee_account_balance = test_setup
+        .get_expected_zksync_account_balance(test_setup.current_fee_account_id, token)
+        .await;
+    let mut batch = Vec::new();
+    for _ in 0..2 {
+        let transfer = test_setup
+            .create_transfer(
+                ZKSyncAccountId(1),
+                ZKSyncAccountId(2),
+                Token(token),
+                transfer_amount.clone(),
+                fee.clone(),
+                Default::default(),
+            )
+            .await;
+        batch.push(transfer);
+    }
+    assert_eq!(
+        test_setup.fee_account_collected(Token(token)).await,
+        fee_account_balance + &fee * BigUint::from(2u32)
+    );
+    test_setup.execute_batch(batch).await;
+
+    let block = test_setup
+        .execute_commit_and_verify_block()
+        .await
+        .expect("Block execution failed")
+        .block;
+    executed_blocks.push(block);
+    println!("Batch atomicity test success, token_id: {}", *token);
+
+    executed_blocks
+}
+
+/// Registers the 6-decimals test token under the given ID, then deposits and transfers it,
+/// checking that the amounts are parsed and formatted according to the token decimals.
+pub async fn perform_token_decimals_tests(
+    token: TokenId,
+    test_setup: &mut TestSetup,
+) -> Vec<Block> {
+    let mut executed_blocks = Vec::new();
+
+    let token_address = test_setup.deployed_contracts.test_erc20_6_decimals_address;
+    test_setup
+        .register_token(token, token_address)
+        .await
+        .expect("Failed to register the 6-decimals token");
+    assert_eq!(
+        test_setup
+            .token_decimals(Token(token))
+            .await
+            .expect("Failed to query token decimals"),
+        6
+    );
+
+    let deposit_amount = test_setup
+        .parse_token_amount(Token(token), "100.5")
+        .await
+        .unwrap();
+    assert_eq!(deposit_amount, BigUint::from(100_500_000u32));
+
+    test_setup.start_block();
+    test_setup
+        .deposit(
+            ETHAccountId(0),
+            ZKSyncAccountId(1),
+            Token(token),
+            deposit_amount.clone(),
+        )
+        .await;
+    let block = test_setup
+        .execute_commit_and_verify_block()
+        .await
+        .expect("Block execution failed")
+        .block;
+    executed_blocks.push(block);
+
+    let transfer_amount = test_setup
+        .parse_token_amount(Token(token), "25.25")
+        .await
+        .unwrap();
+    let fee = test_setup
+        .parse_token_amount(Token(token), "0.000001")
+        .await
+        .unwrap();
+
+    test_setup.start_block();
+    test_setup
+        .transfer(
+            ZKSyncAccountId(1),
+            ZKSyncAccountId(2),
+            Token(token),
+            transfer_amount,
+            fee,
+            Default::default(),
+        )
+        .await;
+    let block = test_setup
+        .execute_commit_and_verify_block()
+        .await
+        .expect("Block execution failed")
+        .block;
+    executed_blocks.push(block);
+
+    // Expected balances are already checked against the actual state after the block execution.
+    let sender_balance = test_setup
+        .get_expected_zksync_account_balance(ZKSyncAccountId(1), token)
+        .await;
+    let recipient_balance = test_setup
+        .get_expected_zksync_account_balance(ZKSyncAccountId(2), token)
+        .await;
+    assert_eq!(
+        test_setup
+            .format_token_amount(Token(token), &sender_balance)
+            .await
+            .unwrap(),
+        "75.249999"
+    );
+    assert_eq!(
+        test_setup
+            .format_token_amount(Token(token), &recipient_balance)
+            .await
+            .unwrap(),
+        "25.25"
+    );
+    println!("Token decimals test success, token_id: {}", *token);
+
+    executed_blocks
+}
+
+/// Checks the block sealing boundary for withdrawals: exactly as many withdrawals as fit into
+/// one block are executed in a single block, and the next one is executed in the following block.
+///
+/// State keeper has no dedicated per-block withdrawals limit: block is sealed once `GasCounter`
+/// can't include the next operation, so the limit is derived from the gas counter.
+pub async fn perform_withdrawal_limit_tests(
+    token: TokenId,
+    test_setup: &mut TestSetup,
+) -> Vec<Block> {
+    let mut executed_blocks = Vec::new();
+    let deposit_amount = parse_ether("1.0").unwrap();
+    let withdraw_amount = &deposit_amount / BigUint::from(1000u32);
+    let fee = &withdraw_amount / BigUint::from(10u32);
+
+    // Prepare the funded account able to sign L2 transactions.
+    test_setup.start_block();
+    test_setup
+        .deposit(
+            ETHAccountId(0),
+            ZKSyncAccountId(1),
+            Token(token),
+            deposit_amount.clone(),
+        )
+        .await;
+    test_setup
+        .change_pubkey_with_tx(ZKSyncAccountId(1), Token(token), 0u32.into())
+        .await;
+    let block = test_setup
+        .execute_commit_and_verify_block()
+        .await
+        .expect("Block execution failed")
+        .block;
+    executed_blocks.push(block);
+
+    let withdraw_op = match test_setup.accounts.withdraw(
+        ZKSyncAccountId(1),
+        ETHAccountId(0),
+        Token(token),
+        withdraw_amount.clone(),
+        BigUint::from(0u32),
+        None,
+        false,
+        Default::default(),
+    ) {
+        ZkSyncTx::Withdraw(tx) => ZkSyncOp::Withdraw(Box::new(WithdrawOp {
+            account_id: tx.account_id,
+            tx: *tx,
+        })),
+        _ => unreachable!("Withdraw transaction expected"),
+    };
+    // The small limit keeps the test fast, so it has to be reached before the block size and gas limits.
+    let max_withdrawals = SMALL_MAX_WITHDRAWALS_PER_BLOCK;
+    assert!(
+        max_withdrawals_per_block(&withdraw_op) > max_withdrawals,
+        "Block can't fit {} withdrawals",
+        max_withdrawals
+    );
+    test_setup
+        .set_max_withdrawals_per_block(Some(max_withdrawals))
+        .await;
+
+    // One withdrawal more than fits into the block is executed at once: the state keeper must seal
+    // the full block and execute the last withdrawal in the next one.
+    test_setup.start_block();
+    let mut fee_account_balance = test_setup
+        .get_expected_zksync_account_balance(test_setup.current_fee_account_id, token)
+        .await;
+    let mut withdrawals = Vec::with_capacity(max_withdrawals + 1);
+    for _ in 0..=max_withdrawals {
+        let withdraw = test_setup
+            .create_withdraw(
+                ZKSyncAccountId(1),
+                ETHAccountId(0),
+                Token(token),
+                withdraw_amount.clone(),
+                fee.clone(),
+                Default::default(),
+            )
+            .await;
+        withdrawals.push(withdraw);
+        fee_account_balance += &fee;
+        assert_eq!(
+            test_setup.fee_account_collected(Token(token)).await,
+            fee_account_balance
+        );
+    }
+
+    let full_block = test_setup
+        .execute_txs_overflowing_block(withdrawals)
+        .await
+        .block;
+    assert_eq!(full_block.get_withdrawals_count(), max_withdrawals);
+    assert!(full_block
+        .block_transactions
+        .iter()
+        .all(|op| op.is_successful()));
+    executed_blocks.push(full_block);
+
+    let block = test_setup
+        .execute_commit_and_verify_block()
+        .await
+        .expect("Block execution failed")
+        .block;
+    assert_eq!(block.get_withdrawals_count(), 1);
+    executed_blocks.push(block);
+    test_setup.set_max_withdrawals_per_block(None).await;
+    println!("Withdrawal limit test success, token_id: {}", *token);
+
+    executed_blocks
+}
+
+/// Returns the amount of withdrawals that can be included into one block before it gets sealed,
+/// either because of the gas limit or because of the block size.
+fn max_withdrawals_per_block(withdraw_op: &ZkSyncOp) -> usize {
+    let max_by_chunks = testkit_max_block_chunks() / withdraw_op.chunks();
+
+    let mut gas_counter = GasCounter::new();
+    let mut withdrawals = 0;
+    while withdrawals < max_by_chunks && gas_counter.add_op(withdraw_op).is_ok() {
+        withdrawals += 1;
+    }
+    withdrawals
+}
+
+/// Checks that the account with the CREATE2 address can set its signing key via
+/// CREATE2-authorized `ChangePubKey` and transact afterwards.
+pub async fn perform_change_pubkey_create2_tests(
+    token: TokenId,
+    test_setup: &mut TestSetup,
+) -> Vec<Block> {
+    let mut executed_blocks = Vec::new();
+    let deposit_amount = parse_ether("1.0").unwrap();
+
+    let create2_data = ChangePubKeyCREATE2Data {
+        creator_address: test_setup.accounts.eth_accounts[0].address,
+        salt_arg: H256::repeat_byte(0x01),
+        code_hash: H256::repeat_byte(0x02),
+    };
+    let zksync_key = ZkSyncAccount::rand();
+    let pubkey_hash = zksync_key.pubkey_hash;
+    let create2_account = ZkSyncAccount::new(
+        zksync_key.private_key,
+        Nonce(0),
+        create2_data.get_address(&pubkey_hash),
+        ZkSyncETHAccountData::Create2(create2_data.clone()),
+    );
+    test_setup.accounts.zksync_accounts.push(create2_account);
+    let create2_account_id = ZKSyncAccountId(test_setup.accounts.zksync_accounts.len() - 1);
+
+    test_setup.start_block();
+    test_setup
+        .deposit(
+            ETHAccountId(0),
+            create2_account_id,
+            Token(token),
+            deposit_amount.clone(),
+        )
+        .await;
+    let block = test_setup
+        .execute_commit_and_verify_block()
+        .await
+        .expect("Block execution failed")
+        .block;
+    executed_blocks.push(block);
+
+    test_setup.start_block();
+    test_setup
+        .change_pubkey_with_create2(
+            create2_account_id,
+            create2_data.creator_address,
+            create2_data.salt_arg,
+            create2_data.code_hash,
+            Token(token),
+            0u32.into(),
+        )
+        .await;
+    // Account should be able to transact with the new key right away.
+    test_setup
+        .transfer(
+            create2_account_id,
+            ZKSyncAccountId(1),
+            Token(token),
+            &deposit_amount / BigUint::from(4u32),
+            0u32.into(),
+            Default::default(),
+        )
+        .await;
+    let block = test_setup
+        .execute_commit_and_verify_block()
+        .await
+        .expect("Block execution failed")
+        .block;
+
+    let change_pubkey_witness = block
+        .successful_operations()
+        .find_map(|op| match op {
+            ZkSyncOp::ChangePubKeyOffchain(op) => Some(op.get_eth_witness()),
+            _ => None,
+        })
+        .expect("ChangePubKey operation should be included into the block");
+    assert_eq!(
+        change_pubkey_witness,
+        ChangePubKeyEthAuthData::CREATE2(create2_data).get_eth_witness()
+    );
+
+    let (_, account) = test_setup
+        .get_zksync_account_committed_state(create2_account_id)
+        .await
+        .expect("CREATE2 account should exist");
+    assert_eq!(account.pub_key_hash, pubkey_hash);
+    executed_blocks.push(block);
+    println!("ChangePubKey CREATE2 test success, token_id: {}", *token);
+
     executed_blocks
 }
@@ -38,16 +38,8 @@ pub struct StateKeeperChannels {
     pub queued_txs_events: mpsc::Receiver<ProcessedOperations>,
 }
 
-// Thread join handle and stop channel sender.
-pub fn spawn_state_keeper(
-    fee_account: &Address,
-    initial_state: ZkSyncStateInitParams,
-) -> (JoinHandle<()>, oneshot::Sender<()>, StateKeeperChannels) {
-    let (proposed_blocks_sender, proposed_blocks_receiver) = mpsc::channel(256);
-    let (state_keeper_req_sender, state_keeper_req_receiver) = mpsc::channel(256);
-    let (mempool_req_sender, mempool_req_receiver) = mpsc::channel(256);
-    let (processed_tx_events_sender, processed_tx_events_receiver) = mpsc::channel(256);
-
+/// Block sizes supported by the testkit state keeper: any combination of up to 1000 operations of the same type.
+fn testkit_block_chunks_sizes() -> Vec<usize> {
     let max_ops_in_block = 1000;
     let ops_chunks = vec![
         TransferToNewOp::CHUNKS,
@@ -62,16 +54,51 @@ pub fn spawn_state_keeper(
         .collect::<Vec<_>>();
     block_chunks_sizes.sort_unstable();
     block_chunks_sizes.dedup();
+    block_chunks_sizes
+}
+
+/// Maximum size of the block produced by the testkit state keeper, in chunks.
+pub fn testkit_max_block_chunks() -> usize {
+    *testkit_block_chunks_sizes().iter().max().unwrap()
+}
+
+// Thread join handle and stop channel sender.
+pub fn spawn_state_keeper(
+    fee_account: &Address,
+    initial_state: ZkSyncStateInitParams,
+) -> (JoinHandle<()>, oneshot::Sender<()>, StateKeeperChannels) {
+    // Iteration limits are big enough for blocks to be sealed only on request.
+    let max_miniblock_iterations = testkit_max_block_chunks();
+    spawn_state_keeper_with_iterations(
+        fee_account,
+        initial_state,
+        max_miniblock_iterations,
+        max_miniblock_iterations,
+    )
+}
+
+/// Same as `spawn_state_keeper`, but the amount of miniblock iterations after which the block
+/// is sealed even if it's not full can be configured, both for the regular blocks and for the blocks
+/// requiring fast processing.
+pub fn spawn_state_keeper_with_iterations(
+    fee_account: &Address,
+    initial_state: ZkSyncStateInitParams,
+    max_miniblock_iterations: usize,
+    fast_miniblock_iterations: usize,
+) -> (JoinHandle<()>, oneshot::Sender<()>, StateKeeperChannels) {
+    let (proposed_blocks_sender, proposed_blocks_receiver) = mpsc::channel(256);
+    let (state_keeper_req_sender, state_keeper_req_receiver) = mpsc::channel(256);
+    let (mempool_req_sender, mempool_req_receiver) = mpsc::channel(256);
+    let (processed_tx_events_sender, processed_tx_events_receiver) = mpsc::channel(256);
 
-    let max_miniblock_iterations = *block_chunks_sizes.iter().max().unwrap();
     let (state_keeper, root_hash_calculator) = ZkSyncStateKeeper::new(
         initial_state,
         *fee_account,
         proposed_blocks_sender,
         mempool_req_sender,
-        block_chunks_sizes,
-        max_miniblock_iterations,
+        testkit_block_chunks_sizes(),
         max_miniblock_iterations,
+        fast_miniblock_iterations,
         processed_tx_events_sender,
     );
 
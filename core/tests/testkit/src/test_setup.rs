@@ -1,12 +1,15 @@
 use crate::eth_account::{get_executed_tx_fee, ETHExecResult, EthereumAccount};
 use crate::external_commands::Contracts;
-use anyhow::bail;
+use anyhow::{bail, ensure, format_err};
 use futures::{
     channel::{mpsc, oneshot},
+    future::LocalBoxFuture,
     SinkExt, StreamExt,
 };
-use num::{bigint::Sign, BigInt, BigUint, ToPrimitive, Zero};
+use num::{bigint::Sign, rational::Ratio, BigInt, BigUint, CheckedSub, ToPrimitive, Zero};
 use std::collections::HashMap;
+use zksync_api::fee_ticker::FeeTicker;
+use zksync_config::{ChainConfig, TickerConfig};
 use zksync_core::{
     committer::CommitRequest,
     state_keeper::{StateKeeperTestkitRequest, ZkSyncStateInitParams},
@@ -14,11 +17,11 @@ use zksync_core::{
 };
 use zksync_types::{
     aggregated_operations::{BlocksCommitOperation, BlocksExecuteOperation, BlocksProofOperation},
-    block::Block,
-    mempool::SignedTxVariant,
+    block::{Block, ExecutedOperations, PendingBlock},
+    mempool::{SignedTxVariant, SignedTxsBatch},
     tx::SignedZkSyncTx,
-    Account, AccountId, AccountMap, Address, BlockNumber, Fr, PriorityOp, TokenId, ZkSyncTx, H256,
-    U256,
+    Account, AccountId, AccountMap, Address, BlockNumber, Fr, PriorityOp, PriorityOpId, TokenId,
+    TokenKind, TxFeeTypes, ZkSyncTx, H256, U256,
 };
 
 use web3::types::TransactionReceipt;
@@ -33,6 +36,9 @@ use zksync_crypto::params::{NFT_STORAGE_ACCOUNT_ADDRESS, NFT_TOKEN_ID};
 use zksync_mempool::ProposedBlock;
 use zksync_types::tx::TimeRange;
 
+/// Function executing operations of a single block, see `TestSetup::commit_and_verify_blocks`.
+pub type BlockBuilder = Box<dyn for<'a> FnOnce(&'a mut TestSetup) -> LocalBoxFuture<'a, ()>>;
+
 /// Used to create transactions between accounts and check for their validity.
 /// Every new block should start with `.start_block()`
 /// and end with `execute_commit_and_verify_block()`
@@ -48,9 +54,14 @@ pub struct TestSetup {
 
     pub accounts: AccountSet,
     pub tokens: HashMap<TokenId, Address>,
+    /// USD prices of one token used by `get_tx_fee`, see `set_token_price`.
+    pub token_prices: HashMap<TokenId, Ratio<BigUint>>,
     pub deployed_contracts: Contracts,
 
     pub expected_changes_for_current_block: ExpectedAccountState,
+    /// Account that collects fees in the current block.
+    /// Equals to `accounts.fee_account_id` unless overridden by `start_block_with_fee_account`.
+    pub current_fee_account_id: ZKSyncAccountId,
 
     pub commit_account: EthereumAccount,
     pub current_state_root: Option<Fr>,
@@ -89,14 +100,17 @@ impl TestSetup {
         let mut tokens = HashMap::new();
         tokens.insert(TokenId(1), deployed_contracts.test_erc20_address);
         tokens.insert(TokenId(0), Address::default());
+        let current_fee_account_id = accounts.fee_account_id;
         Self {
             state_keeper_request_sender: sk_channels.requests,
             proposed_blocks_receiver: sk_channels.new_blocks,
             processed_tx_events_receiver: sk_channels.queued_txs_events,
             accounts,
             tokens,
+            token_prices: HashMap::new(),
             deployed_contracts: deployed_contracts.clone(),
             expected_changes_for_current_block: ExpectedAccountState::default(),
+            current_fee_account_id,
             commit_account,
             current_state_root: Some(initial_root),
             last_committed_block: last_block.unwrap_or_else(|| {
@@ -148,12 +162,156 @@ impl TestSetup {
         }
     }
 
+    /// Returns the expected balance of the current fee account for `token`, as recorded
+    /// by the operations executed in the current block so far.
+    /// If no fee in `token` was recorded in the current block yet, the current balance is returned.
+    pub async fn fee_account_collected(&self, token: Token) -> BigUint {
+        self.get_expected_zksync_account_balance(self.current_fee_account_id, token.0)
+            .await
+    }
+
+    async fn increase_expected_zksync_balance(
+        &mut self,
+        account: ZKSyncAccountId,
+        token: TokenId,
+        amount: &BigUint,
+    ) {
+        let balance = self
+            .get_expected_zksync_account_balance(account, token)
+            .await
+            + amount;
+        self.expected_changes_for_current_block
+            .sync_accounts_state
+            .insert((account, token), balance);
+    }
+
+    async fn decrease_expected_zksync_balance(
+        &mut self,
+        account: ZKSyncAccountId,
+        token: TokenId,
+        amount: &BigUint,
+    ) {
+        let balance = self
+            .get_expected_zksync_account_balance(account, token)
+            .await;
+        let balance = balance.checked_sub(amount).unwrap_or_else(|| {
+            panic!(
+                "Expected balance of account {} in token {} is less than {}",
+                account.0, token, amount
+            )
+        });
+        self.expected_changes_for_current_block
+            .sync_accounts_state
+            .insert((account, token), balance);
+    }
+
     pub fn start_block(&mut self) {
         self.expected_changes_for_current_block = ExpectedAccountState::default();
     }
 
-    pub async fn execute_incorrect_tx(&mut self, tx: ZkSyncTx) {
-        self.execute_tx(tx).await;
+    /// Same as `start_block`, but fees of this block will be collected by `fee_account`.
+    /// The fee account is switched back to the default one once the block is sealed.
+    pub async fn start_block_with_fee_account(&mut self, fee_account: ZKSyncAccountId) {
+        self.start_block();
+        self.set_fee_account(fee_account).await;
+    }
+
+    async fn set_fee_account(&mut self, fee_account: ZKSyncAccountId) {
+        let fee_account_id = self
+            .get_zksync_account_id(fee_account)
+            .await
+            .expect("Fee account should be present in the state");
+        self.state_keeper_request_sender
+            .send(StateKeeperTestkitRequest::SetFeeAccount(fee_account_id))
+            .await
+            .expect("sk receiver dropped");
+        self.current_fee_account_id = fee_account;
+    }
+
+    /// Limits the amount of withdrawals the state keeper puts into a block, starting from the pending block.
+    /// `None` restores the default behavior, where only the block size and gas limits apply.
+    pub async fn set_max_withdrawals_per_block(&mut self, max_withdrawals: Option<usize>) {
+        self.state_keeper_request_sender
+            .send(StateKeeperTestkitRequest::SetMaxWithdrawalsPerBlock(
+                max_withdrawals,
+            ))
+            .await
+            .expect("sk receiver dropped");
+    }
+
+    /// Seals the pending block and switches the fee account back to the default one if needed.
+    async fn seal_block(&mut self) {
+        self.state_keeper_request_sender
+            .clone()
+            .send(StateKeeperTestkitRequest::SealBlock)
+            .await
+            .expect("sk receiver dropped");
+
+        if self.current_fee_account_id != self.accounts.fee_account_id {
+            self.set_fee_account(self.accounts.fee_account_id).await;
+        }
+    }
+
+    /// Executes the transaction which is expected to be rejected, so no state changes are recorded.
+    /// Returns the rejection reason reported by the state keeper, or `Ok(())` if the transaction
+    /// was not rejected.
+    pub async fn execute_incorrect_tx(&mut self, tx: ZkSyncTx) -> Result<(), TxFailReason> {
+        let tx_hash = tx.hash();
+        let pending_block = self.execute_tx(tx).await;
+
+        let executed_tx = pending_block
+            .success_operations
+            .iter()
+            .filter_map(|op| match op {
+                ExecutedOperations::Tx(tx) => Some(tx.as_ref()),
+                ExecutedOperations::PriorityOp(_) => None,
+            })
+            .chain(pending_block.failed_txs.iter())
+            .find(|executed_tx| executed_tx.signed_tx.tx.hash() == tx_hash);
+
+        match executed_tx {
+            Some(executed_tx) if !executed_tx.success => Err(TxFailReason(
+                executed_tx.fail_reason.clone().unwrap_or_default(),
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Executes the transactions as a single atomic batch.
+    /// Expected state changes must be recorded beforehand (e.g. via `create_transfer`).
+    pub async fn execute_batch(&mut self, txs: Vec<ZkSyncTx>) {
+        self.execute_txs_batch(txs).await;
+    }
+
+    /// Executes the batch which is expected to be rejected as a whole,
+    /// so no state changes are recorded.
+    /// Returns the reason of the batch rejection, panics if the batch was applied partially.
+    pub async fn execute_incorrect_batch(
+        &mut self,
+        txs: Vec<ZkSyncTx>,
+    ) -> Result<(), TxFailReason> {
+        let tx_hashes: Vec<_> = txs.iter().map(|tx| tx.hash()).collect();
+        let pending_block = self.execute_txs_batch(txs).await;
+
+        let failed_txs: Vec<_> = pending_block
+            .failed_txs
+            .iter()
+            .filter(|executed_tx| tx_hashes.contains(&executed_tx.signed_tx.tx.hash()))
+            .collect();
+
+        match failed_txs.first() {
+            None => Ok(()),
+            Some(failed_tx) => {
+                assert_eq!(
+                    failed_txs.len(),
+                    tx_hashes.len(),
+                    "Batch was applied partially"
+                );
+                Err(TxFailReason(
+                    failed_tx.fail_reason.clone().unwrap_or_default(),
+                ))
+            }
+        }
     }
 
     pub async fn deposit(
@@ -294,6 +452,8 @@ impl TestSetup {
             )
         };
 
+        self.expected_changes_for_current_block
+            .add_inflow(token.0, &amount);
         let (receipts, deposit_op) = self.accounts.deposit(from, to, token_address, amount).await;
 
         let mut gas_fee = BigUint::from(0u32);
@@ -317,7 +477,7 @@ impl TestSetup {
         (receipts, deposit_op, transfers)
     }
 
-    async fn execute_tx(&mut self, tx: ZkSyncTx) {
+    async fn execute_tx(&mut self, tx: ZkSyncTx) -> PendingBlock {
         let block = ProposedBlock {
             priority_ops: Vec::new(),
             txs: vec![SignedTxVariant::from(SignedZkSyncTx::from(tx))],
@@ -331,7 +491,29 @@ impl TestSetup {
             .expect("sk receiver dropped");
 
         // Receive the pending block processing request from state keeper.
-        self.await_for_pending_block_request().await;
+        self.await_for_pending_block_request().await
+    }
+
+    async fn execute_txs_batch(&mut self, txs: Vec<ZkSyncTx>) -> PendingBlock {
+        let batch = SignedTxsBatch {
+            txs: txs.into_iter().map(SignedZkSyncTx::from).collect(),
+            batch_id: 0,
+            eth_signatures: Vec::new(),
+        };
+        let block = ProposedBlock {
+            priority_ops: Vec::new(),
+            txs: vec![SignedTxVariant::Batch(batch)],
+        };
+
+        // Request miniblock execution.
+        self.state_keeper_request_sender
+            .clone()
+            .send(StateKeeperTestkitRequest::ExecuteMiniBlock(block))
+            .await
+            .expect("sk receiver dropped");
+
+        // Receive the pending block processing request from state keeper.
+        self.await_for_pending_block_request().await
     }
 
     pub async fn deposit_to_random(
@@ -355,6 +537,35 @@ impl TestSetup {
         token: Token,
         amount: BigUint,
         rng: &mut impl Rng,
+    ) -> (Vec<TransactionReceipt>, Vec<AccountTransfer>) {
+        let to_address = Address::from_slice(&rng.gen::<[u8; 20]>());
+        self.create_deposit_to_address(from, token, amount, to_address)
+            .await
+    }
+
+    /// Same as `deposit_to_random`, but the recipient is the ephemeral address derived from `seed`,
+    /// see `AccountSet::ephemeral_address`.
+    pub async fn deposit_to(
+        &mut self,
+        from: ETHAccountId,
+        token: Token,
+        amount: BigUint,
+        seed: u64,
+    ) -> Vec<TransactionReceipt> {
+        self.setup_basic_l1_balances(from, token).await;
+        let (rec, transfers) = self
+            .create_deposit_to_address(from, token, amount, AccountSet::ephemeral_address(seed))
+            .await;
+        self.apply_transfers(&transfers);
+        rec
+    }
+
+    async fn create_deposit_to_address(
+        &mut self,
+        from: ETHAccountId,
+        token: Token,
+        amount: BigUint,
+        to_address: Address,
     ) -> (Vec<TransactionReceipt>, Vec<AccountTransfer>) {
         let mut transfers = vec![AccountTransfer::EthAccountTransfer(EthAccountTransfer {
             account_id: from,
@@ -375,7 +586,7 @@ impl TestSetup {
 
         let (receipts, deposit_op) = self
             .accounts
-            .deposit_to_random(from, token_address, amount, rng)
+            .deposit_to_address(from, token_address, amount, to_address)
             .await;
 
         let mut gas_fee = BigUint::from(0u32);
@@ -399,6 +610,7 @@ impl TestSetup {
     }
 
     pub async fn execute_priority_op(&mut self, op: PriorityOp) {
+        let op_id = PriorityOpId(op.serial_id);
         let block = ProposedBlock {
             priority_ops: vec![op],
             txs: Vec::new(),
@@ -412,7 +624,14 @@ impl TestSetup {
             .expect("sk receiver dropped");
 
         // Receive the pending block processing request from state keeper.
-        self.await_for_pending_block_request().await;
+        let pending_block = self.await_for_pending_block_request().await;
+        let executed = pending_block.success_operations.iter().any(|op| match op {
+            ExecutedOperations::PriorityOp(executed) => {
+                PriorityOpId(executed.priority_op.serial_id) == op_id
+            }
+            ExecutedOperations::Tx(_) => false,
+        });
+        assert!(executed, "Priority operation {} was not executed", op_id);
     }
 
     pub async fn exit(
@@ -479,6 +698,8 @@ impl TestSetup {
             amount: BigInt::from_biguint(Sign::Plus, zksync0_old.clone()),
         }));
 
+        self.expected_changes_for_current_block
+            .add_outflow(token.0, &zksync0_old);
         transfers.push(AccountTransfer::ZkSyncAccountTransfer(
             ZkSyncAccountTransfer {
                 account_id: from,
@@ -532,6 +753,37 @@ impl TestSetup {
         self.execute_tx(tx).await;
     }
 
+    pub async fn change_pubkey_with_create2(
+        &mut self,
+        account: ZKSyncAccountId,
+        creator_address: Address,
+        salt_arg: H256,
+        code_hash: H256,
+        fee_token: Token,
+        fee: BigUint,
+    ) {
+        let account_id = self
+            .get_zksync_account_committed_state(account)
+            .await
+            .expect("can't change pubkey, account does not exist")
+            .0;
+        self.accounts.zksync_accounts[account.0].set_account_id(Some(account_id));
+
+        // Execute transaction
+        let tx = self.accounts.change_pubkey_with_create2(
+            account,
+            creator_address,
+            salt_arg,
+            code_hash,
+            fee_token.0,
+            fee,
+            None,
+            true,
+        );
+
+        self.execute_tx(tx).await;
+    }
+
     pub async fn change_pubkey_with_onchain_auth(
         &mut self,
         eth_account: ETHAccountId,
@@ -550,12 +802,12 @@ impl TestSetup {
 
         // Add fee to the fee collector account
         let mut fee_account = self
-            .get_expected_zksync_account_balance(self.accounts.fee_account_id, fee_token.0)
+            .get_expected_zksync_account_balance(self.current_fee_account_id, fee_token.0)
             .await;
         fee_account += &fee;
         self.expected_changes_for_current_block
             .sync_accounts_state
-            .insert((self.accounts.fee_account_id, fee_token.0), fee_account);
+            .insert((self.current_fee_account_id, fee_token.0), fee_account);
 
         // Update account pubkey
         let account_id = self
@@ -598,12 +850,12 @@ impl TestSetup {
             .insert((creator, fee_token.0), zksync0_old);
 
         let mut zksync0_old = self
-            .get_expected_zksync_account_balance(self.accounts.fee_account_id, fee_token.0)
+            .get_expected_zksync_account_balance(self.current_fee_account_id, fee_token.0)
             .await;
         zksync0_old += &fee;
         self.expected_changes_for_current_block
             .sync_accounts_state
-            .insert((self.accounts.fee_account_id, fee_token.0), zksync0_old);
+            .insert((self.current_fee_account_id, fee_token.0), zksync0_old);
 
         let token_id = self.get_last_committed_nft_id().await;
         let mint_nft =
@@ -662,12 +914,12 @@ impl TestSetup {
             .insert((submitter, tokens.2 .0), submitter_old - &fee);
 
         let fee_account_old = self
-            .get_expected_zksync_account_balance(self.accounts.fee_account_id, tokens.2 .0)
+            .get_expected_zksync_account_balance(self.current_fee_account_id, tokens.2 .0)
             .await;
         self.expected_changes_for_current_block
             .sync_accounts_state
             .insert(
-                (self.accounts.fee_account_id, tokens.2 .0),
+                (self.current_fee_account_id, tokens.2 .0),
                 fee_account_old + &fee,
             );
 
@@ -687,34 +939,35 @@ impl TestSetup {
         fee: BigUint,
         time_range: TimeRange,
     ) {
-        let mut zksync0_old = self
-            .get_expected_zksync_account_balance(from, token.0)
+        let transfer = self
+            .create_transfer(from, to, token, amount, fee, time_range)
             .await;
-        zksync0_old -= &amount;
-        zksync0_old -= &fee;
-        self.expected_changes_for_current_block
-            .sync_accounts_state
-            .insert((from, token.0), zksync0_old);
 
-        let mut zksync0_old = self.get_expected_zksync_account_balance(to, token.0).await;
-        zksync0_old += &amount;
-        self.expected_changes_for_current_block
-            .sync_accounts_state
-            .insert((to, token.0), zksync0_old);
+        self.execute_tx(transfer).await;
+    }
 
-        let mut zksync0_old = self
-            .get_expected_zksync_account_balance(self.accounts.fee_account_id, token.0)
+    /// Creates a signed transfer and records its expected state changes without executing it.
+    /// Useful to compose batches that are expected to be applied.
+    pub async fn create_transfer(
+        &mut self,
+        from: ZKSyncAccountId,
+        to: ZKSyncAccountId,
+        token: Token,
+        amount: BigUint,
+        fee: BigUint,
+        time_range: TimeRange,
+    ) -> ZkSyncTx {
+        // Entries are recorded even for zero amount or fee, so that balances of
+        // all the participants are checked once the block is executed.
+        self.decrease_expected_zksync_balance(from, token.0, &(&amount + &fee))
+            .await;
+        self.increase_expected_zksync_balance(to, token.0, &amount)
+            .await;
+        self.increase_expected_zksync_balance(self.current_fee_account_id, token.0, &fee)
             .await;
-        zksync0_old += &fee;
-        self.expected_changes_for_current_block
-            .sync_accounts_state
-            .insert((self.accounts.fee_account_id, token.0), zksync0_old);
-
-        let transfer = self
-            .accounts
-            .transfer(from, to, token, amount, fee, None, time_range, true);
 
-        self.execute_tx(transfer).await;
+        self.accounts
+            .transfer(from, to, token, amount, fee, None, time_range, true)
     }
 
     pub async fn transfer_to_new_random(
@@ -724,6 +977,39 @@ impl TestSetup {
         amount: BigUint,
         fee: BigUint,
         rng: &mut impl Rng,
+    ) {
+        let to_address = Address::from_slice(&rng.gen::<[u8; 20]>());
+        self.transfer_to_address(from, token, amount, fee, to_address)
+            .await;
+    }
+
+    /// Same as `transfer_to_new_random`, but the recipient is the ephemeral address derived from `seed`,
+    /// see `AccountSet::ephemeral_address`.
+    pub async fn transfer_to_new(
+        &mut self,
+        from: ZKSyncAccountId,
+        token: Token,
+        amount: BigUint,
+        fee: BigUint,
+        seed: u64,
+    ) {
+        self.transfer_to_address(
+            from,
+            token,
+            amount,
+            fee,
+            AccountSet::ephemeral_address(seed),
+        )
+        .await;
+    }
+
+    async fn transfer_to_address(
+        &mut self,
+        from: ZKSyncAccountId,
+        token: Token,
+        amount: BigUint,
+        fee: BigUint,
+        to_address: Address,
     ) {
         let mut zksync0_old = self
             .get_expected_zksync_account_balance(from, token.0)
@@ -735,16 +1021,19 @@ impl TestSetup {
             .insert((from, token.0), zksync0_old);
 
         let mut zksync0_old = self
-            .get_expected_zksync_account_balance(self.accounts.fee_account_id, token.0)
+            .get_expected_zksync_account_balance(self.current_fee_account_id, token.0)
             .await;
         zksync0_old += &fee;
         self.expected_changes_for_current_block
             .sync_accounts_state
-            .insert((self.accounts.fee_account_id, token.0), zksync0_old);
+            .insert((self.current_fee_account_id, token.0), zksync0_old);
+
+        self.expected_changes_for_current_block
+            .add_outflow(token.0, &amount);
 
         let transfer = self
             .accounts
-            .transfer_to_new_random(from, token, amount, fee, None, true, rng);
+            .transfer_to_address(from, token, amount, fee, None, true, to_address);
 
         self.execute_tx(transfer).await;
     }
@@ -756,7 +1045,24 @@ impl TestSetup {
         token: Token,
         amount: BigUint,
         fee: BigUint,
+        time_range: TimeRange,
     ) {
+        let withdraw = self
+            .create_withdraw(from, to, token, amount, fee, time_range)
+            .await;
+        self.execute_tx(withdraw).await;
+    }
+
+    /// Creates a signed withdrawal and records its expected state changes without executing it.
+    pub async fn create_withdraw(
+        &mut self,
+        from: ZKSyncAccountId,
+        to: ETHAccountId,
+        token: Token,
+        amount: BigUint,
+        fee: BigUint,
+        time_range: TimeRange,
+    ) -> ZkSyncTx {
         let mut zksync0_old = self
             .get_expected_zksync_account_balance(from, token.0)
             .await;
@@ -773,18 +1079,18 @@ impl TestSetup {
             .insert((to, token.0), to_eth_balance);
 
         let mut zksync0_old = self
-            .get_expected_zksync_account_balance(self.accounts.fee_account_id, token.0)
+            .get_expected_zksync_account_balance(self.current_fee_account_id, token.0)
             .await;
         zksync0_old += &fee;
         self.expected_changes_for_current_block
             .sync_accounts_state
-            .insert((self.accounts.fee_account_id, token.0), zksync0_old);
+            .insert((self.current_fee_account_id, token.0), zksync0_old);
 
-        let withdraw =
-            self.accounts
-                .withdraw(from, to, token, amount, fee, None, true, Default::default());
+        self.expected_changes_for_current_block
+            .add_outflow(token.0, &amount);
 
-        self.execute_tx(withdraw).await;
+        self.accounts
+            .withdraw(from, to, token, amount, fee, None, true, time_range)
     }
 
     pub async fn withdraw_nft(
@@ -811,12 +1117,12 @@ impl TestSetup {
             .insert((from, token.0), BigUint::zero());
 
         let mut zksync0_old = self
-            .get_expected_zksync_account_balance(self.accounts.fee_account_id, fee_token.0)
+            .get_expected_zksync_account_balance(self.current_fee_account_id, fee_token.0)
             .await;
         zksync0_old += &fee;
         self.expected_changes_for_current_block
             .sync_accounts_state
-            .insert((self.accounts.fee_account_id, fee_token.0), zksync0_old);
+            .insert((self.current_fee_account_id, fee_token.0), zksync0_old);
 
         let withdraw = self
             .accounts
@@ -842,12 +1148,15 @@ impl TestSetup {
             .insert((from, token.0), zksync0_old);
 
         let mut zksync0_old = self
-            .get_expected_zksync_account_balance(self.accounts.fee_account_id, token.0)
+            .get_expected_zksync_account_balance(self.current_fee_account_id, token.0)
             .await;
         zksync0_old += &fee;
         self.expected_changes_for_current_block
             .sync_accounts_state
-            .insert((self.accounts.fee_account_id, token.0), zksync0_old);
+            .insert((self.current_fee_account_id, token.0), zksync0_old);
+
+        self.expected_changes_for_current_block
+            .add_outflow(token.0, &amount);
 
         let withdraw = self
             .accounts
@@ -885,16 +1194,19 @@ impl TestSetup {
             .insert((target_eth_id, token_id.0), target_eth_balance);
 
         let mut fee_account_balance = self
-            .get_expected_zksync_account_balance(self.accounts.fee_account_id, token_id.0)
+            .get_expected_zksync_account_balance(self.current_fee_account_id, token_id.0)
             .await;
         fee_account_balance += &fee;
         self.expected_changes_for_current_block
             .sync_accounts_state
             .insert(
-                (self.accounts.fee_account_id, token_id.0),
+                (self.current_fee_account_id, token_id.0),
                 fee_account_balance,
             );
 
+        self.expected_changes_for_current_block
+            .add_outflow(token_id.0, &target_old);
+
         let forced_exit = self.accounts.forced_exit(
             initiator,
             target,
@@ -950,43 +1262,60 @@ impl TestSetup {
 
     /// Takes the next `CommitRequest` from the proposed blocks receiver and expects
     /// it to be `PendingBlock`. Panics otherwise.
-    async fn await_for_pending_block_request(&mut self) {
-        let new_block_event = self
-            .proposed_blocks_receiver
-            .next()
-            .await
-            .expect("StateKeeper sender dropped");
-        match new_block_event {
-            CommitRequest::SealIncompleteBlock((new_block, _)) => {
-                panic!(
-                    "Expected pending block, got incomplete block proposed. Block: {:?}",
-                    new_block
-                );
-            }
-            CommitRequest::FinishBlock(block_finish_request) => {
-                panic!(
-                    "Expected pending block, got finish block request for block: {}",
-                    block_finish_request.block_number
-                );
-            }
-            CommitRequest::PendingBlock(_) => {
-                // Nothing to be done.
-            }
-            CommitRequest::RemoveRevertedBlock(_) => {
-                // Nothing to be done.
+    ///
+    /// Returns the received pending block, which contains only the operations executed
+    /// since the previous pending block request.
+    async fn await_for_pending_block_request(&mut self) -> PendingBlock {
+        loop {
+            let new_block_event = self
+                .proposed_blocks_receiver
+                .next()
+                .await
+                .expect("StateKeeper sender dropped");
+            match new_block_event {
+                CommitRequest::SealIncompleteBlock((new_block, _)) => {
+                    panic!(
+                        "Expected pending block, got incomplete block proposed. Block: {:?}",
+                        new_block
+                    );
+                }
+                CommitRequest::FinishBlock(block_finish_request) => {
+                    panic!(
+                        "Expected pending block, got finish block request for block: {}",
+                        block_finish_request.block_number
+                    );
+                }
+                CommitRequest::PendingBlock((pending_block, _)) => {
+                    return pending_block;
+                }
+                CommitRequest::RemoveRevertedBlock(_) => {
+                    // Nothing to be done.
+                }
             }
         }
     }
 
     /// Should not be used execept special cases(when we want to commit but don't want to verify block)
+    ///
+    /// Panics if the sealed block has no operations, see `execute_commit_block_allow_empty`.
     pub async fn execute_commit_block(&mut self) -> Block {
-        self.state_keeper_request_sender
-            .clone()
-            .send(StateKeeperTestkitRequest::SealBlock)
-            .await
-            .expect("sk receiver dropped");
+        self.commit_sealed_block(false).await
+    }
+
+    /// Same as `execute_commit_block`, but empty blocks are committed as well.
+    pub async fn execute_commit_block_allow_empty(&mut self) -> Block {
+        self.commit_sealed_block(true).await
+    }
+
+    async fn commit_sealed_block(&mut self, allow_empty: bool) -> Block {
+        self.seal_block().await;
 
         let new_block = self.await_for_block_commit().await;
+        assert!(
+            allow_empty || !new_block.is_empty(),
+            "Attempt to commit an empty block {}: no operations were executed since the previous block",
+            new_block.block_number
+        );
         self.current_state_root = Some(new_block.new_root_hash);
 
         let block_commit_op = BlocksCommitOperation {
@@ -994,7 +1323,7 @@ impl TestSetup {
             blocks: vec![new_block.clone()],
         };
         self.commit_account
-            .commit_block(&block_commit_op)
+            .commit_block(&block_commit_op, None, None)
             .await
             .expect("block commit send tx")
             .expect_success();
@@ -1005,11 +1334,7 @@ impl TestSetup {
     }
 
     pub async fn execute_block(&mut self) -> Block {
-        self.state_keeper_request_sender
-            .clone()
-            .send(StateKeeperTestkitRequest::SealBlock)
-            .await
-            .expect("sk receiver dropped");
+        self.seal_block().await;
 
         self.await_for_block_commit().await
     }
@@ -1022,7 +1347,7 @@ impl TestSetup {
         };
         self.last_committed_block = blocks.last().unwrap().clone();
         self.commit_account
-            .commit_block(&block_commit_op)
+            .commit_block(&block_commit_op, None, None)
             .await
             .expect("block commit send tx")
     }
@@ -1051,7 +1376,7 @@ impl TestSetup {
             proof,
         };
         self.commit_account
-            .verify_block(&block_proof_op)
+            .verify_block(&block_proof_op, None, None)
             .await
             .expect("block verify send tx")
     }
@@ -1071,7 +1396,7 @@ impl TestSetup {
         proof: BlocksProofOperation,
     ) -> ETHExecResult {
         self.commit_account
-            .verify_block(&proof)
+            .verify_block(&proof, None, None)
             .await
             .expect("block verify fail")
     }
@@ -1079,12 +1404,117 @@ impl TestSetup {
     pub async fn execute_commit_and_verify_block(
         &mut self,
     ) -> Result<BlockExecutionResult, anyhow::Error> {
+        self.seal_block().await;
+        let new_block = self.await_for_block_commit().await;
+        let result = self.process_block_onchain(new_block).await;
+
+        self.check_expected_changes().await?;
+
+        Ok(result)
+    }
+
+    /// Executes the transactions in a new block with the given timestamp instead of the system time,
+    /// then commits, verifies and executes this block the same way as `execute_commit_and_verify_block`.
+    ///
+    /// The pending block must be empty. Expected state changes must be recorded beforehand
+    /// (e.g. via `create_transfer`), rejected transactions are included into the block as failed ones.
+    pub async fn execute_commit_block_with_defined_timestamp(
+        &mut self,
+        txs: Vec<ZkSyncTx>,
+        timestamp: u64,
+    ) -> Result<BlockExecutionResult, anyhow::Error> {
+        let block = ProposedBlock {
+            priority_ops: Vec::new(),
+            txs: txs
+                .into_iter()
+                .map(|tx| SignedTxVariant::from(SignedZkSyncTx::from(tx)))
+                .collect(),
+        };
         self.state_keeper_request_sender
             .clone()
-            .send(StateKeeperTestkitRequest::SealBlock)
+            .send(StateKeeperTestkitRequest::ExecuteMiniBlockWithTimestamp(
+                block, timestamp,
+            ))
             .await
             .expect("sk receiver dropped");
-        let new_block = self.await_for_block_commit().await;
+        self.await_for_pending_block_request().await;
+
+        self.execute_commit_and_verify_block().await
+    }
+
+    /// Executes the transactions in a single mini block which is expected not to fit into the pending block.
+    /// The block sealed by the state keeper is committed, verified and executed the same way as in
+    /// `execute_commit_and_verify_block`, while the transactions which didn't fit remain in the new pending block.
+    ///
+    /// Expected state changes must be recorded beforehand (e.g. via `create_withdraw`). They are checked
+    /// once the following block is executed, since the current block contains only a part of the transactions.
+    pub async fn execute_txs_overflowing_block(
+        &mut self,
+        txs: Vec<ZkSyncTx>,
+    ) -> BlockExecutionResult {
+        let block = ProposedBlock {
+            priority_ops: Vec::new(),
+            txs: txs
+                .into_iter()
+                .map(|tx| SignedTxVariant::from(SignedZkSyncTx::from(tx)))
+                .collect(),
+        };
+        self.state_keeper_request_sender
+            .clone()
+            .send(StateKeeperTestkitRequest::ExecuteMiniBlock(block))
+            .await
+            .expect("sk receiver dropped");
+
+        // The sealed block is finished asynchronously, so the pending block with the rest of
+        // the transactions may be received before it.
+        let mut incomplete_block = None;
+        let mut sealed_block = None;
+        let mut pending_block_received = false;
+        while sealed_block.is_none() || !pending_block_received {
+            let new_block_event = self
+                .proposed_blocks_receiver
+                .next()
+                .await
+                .expect("StateKeeper sender dropped");
+            match new_block_event {
+                CommitRequest::SealIncompleteBlock((new_block_request, _)) => {
+                    assert!(
+                        incomplete_block.is_none() && sealed_block.is_none(),
+                        "Transactions overflowed more than one block"
+                    );
+                    incomplete_block = Some(new_block_request.block);
+                }
+                CommitRequest::FinishBlock(block_finish_request) => {
+                    let incomplete_block = incomplete_block.take().unwrap_or_else(|| {
+                        panic!(
+                            "Received FinishBlock request before the block was sealed: {:?}",
+                            block_finish_request
+                        );
+                    });
+                    sealed_block = Some(Block::from_incomplete(
+                        incomplete_block,
+                        self.current_state_root.unwrap(),
+                        block_finish_request.root_hash,
+                    ));
+                }
+                CommitRequest::PendingBlock(_) => {
+                    assert!(
+                        incomplete_block.is_some() || sealed_block.is_some(),
+                        "Transactions fit into the pending block, no block was sealed"
+                    );
+                    pending_block_received = true;
+                }
+                CommitRequest::RemoveRevertedBlock(_) => {
+                    // Nothing to be done.
+                }
+            }
+        }
+
+        self.process_block_onchain(sealed_block.unwrap()).await
+    }
+
+    /// Commits, verifies and executes the sealed block on L1.
+    async fn process_block_onchain(&mut self, new_block: Block) -> BlockExecutionResult {
         self.current_state_root = Some(new_block.new_root_hash);
 
         let block_commit_op = BlocksCommitOperation {
@@ -1093,7 +1523,7 @@ impl TestSetup {
         };
         let commit_result = self
             .commit_account
-            .commit_block(&block_commit_op)
+            .commit_block(&block_commit_op, None, None)
             .await
             .expect("block commit send tx")
             .expect_success();
@@ -1107,7 +1537,7 @@ impl TestSetup {
         };
         let verify_result = self
             .commit_account
-            .verify_block(&block_proof_op)
+            .verify_block(&block_proof_op, None, None)
             .await
             .expect("block verify send tx")
             .expect_success();
@@ -1136,15 +1566,93 @@ impl TestSetup {
 
         let block_chunks = new_block.block_chunks_size;
 
+        BlockExecutionResult::new(
+            new_block,
+            commit_result,
+            verify_result,
+            withdrawals_result,
+            pending_withdrawals_result,
+            block_chunks,
+        )
+    }
+
+    /// Builds a block with each of the `block_builders` and processes them all at once:
+    /// the blocks are committed, proven and executed with a single L1 transaction per
+    /// operation, similar to how the server aggregates operations.
+    ///
+    /// Expected changes are accumulated over all the blocks and checked after their execution,
+    /// so the builders must not call `start_block`.
+    pub async fn commit_and_verify_blocks(
+        &mut self,
+        block_builders: Vec<BlockBuilder>,
+    ) -> Result<Vec<Block>, anyhow::Error> {
+        assert!(!block_builders.is_empty());
+        self.start_block();
+
+        let mut blocks = Vec::with_capacity(block_builders.len());
+        for build_block in block_builders {
+            build_block(self).await;
+            let block = self.execute_block().await;
+            self.current_state_root = Some(block.new_root_hash);
+            blocks.push(block);
+        }
+
+        self.commit_blocks(&blocks).await.expect_success();
+        self.prove_blocks(&blocks, None).await.expect_success();
+        self.execute_blocks_onchain(&blocks).await.expect_success();
+        self.commit_account
+            .execute_pending_withdrawals(
+                &BlocksExecuteOperation {
+                    blocks: blocks.clone(),
+                },
+                &self.tokens,
+                &self.deployed_contracts.pending_withdrawer,
+            )
+            .await
+            .expect("execute block tx")
+            .map(|a| a.expect_success());
+
+        self.check_expected_changes().await?;
+
+        Ok(blocks)
+    }
+
+    /// Compares the expected changes of the current block with the actual L1 and L2 balances,
+    /// and updates account IDs of the zkSync accounts.
+    async fn check_expected_changes(&mut self) -> Result<(), anyhow::Error> {
         let mut block_checks_failed = false;
+        let mut tokens_by_eth_account: HashMap<ETHAccountId, Vec<TokenId>> = HashMap::new();
+        for (eth_account, token) in self
+            .expected_changes_for_current_block
+            .eth_accounts_state
+            .keys()
+        {
+            tokens_by_eth_account
+                .entry(*eth_account)
+                .or_default()
+                .push(*token);
+        }
+        let mut real_balances = HashMap::new();
+        for (eth_account, tokens) in tokens_by_eth_account {
+            for (token, balance) in self.get_eth_balances(eth_account, &tokens).await {
+                real_balances.insert((eth_account, token), balance);
+            }
+        }
+
         for ((eth_account, token), expected_balance) in
             &self.expected_changes_for_current_block.eth_accounts_state
         {
-            let real_balance = self.get_eth_balance(*eth_account, *token).await;
-            if expected_balance != &real_balance {
+            let real_balance = &real_balances[&(*eth_account, *token)];
+            if expected_balance != real_balance {
                 println!("eth acc: {}, token: {}", eth_account.0, token);
-                println!("expected: {}", expected_balance);
-                println!("real:     {}", real_balance);
+                println!(
+                    "expected: {}",
+                    self.display_amount(Token(*token), expected_balance).await
+                );
+                println!(
+                    "real:     {}",
+                    self.display_amount(Token(*token), real_balance).await
+                );
                 block_checks_failed = true;
             }
         }
@@ -1157,8 +1665,8 @@ impl TestSetup {
                 println!(
                     "zksync acc {} balance {}, real: {} token: {}",
                     zksync_account.0,
-                    balance,
-                    real.clone(),
+                    self.display_amount(Token(*token), balance).await,
+                    self.display_amount(Token(*token), &real).await,
                     token.0
                 );
                 block_checks_failed = true;
@@ -1174,14 +1682,190 @@ impl TestSetup {
                 .set_account_id(self.get_zksync_account_id(ZKSyncAccountId(zk_id)).await);
         }
 
-        Ok(BlockExecutionResult::new(
-            new_block,
-            commit_result,
-            verify_result,
-            withdrawals_result,
-            pending_withdrawals_result,
-            block_chunks,
-        ))
+        Ok(())
+    }
+
+    /// Makes the ERC20 token available for the testkit operations.
+    /// Token is registered in the governance contract, unless it's registered already,
+    /// and its on-chain ID must match `id`.
+    pub async fn register_token(
+        &mut self,
+        id: TokenId,
+        address: Address,
+    ) -> Result<(), anyhow::Error> {
+        ensure!(
+            !self.tokens.contains_key(&id),
+            "Token with id {} is already registered",
+            id
+        );
+        ensure!(
+            !self
+                .tokens
+                .values()
+                .any(|registered| *registered == address),
+            "Token {:?} is already registered",
+            address
+        );
+
+        let governance = self.deployed_contracts.governance;
+        let mut onchain_id = self
+            .commit_account
+            .governance_token_id(governance, address)
+            .await?;
+        if onchain_id.is_none() {
+            self.commit_account
+                .add_token(governance, address)
+                .await?
+                .success_result()?;
+            onchain_id = self
+                .commit_account
+                .governance_token_id(governance, address)
+                .await?;
+        }
+        ensure!(
+            onchain_id == Some(id),
+            "Token {:?} has id {:?} in the governance contract, expected {}",
+            address,
+            onchain_id,
+            id
+        );
+
+        self.tokens.insert(id, address);
+        Ok(())
+    }
+
+    /// Returns the number of decimals of the token.
+    /// ETH always has 18 decimals, for ERC20 tokens the value is queried from the token contract.
+    pub async fn token_decimals(&self, token: Token) -> Result<u8, anyhow::Error> {
+        if token.0 == TokenId(0) {
+            return Ok(18);
+        }
+        let address = self
+            .tokens
+            .get(&token.0)
+            .ok_or_else(|| format_err!("Token {} is not registered", token.0))?;
+        self.commit_account.erc20_decimals(address).await
+    }
+
+    /// Sets the fixed USD price of one token (not of one wei) used by `get_tx_fee`.
+    ///
+    /// Test-only: relies on the `ticker_price_override` feature of the fee ticker.
+    pub fn set_token_price(&mut self, token: Token, usd_price: Ratio<BigUint>) {
+        self.token_prices.insert(token.0, usd_price);
+    }
+
+    /// Returns the fee the server would request for the transaction paid in `token`, computed by the fee ticker
+    /// with the prices set via `set_token_price` and the provided gas price. The recipient is considered existing.
+    ///
+    /// Prices of both the fee token and ETH must be set.
+    pub async fn get_tx_fee(
+        &self,
+        tx_type: TxFeeTypes,
+        token: Token,
+        gas_price_wei: BigUint,
+    ) -> Result<BigUint, anyhow::Error> {
+        let mut tokens = Vec::with_capacity(self.tokens.len());
+        for (&id, &address) in &self.tokens {
+            let decimals = self.token_decimals(Token(id)).await?;
+            let symbol = format!("TOKEN-{}", *id);
+            tokens.push(zksync_types::Token::new(
+                id,
+                address,
+                &symbol,
+                decimals,
+                TokenKind::ERC20,
+            ));
+        }
+
+        let ticker = FeeTicker::new_with_fixed_prices(
+            tokens,
+            gas_price_wei,
+            &TickerConfig::from_env(),
+            ChainConfig::from_env().max_blocks_to_aggregate(),
+        );
+        for (token_id, usd_price) in &self.token_prices {
+            ticker.set_token_price(*token_id, usd_price.clone());
+        }
+        let fee = ticker
+            .get_fee_from_ticker_in_wei(tx_type, token.0.into(), Address::default())
+            .await?;
+        Ok(fee.normal_fee.total_fee)
+    }
+
+    /// Formats the amount in the smallest token units as a decimal token amount, e.g. `1.5`.
+    pub async fn format_token_amount(
+        &self,
+        token: Token,
+        amount: &BigUint,
+    ) -> Result<String, anyhow::Error> {
+        let decimals = self.token_decimals(token).await?;
+        Ok(zksync_utils::format_token_amount(amount, decimals))
+    }
+
+    /// Parses the decimal token amount (e.g. `1.5`) into the smallest token units.
+    pub async fn parse_token_amount(
+        &self,
+        token: Token,
+        amount: &str,
+    ) -> Result<BigUint, anyhow::Error> {
+        let decimals = self.token_decimals(token).await?;
+        zksync_utils::parse_token_amount(amount, decimals)
+    }
+
+    /// Amount representation for the balance mismatch reports: raw value followed by the formatted one.
+    async fn display_amount(&self, token: Token, amount: &BigUint) -> String {
+        match self.format_token_amount(token, amount).await {
+            Ok(formatted) => format!("{} ({})", amount, formatted),
+            Err(_) => amount.to_string(),
+        }
+    }
+
+    /// Pauses or unpauses deposits of the token via the governance contract
+    /// and checks that the on-chain state was updated.
+    pub async fn set_token_paused(&self, token: Token, paused: bool) -> Result<(), anyhow::Error> {
+        let address = *self
+            .tokens
+            .get(&token.0)
+            .ok_or_else(|| anyhow::format_err!("Token with id {} is not registered", token.0))?;
+        let governance = self.deployed_contracts.governance;
+        self.commit_account
+            .set_token_paused(governance, address, paused)
+            .await?
+            .success_result()?;
+
+        ensure!(
+            self.commit_account
+                .is_token_paused(governance, token.0)
+                .await?
+                == paused,
+            "Paused status of token {} was not updated",
+            token.0
+        );
+        Ok(())
+    }
+
+    /// Changes the validator status of the address via the governance contract
+    /// and checks that the on-chain state was updated.
+    pub async fn set_validator(
+        &self,
+        validator: Address,
+        active: bool,
+    ) -> Result<(), anyhow::Error> {
+        let governance = self.deployed_contracts.governance;
+        self.commit_account
+            .set_validator(governance, validator, active)
+            .await?
+            .success_result()?;
+
+        ensure!(
+            self.commit_account
+                .is_validator(governance, validator)
+                .await?
+                == active,
+            "Validator status of {:?} was not updated",
+            validator
+        );
+        Ok(())
     }
 
     pub async fn get_last_committed_nft_id(&self) -> u32 {
@@ -1209,6 +1893,60 @@ impl TestSetup {
             .map(|a| a.0)
     }
 
+    /// Checks that the root hash of the current state keeper state matches the expected one.
+    pub async fn assert_state_root(&self, expected: Fr) {
+        let (sender, receiver) = oneshot::channel();
+        self.state_keeper_request_sender
+            .clone()
+            .send(StateKeeperTestkitRequest::GetRoot(sender))
+            .await
+            .expect("sk request send");
+        let actual = receiver.await.expect("sk root resp recv");
+        assert!(
+            actual == expected,
+            "State root mismatch: expected {}, actual {}",
+            Block::encode_fr_hex(expected),
+            Block::encode_fr_hex(actual)
+        );
+    }
+
+    /// Sets the balance of the zkSync account directly in the state keeper, bypassing the transaction processing.
+    /// Intended to prepare complex preconditions: the change is not backed by any operation, so the block
+    /// including it cannot be proven. The expected state of the current block is rebased on the new balance.
+    ///
+    /// Returns the new state root hash.
+    pub async fn set_balance(
+        &mut self,
+        zksync_id: ZKSyncAccountId,
+        token: Token,
+        amount: BigUint,
+    ) -> Fr {
+        let account_id = self
+            .get_zksync_account_id(zksync_id)
+            .await
+            .expect("Account should be present in the state");
+
+        let (sender, receiver) = oneshot::channel();
+        self.state_keeper_request_sender
+            .send(StateKeeperTestkitRequest::SetBalance {
+                account_id,
+                token: token.0,
+                amount: amount.clone(),
+                resp: sender,
+            })
+            .await
+            .expect("sk request send");
+        let root_hash = receiver
+            .await
+            .expect("sk set balance resp recv")
+            .expect("Account should be present in the state");
+
+        self.expected_changes_for_current_block
+            .sync_accounts_state
+            .insert((zksync_id, token.0), amount);
+        root_hash
+    }
+
     pub async fn get_current_state(&mut self) -> ZkSyncStateInitParams {
         let (sender, receiver) = oneshot::channel();
         self.state_keeper_request_sender
@@ -1218,6 +1956,43 @@ impl TestSetup {
         receiver.await.unwrap()
     }
 
+    /// Dumps the whole account map of the current state to the JSON file,
+    /// so it can be restored with `TestSetup::load_state_from_file`.
+    pub async fn dump_state_to_file(&mut self, path: &str) -> Result<(), anyhow::Error> {
+        let accounts: Vec<(AccountId, Account)> = self
+            .get_current_state()
+            .await
+            .state
+            .get_accounts()
+            .into_iter()
+            .map(|(id, account)| (AccountId(id), account))
+            .collect();
+
+        let file = std::fs::File::create(path)?;
+        serde_json::to_writer_pretty(std::io::BufWriter::new(file), &accounts)?;
+        Ok(())
+    }
+
+    /// Loads the state dumped by `TestSetup::dump_state_to_file`.
+    /// Note that only accounts are restored, block number and priority op counter start from zero.
+    pub fn load_state_from_file(path: &str) -> Result<ZkSyncStateInitParams, anyhow::Error> {
+        let file = std::fs::File::open(path)?;
+        let accounts: Vec<(AccountId, Account)> =
+            serde_json::from_reader(std::io::BufReader::new(file))?;
+
+        let mut params = ZkSyncStateInitParams::new();
+        for (id, account) in accounts {
+            params.state.nfts.extend(
+                account
+                    .minted_nfts
+                    .iter()
+                    .map(|(token_id, nft)| (*token_id, nft.clone())),
+            );
+            params.state.insert_account(id, account);
+        }
+        Ok(params)
+    }
+
     async fn get_zksync_balance(&self, zksync_id: ZKSyncAccountId, token: TokenId) -> BigUint {
         let result = self
             .get_zksync_account_committed_state(zksync_id)
@@ -1227,9 +2002,110 @@ impl TestSetup {
         result
     }
 
+    /// Returns the current balances of all the zkSync test accounts in all the known tokens.
+    /// Should be taken before the block is started, to be checked by `assert_conservation` once it's executed.
+    pub async fn snapshot_balances(&self) -> HashMap<(ZKSyncAccountId, TokenId), BigUint> {
+        let mut balances = HashMap::new();
+        for zksync_id in (0..self.accounts.zksync_accounts.len()).map(ZKSyncAccountId) {
+            let account = self
+                .get_zksync_account_committed_state(zksync_id)
+                .await
+                .map(|(_, account)| account);
+            for &token in self.tokens.keys() {
+                let balance = account
+                    .as_ref()
+                    .map(|account| account.get_balance(token))
+                    .unwrap_or_default();
+                balances.insert((zksync_id, token), balance);
+            }
+        }
+        balances
+    }
+
+    /// Checks that no funds were created or destroyed in the current block.
+    ///
+    /// For every token, the total balance change of the zkSync test accounts since the `initial_balances`
+    /// snapshot (see `snapshot_balances`) must be equal to the amount of funds deposited from L1 minus the amount
+    /// of funds that left them (withdrawals, exits and transfers to the untracked accounts).
+    pub async fn assert_conservation(
+        &self,
+        initial_balances: &HashMap<(ZKSyncAccountId, TokenId), BigUint>,
+    ) {
+        let current_balances = self.snapshot_balances().await;
+
+        let mut balance_changes: HashMap<TokenId, BigInt> = HashMap::new();
+        for (&(zksync_id, token), initial_balance) in initial_balances {
+            let current_balance = current_balances
+                .get(&(zksync_id, token))
+                .cloned()
+                .unwrap_or_default();
+            *balance_changes.entry(token).or_default() +=
+                BigInt::from(current_balance) - BigInt::from(initial_balance.clone());
+        }
+
+        let expected = &self.expected_changes_for_current_block;
+        let mut tokens: Vec<TokenId> = balance_changes
+            .keys()
+            .chain(expected.inflow.keys())
+            .chain(expected.outflow.keys())
+            .cloned()
+            .collect();
+        tokens.sort();
+        tokens.dedup();
+
+        let mut conservation_failed = false;
+        for token in tokens {
+            let balance_change = balance_changes.get(&token).cloned().unwrap_or_default();
+            let expected_change =
+                BigInt::from(expected.inflow.get(&token).cloned().unwrap_or_default())
+                    - BigInt::from(expected.outflow.get(&token).cloned().unwrap_or_default());
+            if balance_change != expected_change {
+                println!("token: {}", token);
+                println!("expected change: {}", expected_change);
+                println!("real change:     {}", balance_change);
+                println!("discrepancy:     {}", &balance_change - &expected_change);
+                conservation_failed = true;
+            }
+        }
+
+        if conservation_failed {
+            panic!("Funds were not conserved in block");
+        }
+    }
+
     pub async fn get_eth_balance(&self, eth_account_id: ETHAccountId, token: TokenId) -> BigUint {
+        self.get_l1_balance(eth_account_id, token).await
+            + self
+                .get_balance_to_withdraw(eth_account_id, self.tokens[&token])
+                .await
+    }
+
+    /// Same as `get_eth_balance`, but balances to withdraw for all the tokens
+    /// are queried at once.
+    pub async fn get_eth_balances(
+        &self,
+        eth_account_id: ETHAccountId,
+        tokens: &[TokenId],
+    ) -> HashMap<TokenId, BigUint> {
+        let token_addresses = tokens
+            .iter()
+            .map(|token| (*token, self.tokens[token]))
+            .collect::<Vec<_>>();
+        let mut balances = self.accounts.eth_accounts[eth_account_id.0]
+            .balances_to_withdraw_multi(&token_addresses)
+            .await
+            .expect("failed to query balances to withdraw");
+
+        for token in tokens {
+            let l1_balance = self.get_l1_balance(eth_account_id, *token).await;
+            *balances.entry(*token).or_default() += l1_balance;
+        }
+        balances
+    }
+
+    async fn get_l1_balance(&self, eth_account_id: ETHAccountId, token: TokenId) -> BigUint {
         let account = &self.accounts.eth_accounts[eth_account_id.0];
-        let result = if token == TokenId(0) {
+        if token == TokenId(0) {
             account
                 .eth_balance()
                 .await
@@ -1239,13 +2115,7 @@ impl TestSetup {
                 .erc20_balance(&self.tokens[&token])
                 .await
                 .expect("Failed to get erc20 balance")
-        };
-
-        let result = result
-            + self
-                .get_balance_to_withdraw(eth_account_id, self.tokens[&token])
-                .await;
-        result
+        }
     }
 
     pub async fn get_balance_to_withdraw(
@@ -1284,6 +2154,27 @@ impl TestSetup {
             .expect("Block number query")
     }
 
+    /// Advances the L1 chain by mining `n` blocks.
+    ///
+    /// Requires a dev node that supports the `evm_mine` RPC method.
+    pub async fn advance_eth_blocks(&self, n: u64) {
+        self.commit_account
+            .mine_blocks(n)
+            .await
+            .expect("Failed to mine L1 blocks");
+    }
+
+    /// Waits until the mined L1 transaction has `confirmations` confirmations.
+    /// The missing blocks are mined, so the waiting doesn't depend on the node producing blocks.
+    ///
+    /// Requires a dev node that supports the `evm_mine` RPC method.
+    pub async fn await_eth_confirmations(&self, receipt: &TransactionReceipt, confirmations: u64) {
+        self.commit_account
+            .await_confirmations(receipt, confirmations)
+            .await
+            .expect("Failed to wait for tx confirmations");
+    }
+
     pub fn get_tokens(&self) -> Vec<Token> {
         self.tokens.iter().map(|(id, _)| Token(*id)).collect()
     }
@@ -1350,4 +2241,10 @@ impl TestSetup {
         )
         .expect("Failed to generate exit proof")
     }
+
+    /// Checks the exit proof against the exit circuit verification key, so that
+    /// a broken proof fails the test before the `exit` transaction is sent.
+    pub fn verify_exit_proof(&self, proof: &EncodedSingleProof) -> Result<(), anyhow::Error> {
+        zksync_prover_utils::verify_exit_proof(proof)
+    }
 }
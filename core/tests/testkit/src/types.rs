@@ -1,10 +1,11 @@
 //! Common primitives used within testkit.
 use num::BigUint;
 use std::collections::HashMap;
+use std::fmt;
 use web3::types::TransactionReceipt;
 use zksync_config::ZkSyncConfig;
 use zksync_types::block::Block;
-use zksync_types::TokenId;
+use zksync_types::{TokenId, ZkSyncOp};
 
 #[derive(Debug, Clone)]
 pub struct TestkitConfig {
@@ -45,6 +46,11 @@ pub struct BlockExecutionResult {
     pub withdrawals_result: TransactionReceipt,
     pub pending_withdrawals_result: Option<TransactionReceipt>,
     pub block_size_chunks: usize,
+    /// Total amounts deposited in the block, per token.
+    pub deposits: HashMap<TokenId, BigUint>,
+    /// Total amounts withdrawn to L1 in the block (including full and forced exits), per token.
+    /// Each withdrawn NFT counts as a single unit of its token.
+    pub withdrawals: HashMap<TokenId, BigUint>,
 }
 
 impl BlockExecutionResult {
@@ -56,6 +62,7 @@ impl BlockExecutionResult {
         pending_withdrawals_result: Option<TransactionReceipt>,
         block_size_chunks: usize,
     ) -> Self {
+        let (deposits, withdrawals) = Self::l1_flows(&block);
         Self {
             block,
             commit_result,
@@ -63,10 +70,55 @@ impl BlockExecutionResult {
             withdrawals_result,
             pending_withdrawals_result,
             block_size_chunks,
+            deposits,
+            withdrawals,
         }
     }
+
+    /// Sums up amounts of successful deposits and withdrawals in the block.
+    fn l1_flows(block: &Block) -> (HashMap<TokenId, BigUint>, HashMap<TokenId, BigUint>) {
+        let mut deposits: HashMap<TokenId, BigUint> = HashMap::new();
+        let mut withdrawals: HashMap<TokenId, BigUint> = HashMap::new();
+        for op in block.successful_operations() {
+            match op {
+                ZkSyncOp::Deposit(op) => {
+                    *deposits.entry(op.priority_op.token).or_default() += &op.priority_op.amount;
+                }
+                ZkSyncOp::Withdraw(op) => {
+                    *withdrawals.entry(op.tx.token).or_default() += &op.tx.amount;
+                }
+                ZkSyncOp::FullExit(op) => {
+                    if let Some(amount) = &op.withdraw_amount {
+                        *withdrawals.entry(op.priority_op.token).or_default() += &amount.0;
+                    }
+                }
+                ZkSyncOp::WithdrawNFT(op) => {
+                    *withdrawals.entry(op.tx.token).or_default() += 1u32;
+                }
+                ZkSyncOp::ForcedExit(op) => {
+                    if let Some(amount) = &op.withdraw_amount {
+                        *withdrawals.entry(op.tx.token).or_default() += &amount.0;
+                    }
+                }
+                _ => {}
+            }
+        }
+        (deposits, withdrawals)
+    }
+}
+
+/// Reason of the transaction rejection reported by the state keeper.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TxFailReason(pub String);
+
+impl fmt::Display for TxFailReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
 }
 
+impl std::error::Error for TxFailReason {}
+
 // Struct used to keep expected balance changes after transactions execution.
 #[derive(Default, Debug)]
 pub struct ExpectedAccountState {
@@ -75,4 +127,19 @@ pub struct ExpectedAccountState {
 
     // Amount of withdraw operations performed in block.
     pub withdraw_ops: usize,
+
+    // Funds that entered the tracked zkSync accounts from L1 in block.
+    pub inflow: HashMap<TokenId, BigUint>,
+    // Funds that left the tracked zkSync accounts in block, either to L1 or to untracked accounts.
+    pub outflow: HashMap<TokenId, BigUint>,
+}
+
+impl ExpectedAccountState {
+    pub fn add_inflow(&mut self, token: TokenId, amount: &BigUint) {
+        *self.inflow.entry(token).or_default() += amount;
+    }
+
+    pub fn add_outflow(&mut self, token: TokenId, amount: &BigUint) {
+        *self.outflow.entry(token).or_default() += amount;
+    }
 }
@@ -1,6 +1,7 @@
 pub use jsonrpc_core::types::response::Failure as RpcFailure;
 use thiserror::Error;
 use zksync_eth_signer::error::SignerError;
+use zksync_types::BlockNumber;
 
 #[derive(Debug, Error, PartialEq)]
 pub enum ClientError {
@@ -24,6 +25,8 @@ pub enum ClientError {
 
     #[error("Operation timeout")]
     OperationTimeout,
+    #[error("Block {0} was not verified before the timeout")]
+    BlockVerificationTimeout(BlockNumber),
     #[error("Polling interval is too small")]
     PollingIntervalIsTooSmall,
 
@@ -2,7 +2,7 @@
 // from: https://github.com/matter-labs/zksync-dev/blob/dev/core/loadtest/src/rpc_client.rs
 
 // Built-in imports
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 // External uses
 use async_trait::async_trait;
@@ -13,7 +13,7 @@ use num::BigUint;
 use zksync_types::{
     network::Network,
     tx::{PackedEthSignature, TxHash, ZkSyncTx},
-    Address, TokenLike, TxFeeTypes,
+    Address, BlockNumber, TokenLike, TxFeeTypes,
 };
 
 // Local uses
@@ -96,6 +96,41 @@ pub trait Provider {
     fn network(&self) -> Network;
 }
 
+/// Interval between the server status requests in `RpcProvider::wait_for_block_verified`.
+const BLOCK_POLLING_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Configuration of the retries of `RpcProvider` requests.
+///
+/// Only recoverable errors (network errors and internal server errors) are retried,
+/// application errors (e.g. insufficient fee) are returned right away.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryConfig {
+    /// Initial backoff delay. It is doubled before each retry, so the first retry happens after twice this value.
+    pub initial_delay: Duration,
+    /// Request is not retried anymore once the backoff delay reaches this value.
+    pub max_delay: Duration,
+    /// Maximum amount of retries of a single request. If not set, retries are only limited by `max_delay`.
+    pub max_retries: Option<usize>,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            initial_delay: Duration::from_millis(50),
+            max_delay: Duration::from_secs(30),
+            max_retries: None,
+        }
+    }
+}
+
+impl RetryConfig {
+    /// Checks whether the request can be retried once more, given the current backoff delay
+    /// and the amount of already performed retries.
+    fn can_retry(&self, delay: Duration, retries: usize) -> bool {
+        delay < self.max_delay && self.max_retries.map_or(true, |max| retries < max)
+    }
+}
+
 /// `RpcProvider` is capable of interacting with the ZKSync node via its
 /// JSON RPC interface.
 #[derive(Debug, Clone)]
@@ -103,6 +138,7 @@ pub struct RpcProvider {
     rpc_addr: String,
     client: reqwest::Client,
     network: Network,
+    retry_config: RetryConfig,
 }
 
 #[async_trait]
@@ -193,6 +229,7 @@ impl RpcProvider {
             rpc_addr: get_rpc_addr(network).into(),
             client: reqwest::Client::new(),
             network,
+            retry_config: RetryConfig::default(),
         }
     }
 
@@ -202,6 +239,7 @@ impl RpcProvider {
             rpc_addr: rpc_addr.into(),
             client: reqwest::Client::new(),
             network: Network::Unknown,
+            retry_config: RetryConfig::default(),
         }
     }
 
@@ -211,6 +249,22 @@ impl RpcProvider {
             rpc_addr: rpc_addr.into(),
             client: reqwest::Client::new(),
             network,
+            retry_config: RetryConfig::default(),
+        }
+    }
+
+    /// Creates a new `Provider` object connected to a custom address and the desired zkSync network,
+    /// which retries failed requests according to the provided config.
+    pub fn with_retry_config(
+        rpc_addr: impl Into<String>,
+        network: Network,
+        retry_config: RetryConfig,
+    ) -> Self {
+        Self {
+            rpc_addr: rpc_addr.into(),
+            client: reqwest::Client::new(),
+            network,
+            retry_config,
         }
     }
 
@@ -225,6 +279,16 @@ impl RpcProvider {
         self.send_and_deserialize(&msg).await
     }
 
+    /// Requests and returns information about several ZKSync accounts in one call.
+    /// Results are returned in the same order as the provided addresses.
+    pub async fn accounts_info(
+        &self,
+        addresses: Vec<Address>,
+    ) -> Result<Vec<AccountInfo>, ClientError> {
+        let msg = JsonRpcRequest::accounts_info(addresses);
+        self.send_and_deserialize(&msg).await
+    }
+
     /// Requests and returns information about an Ethereum operation given its `serial_id`.
     pub async fn ethop_info(&self, serial_id: u32) -> Result<EthOpInfo, ClientError> {
         let msg = JsonRpcRequest::ethop_info(serial_id);
@@ -240,6 +304,34 @@ impl RpcProvider {
         self.send_and_deserialize(&msg).await
     }
 
+    /// Requests and returns the current server status.
+    pub async fn status(&self) -> Result<StatusInfo, ClientError> {
+        let msg = JsonRpcRequest::status();
+        self.send_and_deserialize(&msg).await
+    }
+
+    /// Polls the server status until the block with the given number is verified.
+    /// Returns `ClientError::BlockVerificationTimeout` if it doesn't happen within `timeout`.
+    pub async fn wait_for_block_verified(
+        &self,
+        block_number: BlockNumber,
+        timeout: Duration,
+    ) -> Result<(), ClientError> {
+        let mut timer = tokio::time::interval(BLOCK_POLLING_INTERVAL);
+        let start = Instant::now();
+
+        loop {
+            timer.tick().await;
+
+            if self.status().await?.last_verified_block >= block_number {
+                return Ok(());
+            }
+            if start.elapsed() >= timeout {
+                return Err(ClientError::BlockVerificationTimeout(block_number));
+            }
+        }
+    }
+
     /// Performs a POST query to the JSON RPC endpoint,
     /// and decodes the response, returning the decoded `serde_json::Value`.
     /// `Ok` is returned only for successful calls, for any kind of error
@@ -248,8 +340,8 @@ impl RpcProvider {
     async fn post(&self, message: impl serde::Serialize) -> ResponseResult<serde_json::Value> {
         // Repeat requests with exponential backoff until an ok response is received to avoid
         // network and internal errors impact.
-        const MAX_DURATION: Duration = Duration::from_secs(30);
-        let mut delay = Duration::from_millis(50);
+        let mut delay = self.retry_config.initial_delay;
+        let mut retries = 0;
         loop {
             let result = self.post_raw(&message).await;
 
@@ -267,8 +359,9 @@ impl RpcProvider {
                 _ => false,
             };
 
-            if should_retry && delay < MAX_DURATION {
+            if should_retry && self.retry_config.can_retry(delay, retries) {
                 delay *= 2;
+                retries += 1;
                 tokio::time::sleep(delay).await;
                 continue;
             }
@@ -366,6 +459,10 @@ mod messages {
             Self::create("account_info", json_values![address])
         }
 
+        pub fn accounts_info(addresses: Vec<Address>) -> Self {
+            Self::create("accounts_info", json_values![addresses])
+        }
+
         pub fn submit_tx(tx: ZkSyncTx, eth_signature: Option<PackedEthSignature>) -> Self {
             let params = json_values![tx, eth_signature.map(TxEthSignature::EthereumSignature)];
             Self::create("tx_submit", params)
@@ -399,6 +496,10 @@ mod messages {
             Self::create("tx_info", json_values![tx_hash])
         }
 
+        pub fn status() -> Self {
+            Self::create("status", json_values![])
+        }
+
         pub fn tokens() -> Self {
             Self::create("tokens", json_values![])
         }
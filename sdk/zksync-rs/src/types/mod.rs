@@ -3,7 +3,7 @@ use std::collections::HashMap;
 use num::BigUint;
 use serde::{Deserialize, Serialize};
 
-use zksync_types::{AccountId, Address, Nonce, PubKeyHash, Token, TokenId, H256};
+use zksync_types::{AccountId, Address, BlockNumber, Nonce, PubKeyHash, Token, TokenId, H256};
 use zksync_utils::{BigUintSerdeAsRadix10Str, BigUintSerdeWrapper};
 
 pub type Tokens = HashMap<String, Token>;
@@ -65,6 +65,15 @@ pub struct BlockInfo {
     pub verified: bool,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct StatusInfo {
+    pub last_committed_block: BlockNumber,
+    pub last_verified_block: BlockNumber,
+    pub mempool_size: u32,
+    pub network_synced: bool,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct TransactionInfo {
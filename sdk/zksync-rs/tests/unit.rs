@@ -772,3 +772,54 @@ mod wallet_tests {
         assert_eq!(eth_provider.contract_address().as_bytes(), expected_address);
     }
 }
+
+mod provider_tests {
+    use std::{
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        time::Duration,
+    };
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+    use zksync::{
+        provider::{Provider, RetryConfig},
+        Network, RpcProvider,
+    };
+
+    /// Checks that a request failing with a recoverable error is retried exactly `max_retries` times.
+    #[tokio::test]
+    async fn test_provider_stops_after_max_retries() {
+        const MAX_RETRIES: usize = 3;
+        const ERROR_RESPONSE: &[u8] =
+            b"HTTP/1.1 503 Service Unavailable\r\ncontent-length: 0\r\nconnection: close\r\n\r\n";
+
+        // Server that responds to every request with an error, counting the received requests.
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let rpc_addr = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(AtomicUsize::new(0));
+        let requests_counter = requests.clone();
+        tokio::spawn(async move {
+            loop {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                requests_counter.fetch_add(1, Ordering::SeqCst);
+                let mut buf = [0u8; 4096];
+                let _ = socket.read(&mut buf).await;
+                let _ = socket.write_all(ERROR_RESPONSE).await;
+            }
+        });
+
+        let retry_config = RetryConfig {
+            initial_delay: Duration::from_millis(1),
+            max_delay: Duration::from_secs(60),
+            max_retries: Some(MAX_RETRIES),
+        };
+        let provider = RpcProvider::with_retry_config(rpc_addr, Network::Localhost, retry_config);
+
+        assert!(provider.contract_address().await.is_err());
+        assert_eq!(requests.load(Ordering::SeqCst), MAX_RETRIES + 1);
+    }
+}